@@ -51,14 +51,6 @@ const FREEZE_PERIOD_IN_SECONDS: u32 = 3 * 24 * 60 * 60;
 const SIX_MONTHS_IN_SECONDS: u32 =
     (6.0 * (365.25 / 12.0) * 24.0 * 60.0 * 60.0) as u32;
 
-fn compute_by_proposal_key(proposal_name: Name, voter: AccountName) -> u128 {
-    u128::from(proposal_name.as_u64()) << 64 | u128::from(voter.as_u64())
-}
-
-fn compute_by_voter_key(proposal_name: Name, voter: AccountName) -> u128 {
-    u128::from(voter.as_u64()) << 64 | u128::from(voter.as_u64())
-}
-
 #[eosio::table("proposal")]
 pub struct ProposalRow {
     #[eosio(primary_key)]
@@ -102,8 +94,9 @@ impl Table for VoteRow {
 
     fn secondary_keys(row: &Self::Row) -> SecondaryKeys {
         (
-            compute_by_proposal_key(row.proposal_name, row.voter),
-            compute_by_voter_key(row.proposal_name, row.voter),
+            (row.proposal_name, row.voter).to_u128(),
+            // the voter in both halves is the key deployed contracts store
+            (row.voter, row.voter).to_u128(),
         )
             .into()
     }
@@ -34,14 +34,18 @@ impl Table for CurrencyStats {
 }
 
 #[eosio::action]
-fn create(issuer: AccountName, max_supply: Asset) {
+fn create(
+    issuer: AccountName,
+    #[eosio(
+        valid = "invalid symbol name",
+        positive = "max-supply must be positive"
+    )]
+    max_supply: Asset,
+) {
     let code = current_receiver();
     require_auth(code);
 
     let symbol = max_supply.symbol;
-    assert!(symbol.is_valid(), "invalid symbol name");
-    assert!(max_supply.is_valid(), "invalid supply");
-    assert!(max_supply.amount > 0, "max-supply must be positive");
 
     let symbol_code = symbol.code();
     let stats_table = CurrencyStats::table(code, symbol_code);
@@ -60,10 +64,16 @@ fn create(issuer: AccountName, max_supply: Asset) {
 }
 
 #[eosio::action]
-fn issue(to: AccountName, quantity: Asset, memo: String) {
+fn issue(
+    to: AccountName,
+    #[eosio(
+        valid = "invalid symbol name",
+        positive = "must issue positive quantity"
+    )]
+    quantity: Asset,
+    #[eosio(max_len = 256)] memo: String,
+) {
     let symbol = quantity.symbol;
-    assert!(symbol.is_valid(), "invalid symbol name");
-    assert!(memo.len() <= 256, "memo has more than 256 bytes");
 
     let code = current_receiver();
     let symbol_code = symbol.code();
@@ -78,8 +88,6 @@ fn issue(to: AccountName, quantity: Asset, memo: String) {
         "tokens can only be issued to issuer account",
     );
     require_auth(st.issuer);
-    assert!(quantity.is_valid(), "invalid quantity");
-    assert!(quantity.amount > 0, "must issue positive quantity");
     assert!(
         quantity.symbol == st.supply.symbol,
         "symbol precision mismatch",
@@ -112,10 +120,15 @@ fn issue(to: AccountName, quantity: Asset, memo: String) {
 }
 
 #[eosio::action]
-fn retire(quantity: Asset, memo: String) {
+fn retire(
+    #[eosio(
+        valid = "invalid symbol name",
+        positive = "must retire positive quantity"
+    )]
+    quantity: Asset,
+    #[eosio(max_len = 256)] memo: String,
+) {
     let symbol = quantity.symbol;
-    assert!(symbol.is_valid(), "invalid symbol name");
-    assert!(memo.len() <= 256, "memo has more than 256 bytes");
 
     let code = current_receiver();
     let symbol_code = symbol.code();
@@ -126,8 +139,6 @@ fn retire(quantity: Asset, memo: String) {
 
     let mut st = cursor.get().expect("error reading stats table");
     require_auth(st.issuer);
-    assert!(quantity.is_valid(), "invalid quantity");
-    assert!(quantity.amount > 0, "must retire positive quantity");
     assert!(symbol == st.supply.symbol, "symbol precision mismatch");
 
     st.supply -= quantity;
@@ -135,10 +146,18 @@ fn retire(quantity: Asset, memo: String) {
     sub_balance(st.issuer, quantity);
 }
 
-#[eosio::action]
-fn transfer(from: AccountName, to: AccountName, quantity: Asset, memo: String) {
+#[eosio::action(auth = "from")]
+fn transfer(
+    from: AccountName,
+    to: AccountName,
+    #[eosio(
+        valid = "invalid quantity",
+        positive = "must transfer positive quantity"
+    )]
+    quantity: Asset,
+    #[eosio(max_len = 256)] memo: String,
+) {
     assert!(from != to, "cannot transfer to self");
-    require_auth(from);
     assert!(is_account(to), "to account does not exist");
 
     let code = current_receiver();
@@ -152,13 +171,10 @@ fn transfer(from: AccountName, to: AccountName, quantity: Asset, memo: String) {
     require_recipient(from);
     require_recipient(to);
 
-    assert!(quantity.is_valid(), "invalid quantity");
-    assert!(quantity.amount > 0, "must transfer positive quantity");
     assert!(
         quantity.symbol == st.supply.symbol,
         "symbol precision mismatch",
     );
-    assert!(memo.len() <= 256, "memo has more than 256 bytes");
 
     let payer = if has_auth(to) { to } else { from };
 
@@ -172,30 +188,28 @@ fn sub_balance(owner: AccountName, value: Asset) {
     let cursor = table
         .find(value.symbol.code())
         .expect("no balance object found");
-    let mut from = cursor.get().expect("read");
-    assert!(from.balance.amount >= value.amount, "overdrawn balance");
-
-    from.balance -= value;
-    cursor.modify(Payer::Same, from).expect("write");
+    cursor
+        .modify_with(Payer::Same, |from| {
+            assert!(from.balance.amount >= value.amount, "overdrawn balance");
+            from.balance -= value;
+        })
+        .expect("write");
 }
 
 fn add_balance(owner: AccountName, value: Asset, ram_payer: AccountName) {
     let code = current_receiver();
     let accounts_table = Account::table(code, owner);
-    let cursor = accounts_table.find(value.symbol.code());
-    match cursor {
-        Some(cursor) => {
-            let mut account = cursor.get().expect("read");
-            account.balance += value;
-            cursor
-                .modify(Payer::New(ram_payer), &account)
-                .expect("write");
-        }
-        None => {
-            let account = Account { balance: value };
-            accounts_table.emplace(ram_payer, &account).expect("write");
-        }
-    }
+    let account = Account {
+        balance: Asset {
+            amount: 0,
+            symbol: value.symbol,
+        },
+    };
+    accounts_table
+        .upsert(value.symbol.code(), ram_payer, account, |account| {
+            account.balance += value
+        })
+        .expect("write");
 }
 
 #[eosio::action]
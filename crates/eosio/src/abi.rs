@@ -1,4 +1,8 @@
-use alloc::{string::String, vec::Vec};
+use crate::{action::ActionFn, contract_error::ContractError, table::KvTable};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 
 #[derive(Debug, PartialEq)]
 pub struct Abi {
@@ -10,6 +14,8 @@ pub struct Abi {
     pub ricardian_clauses: Vec<AbiRicardianClause>,
     pub error_messages: Vec<AbiErrorMessage>,
     pub abi_extensions: Vec<AbiExtension>,
+    pub kv_tables: Vec<AbiKvTable>,
+    pub action_results: Vec<AbiActionResult>,
     // TODO variants: Vec<Variant>,
 }
 
@@ -39,6 +45,21 @@ pub struct AbiAction {
     pub ricardian_contract: String,
 }
 
+impl AbiAction {
+    /// Describes the action `A`, with its Ricardian contract. The type of
+    /// the action data is named after the action.
+    #[must_use]
+    #[inline]
+    pub fn new<A: ActionFn>() -> Self {
+        let name = A::NAME.to_string();
+        Self {
+            type_: name.clone(),
+            name,
+            ricardian_contract: A::RICARDIAN_CONTRACT.into(),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct AbiTable {
     pub name: String,
@@ -48,18 +69,112 @@ pub struct AbiTable {
     pub type_: String,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub struct AbiKvTable {
+    pub name: String,
+    pub type_: String,
+    pub primary_index: AbiKvIndex,
+    pub secondary_indices: Vec<AbiKvIndex>,
+}
+
+impl AbiKvTable {
+    /// Describes the key-value table `T` and its indexes.
+    #[must_use]
+    #[inline]
+    pub fn new<T: KvTable>() -> Self {
+        let secondary_indices = T::SECONDARY_INDEXES
+            .iter()
+            .zip(T::SECONDARY_ABI_TYPES)
+            .map(|(name, type_)| AbiKvIndex {
+                name: name.to_string(),
+                type_: (*type_).into(),
+            })
+            .collect();
+        Self {
+            name: T::NAME.to_string(),
+            type_: T::ROW_ABI_TYPE.into(),
+            primary_index: AbiKvIndex {
+                name: T::PRIMARY_INDEX.to_string(),
+                type_: T::PRIMARY_ABI_TYPE.into(),
+            },
+            secondary_indices,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AbiKvIndex {
+    pub name: String,
+    pub type_: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AbiActionResult {
+    pub name: String,
+    pub result_type: String,
+}
+
+impl AbiActionResult {
+    /// Describes the return value of the action `A`, if it returns one.
+    #[must_use]
+    #[inline]
+    pub fn new<A: ActionFn>() -> Option<Self> {
+        if A::OUTPUT_ABI_TYPE.is_empty() {
+            return None;
+        }
+        Some(Self {
+            name: A::NAME.to_string(),
+            result_type: A::OUTPUT_ABI_TYPE.into(),
+        })
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct AbiRicardianClause {
     pub id: String,
     pub body: String,
 }
 
+/// A Ricardian clause of a contract, declared with `ricardian_clauses!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RicardianClause {
+    /// The ID of the clause
+    pub id: &'static str,
+    /// The markdown of the clause
+    pub body: &'static str,
+}
+
+impl From<RicardianClause> for AbiRicardianClause {
+    #[inline]
+    fn from(clause: RicardianClause) -> Self {
+        Self {
+            id: clause.id.into(),
+            body: clause.body.into(),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct AbiErrorMessage {
     pub error_code: u64,
     pub error_msg: String,
 }
 
+impl AbiErrorMessage {
+    /// Describes every error of `E`.
+    #[must_use]
+    #[inline]
+    pub fn all<E: ContractError>() -> Vec<Self> {
+        E::ERRORS
+            .iter()
+            .map(|&(error_code, error_msg)| Self {
+                error_code,
+                error_msg: error_msg.into(),
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct AbiExtension {
     pub type_: u16,
@@ -30,8 +30,21 @@ pub struct Action<T> {
 pub trait ActionFn: Read + Write + NumBytes + Clone {
     /// TODO docs
     const NAME: ActionName;
+    /// The names of the arguments whose authority the action requires, set
+    /// with `#[eosio::action(auth = "arg")]`. Used to describe the action in
+    /// ABIs and Ricardian contracts.
+    const REQUIRED_AUTH: &'static [&'static str] = &[];
+    /// The Ricardian contract of the action, embedded with
+    /// `#[eosio::action(ricardian = "path/to/action.md")]`.
+    const RICARDIAN_CONTRACT: &'static str = "";
+    /// The ABI type name of `Output`, or an empty string for actions that
+    /// don't return a value.
+    const OUTPUT_ABI_TYPE: &'static str = "";
+    /// The type returned by the action. Anything other than `()` is packed
+    /// and set as the action return value.
+    type Output: Write + NumBytes;
     /// TODO docs.
-    fn call(self);
+    fn call(self) -> Self::Output;
     /// TODO docs
     #[inline]
     fn to_action(
@@ -51,6 +64,52 @@ pub trait ActionFn: Read + Write + NumBytes + Clone {
     }
 }
 
+/// A handler for notifications of actions sent to other contracts, created
+/// with `#[eosio::on_notify]`.
+///
+/// The first argument of the handler is the contract the action was sent to,
+/// and the rest are the action data. Use `*` to accept notifications from any
+/// contract, and check the first receiver yourself.
+///
+/// ```
+/// use eosio::{n, AccountName, Asset, NotifyFn};
+///
+/// #[eosio::on_notify("eosio.token::transfer")]
+/// fn on_transfer(
+///     first_receiver: AccountName,
+///     from: AccountName,
+///     to: AccountName,
+///     quantity: Asset,
+///     memo: String,
+/// ) {
+///     // credit the deposit
+/// }
+///
+/// #[eosio::on_notify("*::transfer")]
+/// fn on_any_transfer(
+///     first_receiver: AccountName,
+///     from: AccountName,
+///     to: AccountName,
+///     quantity: Asset,
+///     memo: String,
+/// ) {
+/// }
+///
+/// assert_eq!(OnTransfer::CODE, Some(AccountName::new(n!("eosio.token"))));
+/// assert_eq!(OnAnyTransfer::CODE, None);
+/// assert_eq!(OnTransfer::NAME.as_u64(), n!("transfer"));
+/// ```
+pub trait NotifyFn: Read + Write + NumBytes + Clone {
+    /// The contract the action must be sent to, or `None` to handle
+    /// notifications from any contract.
+    const CODE: Option<AccountName>;
+    /// The name of the action
+    const NAME: ActionName;
+    /// Handles a notification. `first_receiver` is the contract the action
+    /// was sent to, which the dispatcher has already checked against `CODE`.
+    fn call(self, first_receiver: AccountName);
+}
+
 /// A permission
 #[derive(
     Debug,
@@ -108,6 +167,17 @@ impl fmt::Display for ParsePermissionLevelError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ParsePermissionLevelError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Format => None,
+            Self::Actor(err) | Self::Permission(err) => Some(err),
+        }
+    }
+}
+
 impl FromStr for PermissionLevel {
     type Err = ParsePermissionLevelError;
 
@@ -99,6 +99,9 @@ impl fmt::Display for ParseAssetError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ParseAssetError {}
+
 impl From<ParseSymbolError> for ParseAssetError {
     #[inline]
     fn from(value: ParseSymbolError) -> Self {
@@ -205,6 +208,9 @@ impl fmt::Display for AssetOpError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for AssetOpError {}
+
 /// TODO docs
 #[derive(Debug, Clone, Copy)]
 pub enum AssetDivOpError {
@@ -228,6 +234,9 @@ impl fmt::Display for AssetDivOpError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for AssetDivOpError {}
+
 macro_rules! impl_op {
     ($($checked_trait:ident, $checked_error:ident, $checked_fn:ident, $op_trait:ident, $op_fn:ident, $assign_trait:ident, $assign_fn:ident)*) => ($(
         impl $checked_trait<i64> for Asset {
@@ -0,0 +1,175 @@
+//! <https://github.com/EOSIO/eos/blob/1418543149b7caf8fc69a23621e3db7f3c6d18ad/libraries/chain/include/eosio/chain/block_header.hpp>
+use crate::{
+    bytes::{NumBytes, Read, ReadError, Write, WriteError},
+    AccountName, BlockTimestamp, Checksum256, ProducerAuthoritySchedule,
+    ProducerSchedule, Signature,
+};
+use alloc::vec::Vec;
+
+/// An extension attached to a block header, identified by its type.
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq, Eq)]
+#[eosio(crate_path = "crate::bytes")]
+pub struct BlockHeaderExtension {
+    /// The extension type
+    pub id: u16,
+    /// The serialized extension
+    pub data: Vec<u8>,
+}
+
+impl BlockHeaderExtension {
+    /// Extension listing the protocol features activated by a block.
+    pub const PROTOCOL_FEATURE_ACTIVATION: u16 = 0;
+    /// Extension proposing a new producer authority schedule.
+    pub const PRODUCER_SCHEDULE_CHANGE: u16 = 1;
+}
+
+/// The header of a block, without the producer's signature.
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq)]
+#[eosio(crate_path = "crate::bytes")]
+pub struct BlockHeader {
+    /// The time slot this block was produced in
+    pub timestamp: BlockTimestamp,
+    /// The producer that created this block
+    pub producer: AccountName,
+    /// The number of prior blocks this producer is confirming
+    pub confirmed: u16,
+    /// The ID of the previous block
+    pub previous: Checksum256,
+    /// Merkle root of the transaction receipts in this block
+    pub transaction_mroot: Checksum256,
+    /// Merkle root of the action receipts in this block
+    pub action_mroot: Checksum256,
+    /// The version of the producer schedule used to produce this block
+    pub schedule_version: u32,
+    /// A new legacy producer schedule proposed by this block
+    pub new_producers: Option<ProducerSchedule>,
+    /// Additional data, such as producer schedule changes
+    pub header_extensions: Vec<BlockHeaderExtension>,
+}
+
+impl BlockHeader {
+    /// Returns the block number encoded in the first 4 bytes of a block ID.
+    #[inline]
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn num_from_id(id: &Checksum256) -> u32 {
+        (id.words()[0] >> 96) as u32
+    }
+
+    /// Returns the number of this block.
+    #[inline]
+    #[must_use]
+    pub const fn block_num(&self) -> u32 {
+        Self::num_from_id(&self.previous).wrapping_add(1)
+    }
+
+    /// Returns the SHA256 digest of the serialized header.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the header could not be serialized.
+    #[inline]
+    pub fn digest(&self) -> Result<Checksum256, WriteError> {
+        self.pack().map(Checksum256::hash)
+    }
+
+    /// Returns the ID of this block, which is its digest with the first 4
+    /// bytes replaced by the block number.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the header could not be serialized.
+    #[inline]
+    pub fn id(&self) -> Result<Checksum256, WriteError> {
+        let [high, low] = self.digest()?.words();
+        let high = (high & !(u128::from(u32::MAX) << 96))
+            | (u128::from(self.block_num()) << 96);
+        Ok(Checksum256::from_words([high, low]))
+    }
+
+    /// Returns the producer authority schedule proposed by this block, either
+    /// through `new_producers` or a schedule change extension. Returns
+    /// `Ok(None)` if no schedule is proposed.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if a schedule change extension could not be read.
+    pub fn new_producer_schedule(
+        &self,
+    ) -> Result<Option<ProducerAuthoritySchedule>, ReadError> {
+        if let Some(schedule) = &self.new_producers {
+            return Ok(Some(schedule.clone().into()));
+        }
+        self.header_extensions
+            .iter()
+            .find(|ext| {
+                ext.id == BlockHeaderExtension::PRODUCER_SCHEDULE_CHANGE
+            })
+            .map(|ext| ProducerAuthoritySchedule::unpack(&ext.data))
+            .transpose()
+    }
+}
+
+/// A block header signed by its producer.
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq)]
+#[eosio(crate_path = "crate::bytes")]
+pub struct SignedBlockHeader {
+    /// The signed header
+    pub header: BlockHeader,
+    /// Signature of the producer over the header's signing digest
+    pub producer_signature: Signature,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockHeader, BlockHeaderExtension};
+    use crate::{
+        AccountName, Checksum256, ProducerAuthoritySchedule, ProducerKey,
+        ProducerSchedule, PublicKey, Write,
+    };
+
+    #[test]
+    fn block_num_and_id() {
+        let previous = Checksum256::from_words([0x0000_0041_u128 << 96, 7]);
+        let header = BlockHeader {
+            previous,
+            ..BlockHeader::default()
+        };
+        assert_eq!(BlockHeader::num_from_id(&previous), 0x41);
+        assert_eq!(header.block_num(), 0x42);
+
+        let id = header.id().unwrap();
+        let digest = header.digest().unwrap();
+        assert_eq!(BlockHeader::num_from_id(&id), 0x42);
+        assert_eq!(id.to_bytes().get(..4), Some(&[0, 0, 0, 0x42][..]));
+        assert_eq!(id.to_bytes().get(4..), digest.to_bytes().get(4..));
+    }
+
+    #[test]
+    fn new_producer_schedule() {
+        let legacy = ProducerSchedule {
+            version: 2,
+            producers: vec![ProducerKey {
+                producer_name: AccountName::new(5),
+                block_signing_key: PublicKey::default(),
+            }],
+        };
+        let schedule = ProducerAuthoritySchedule::from(legacy.clone());
+
+        let mut header = BlockHeader::default();
+        assert_eq!(header.new_producer_schedule().unwrap(), None);
+
+        header.header_extensions.push(BlockHeaderExtension {
+            id: BlockHeaderExtension::PRODUCER_SCHEDULE_CHANGE,
+            data: schedule.pack().unwrap(),
+        });
+        assert_eq!(
+            header.new_producer_schedule().unwrap(),
+            Some(schedule.clone())
+        );
+
+        header.header_extensions.clear();
+        header.new_producers = Some(legacy);
+        assert_eq!(header.new_producer_schedule().unwrap(), Some(schedule));
+    }
+}
@@ -0,0 +1,770 @@
+//! <https://github.com/EOSIO/eos/blob/1418543149b7caf8fc69a23621e3db7f3c6d18ad/libraries/chain/block_header_state.cpp>
+use crate::{
+    bytes::{NumBytes, Read, ReadError, Write, WriteError},
+    BlockHeader, BlockHeaderExtension, Checksum256, IncrementalMerkle,
+    ProducerAuthoritySchedule, PublicKey, Signature, SignedBlockHeader,
+};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A producer schedule that has been proposed but is not yet active.
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq)]
+#[eosio(crate_path = "crate::bytes")]
+pub struct PendingSchedule {
+    /// The block number that proposed the schedule
+    pub schedule_lib_num: u32,
+    /// Digest of the serialized schedule, as it was proposed
+    pub schedule_hash: Checksum256,
+    /// The proposed schedule
+    pub schedule: ProducerAuthoritySchedule,
+}
+
+/// The state of the chain at a block, as needed to validate the headers of
+/// the blocks that follow it.
+///
+/// Only producer signatures and schedule changes are tracked; confirmations
+/// are not counted, so a pending schedule is promoted as soon as a correctly
+/// signed header claims its version. Forks are handled by keeping the state
+/// of every block that may still be built upon and calling
+/// [`BlockHeaderState::next`] on the parent of each new header.
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq)]
+#[eosio(crate_path = "crate::bytes")]
+pub struct BlockHeaderState {
+    /// The ID of the block
+    pub id: Checksum256,
+    /// The number of the block
+    pub block_num: u32,
+    /// The signed header of the block
+    pub header: SignedBlockHeader,
+    /// The schedule that produced the block
+    pub active_schedule: ProducerAuthoritySchedule,
+    /// The schedule that will become active next, if any producers are set
+    pub pending_schedule: PendingSchedule,
+    /// Merkle tree of the IDs of all blocks before this one
+    pub blockroot_merkle: IncrementalMerkle,
+}
+
+/// Error that can be returned when validating a block header.
+#[derive(Debug, Clone, Copy)]
+pub enum HeaderError {
+    /// The header does not build on the expected block.
+    UnlinkableBlock,
+    /// The header's timestamp is not after its parent's.
+    TimestampNotIncreasing,
+    /// The header claims a schedule version that is neither active nor
+    /// pending.
+    ScheduleVersionMismatch,
+    /// The header was not created by the scheduled producer.
+    WrongProducer,
+    /// The header proposes a schedule that cannot replace the current one.
+    InvalidScheduleChange,
+    /// A signature did not recover to a key of the scheduled producer.
+    InvalidSignature,
+    /// The signatures do not satisfy the producer's signing authority.
+    ThresholdNotMet,
+    /// Failed to read a header extension.
+    Read(ReadError),
+    /// Failed to serialize the header.
+    Write(WriteError),
+}
+
+impl fmt::Display for HeaderError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnlinkableBlock => write!(f, "unlinkable block"),
+            Self::TimestampNotIncreasing => {
+                write!(f, "timestamp is not after the previous block")
+            }
+            Self::ScheduleVersionMismatch => {
+                write!(f, "wrong producer schedule version")
+            }
+            Self::WrongProducer => write!(f, "wrong producer"),
+            Self::InvalidScheduleChange => {
+                write!(f, "invalid producer schedule change")
+            }
+            Self::InvalidSignature => write!(f, "invalid block signature"),
+            Self::ThresholdNotMet => {
+                write!(f, "block signing authority not satisfied")
+            }
+            Self::Read(err) => {
+                f.write_str("failed to read extension: ")?;
+                fmt::Display::fmt(err, f)
+            }
+            Self::Write(err) => {
+                f.write_str("failed to write header: ")?;
+                fmt::Display::fmt(err, f)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HeaderError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read(err) => Some(err),
+            Self::Write(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReadError> for HeaderError {
+    #[inline]
+    fn from(err: ReadError) -> Self {
+        Self::Read(err)
+    }
+}
+
+impl From<WriteError> for HeaderError {
+    #[inline]
+    fn from(err: WriteError) -> Self {
+        Self::Write(err)
+    }
+}
+
+impl BlockHeaderState {
+    /// Returns the digest that producers sign for a header.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the header could not be serialized.
+    pub fn sig_digest(
+        header: &BlockHeader,
+        blockroot_merkle: &IncrementalMerkle,
+        pending_schedule: &PendingSchedule,
+    ) -> Result<Checksum256, WriteError> {
+        let header_bmroot =
+            (header.digest()?, blockroot_merkle.root()).pack()?;
+        let digest = (
+            Checksum256::hash(header_bmroot),
+            pending_schedule.schedule_hash,
+        )
+            .pack()?;
+        Ok(Checksum256::hash(digest))
+    }
+
+    /// Validates a header that builds on this block and returns the state of
+    /// the new block. `recover` returns the public key that created a
+    /// signature over a digest, or `None` if it cannot be recovered.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the header is not a valid successor of this block.
+    #[inline]
+    pub fn next<F>(
+        &self,
+        header: &SignedBlockHeader,
+        recover: F,
+    ) -> Result<Self, HeaderError>
+    where
+        F: Fn(&Checksum256, &Signature) -> Option<PublicKey>,
+    {
+        self.next_with_signatures(header, &[], recover)
+    }
+
+    /// Like [`BlockHeaderState::next`], but also counts signatures from the
+    /// block's additional signatures extension towards the producer's
+    /// signing authority.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the header is not a valid successor of this block.
+    pub fn next_with_signatures<F>(
+        &self,
+        header: &SignedBlockHeader,
+        additional_signatures: &[Signature],
+        recover: F,
+    ) -> Result<Self, HeaderError>
+    where
+        F: Fn(&Checksum256, &Signature) -> Option<PublicKey>,
+    {
+        let SignedBlockHeader {
+            header: unsigned,
+            producer_signature,
+        } = header;
+        if unsigned.previous != self.id {
+            return Err(HeaderError::UnlinkableBlock);
+        }
+        if unsigned.timestamp <= self.header.header.timestamp {
+            return Err(HeaderError::TimestampNotIncreasing);
+        }
+        let block_num = self.block_num.wrapping_add(1);
+
+        let pending = &self.pending_schedule;
+        let was_promoted = !pending.schedule.producers.is_empty()
+            && unsigned.schedule_version == pending.schedule.version;
+        let (active_schedule, mut pending_schedule) = if was_promoted {
+            let promoted = PendingSchedule {
+                schedule_lib_num: pending.schedule_lib_num,
+                schedule_hash: pending.schedule_hash,
+                schedule: ProducerAuthoritySchedule {
+                    version: pending.schedule.version,
+                    producers: Vec::new(),
+                },
+            };
+            (pending.schedule.clone(), promoted)
+        } else {
+            (self.active_schedule.clone(), pending.clone())
+        };
+        if unsigned.schedule_version != active_schedule.version {
+            return Err(HeaderError::ScheduleVersionMismatch);
+        }
+
+        // like nodeos, the block that promotes the pending schedule is still
+        // produced by the parent's active schedule
+        let producer = self
+            .active_schedule
+            .scheduled_producer(unsigned.timestamp)
+            .filter(|p| p.producer_name == unsigned.producer)
+            .ok_or(HeaderError::WrongProducer)?;
+
+        if let Some(schedule) = unsigned.new_producer_schedule()? {
+            let has_extension = unsigned.header_extensions.iter().any(|ext| {
+                ext.id == BlockHeaderExtension::PRODUCER_SCHEDULE_CHANGE
+            });
+            // a block cannot propose a schedule in the same block in which
+            // the pending one was promoted
+            if was_promoted
+                || (unsigned.new_producers.is_some() && has_extension)
+                || Some(schedule.version)
+                    != active_schedule.version.checked_add(1)
+                || !pending_schedule.schedule.producers.is_empty()
+            {
+                return Err(HeaderError::InvalidScheduleChange);
+            }
+            let schedule_hash = match &unsigned.new_producers {
+                Some(legacy) => Checksum256::hash(legacy.pack()?),
+                None => Checksum256::hash(schedule.pack()?),
+            };
+            pending_schedule = PendingSchedule {
+                schedule_lib_num: block_num,
+                schedule_hash,
+                schedule,
+            };
+        }
+
+        let mut blockroot_merkle = self.blockroot_merkle.clone();
+        blockroot_merkle.append(self.id);
+
+        let digest =
+            Self::sig_digest(unsigned, &blockroot_merkle, &pending_schedule)?;
+        let authority = &producer.authority;
+        let mut signed_keys: Vec<PublicKey> = Vec::new();
+        let mut weight = 0_u64;
+        for signature in
+            core::iter::once(producer_signature).chain(additional_signatures)
+        {
+            let key = recover(&digest, signature)
+                .ok_or(HeaderError::InvalidSignature)?;
+            if signed_keys.contains(&key) {
+                return Err(HeaderError::InvalidSignature);
+            }
+            let key_weight = authority
+                .keys
+                .iter()
+                .find(|k| k.key == key)
+                .ok_or(HeaderError::InvalidSignature)?;
+            weight = weight.saturating_add(u64::from(key_weight.weight));
+            signed_keys.push(key);
+        }
+        if weight < u64::from(authority.threshold) {
+            return Err(HeaderError::ThresholdNotMet);
+        }
+
+        Ok(Self {
+            id: unsigned.id()?,
+            block_num,
+            header: header.clone(),
+            active_schedule,
+            pending_schedule,
+            blockroot_merkle,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockHeaderState, HeaderError};
+    use crate::{
+        n, AccountName, BlockHeader, BlockHeaderExtension,
+        BlockSigningAuthority, BlockTimestamp, Checksum256, KeyWeight,
+        ProducerAuthority, ProducerAuthoritySchedule, PublicKey, Signature,
+        SignedBlockHeader, UnsignedInt, Write,
+    };
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+
+    fn key(seed: u8) -> PublicKey {
+        PublicKey {
+            type_: UnsignedInt::from(0_u8),
+            data: [seed; 33],
+        }
+    }
+
+    /// A fake signature scheme: the signature holds the signing key followed
+    /// by the signed digest.
+    fn fake_sign(key: &PublicKey, digest: &Checksum256) -> Signature {
+        let mut data = [0_u8; 65];
+        let (k, d) = data.split_at_mut(33);
+        k.copy_from_slice(&key.data);
+        d.copy_from_slice(&digest.to_bytes());
+        Signature {
+            type_: UnsignedInt::from(0_u8),
+            data,
+        }
+    }
+
+    fn fake_recover(
+        digest: &Checksum256,
+        signature: &Signature,
+    ) -> Option<PublicKey> {
+        let (k, d) = signature.data.split_at(33);
+        if d != &digest.to_bytes()[..] {
+            return None;
+        }
+        let mut data = [0_u8; 33];
+        data.copy_from_slice(k);
+        Some(PublicKey {
+            type_: UnsignedInt::from(0_u8),
+            data,
+        })
+    }
+
+    fn schedule(
+        version: u32,
+        producers: &[(u64, u8)],
+    ) -> ProducerAuthoritySchedule {
+        ProducerAuthoritySchedule {
+            version,
+            producers: producers
+                .iter()
+                .map(|(name, seed)| ProducerAuthority {
+                    producer_name: AccountName::new(*name),
+                    authority: key(*seed).into(),
+                })
+                .collect(),
+        }
+    }
+
+    fn genesis() -> BlockHeaderState {
+        BlockHeaderState {
+            id: Checksum256::from_words([1 << 96, 0]),
+            block_num: 1,
+            active_schedule: schedule(1, &[(1, 1), (2, 2)]),
+            ..BlockHeaderState::default()
+        }
+    }
+
+    fn header(
+        parent: &BlockHeaderState,
+        slot: u32,
+        producer: u64,
+    ) -> BlockHeader {
+        BlockHeader {
+            timestamp: BlockTimestamp::from(slot),
+            producer: AccountName::new(producer),
+            previous: parent.id,
+            schedule_version: parent.active_schedule.version,
+            ..BlockHeader::default()
+        }
+    }
+
+    fn unsigned(header: BlockHeader) -> SignedBlockHeader {
+        SignedBlockHeader {
+            header,
+            producer_signature: Signature::default(),
+        }
+    }
+
+    /// Signs `header` with `keys` using the digest `parent.next` expects.
+    fn sign(
+        parent: &BlockHeaderState,
+        header: BlockHeader,
+        keys: &[PublicKey],
+    ) -> (SignedBlockHeader, Vec<Signature>) {
+        let digest = Cell::new(None);
+        let unsigned = unsigned(header);
+        let _ = parent.next(&unsigned, |d, _| {
+            digest.set(Some(*d));
+            None
+        });
+        let digest = digest.get().unwrap();
+        let mut signatures = keys.iter().map(|k| fake_sign(k, &digest));
+        let signed = SignedBlockHeader {
+            header: unsigned.header,
+            producer_signature: signatures.next().unwrap(),
+        };
+        (signed, signatures.collect())
+    }
+
+    #[test]
+    fn validates_chain() {
+        let genesis = genesis();
+        let (signed, _) = sign(&genesis, header(&genesis, 1, 1), &[key(1)]);
+        let second = genesis.next(&signed, fake_recover).unwrap();
+        assert_eq!(second.block_num, 2);
+        assert_eq!(second.id, signed.header.id().unwrap());
+        assert_eq!(second.blockroot_merkle.root(), genesis.id);
+
+        let (signed, _) = sign(&second, header(&second, 12, 2), &[key(2)]);
+        let third = second.next(&signed, fake_recover).unwrap();
+        assert_eq!(third.block_num, 3);
+        assert_eq!(third.blockroot_merkle.node_count(), 2);
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        let genesis = genesis();
+        let (signed, _) = sign(&genesis, header(&genesis, 1, 1), &[key(1)]);
+
+        let mut unlinkable = signed.clone();
+        unlinkable.header.previous = Checksum256::default();
+        assert!(matches!(
+            genesis.next(&unlinkable, fake_recover),
+            Err(HeaderError::UnlinkableBlock)
+        ));
+
+        let mut tampered = signed.clone();
+        tampered.header.action_mroot = Checksum256::hash("tampered");
+        assert!(matches!(
+            genesis.next(&tampered, fake_recover),
+            Err(HeaderError::InvalidSignature)
+        ));
+
+        let mut wrong_version = signed.clone();
+        wrong_version.header.schedule_version = 2;
+        assert!(matches!(
+            genesis.next(&wrong_version, fake_recover),
+            Err(HeaderError::ScheduleVersionMismatch)
+        ));
+
+        let mut wrong_producer = signed;
+        wrong_producer.header.producer = AccountName::new(2);
+        assert!(matches!(
+            genesis.next(&wrong_producer, fake_recover),
+            Err(HeaderError::WrongProducer)
+        ));
+
+        let (wrong_key, _) = sign(&genesis, header(&genesis, 1, 1), &[key(2)]);
+        assert!(matches!(
+            genesis.next(&wrong_key, fake_recover),
+            Err(HeaderError::InvalidSignature)
+        ));
+
+        let second = genesis
+            .next(
+                &sign(&genesis, header(&genesis, 1, 1), &[key(1)]).0,
+                fake_recover,
+            )
+            .unwrap();
+        let stale = unsigned(header(&second, 1, 1));
+        assert!(matches!(
+            second.next(&stale, fake_recover),
+            Err(HeaderError::TimestampNotIncreasing)
+        ));
+    }
+
+    #[test]
+    fn forks() {
+        let genesis = genesis();
+        let (a, _) = sign(&genesis, header(&genesis, 1, 1), &[key(1)]);
+        let (b, _) = sign(&genesis, header(&genesis, 2, 1), &[key(1)]);
+        let a = genesis.next(&a, fake_recover).unwrap();
+        let b = genesis.next(&b, fake_recover).unwrap();
+        assert_ne!(a.id, b.id);
+
+        let (forged, _) = sign(&b, header(&b, 12, 2), &[key(1)]);
+        assert!(matches!(
+            b.next(&forged, fake_recover),
+            Err(HeaderError::InvalidSignature)
+        ));
+        let (from_a, _) = sign(&a, header(&a, 12, 2), &[key(2)]);
+        assert!(matches!(
+            b.next(&from_a, fake_recover),
+            Err(HeaderError::UnlinkableBlock)
+        ));
+        assert!(a.next(&from_a, fake_recover).is_ok());
+    }
+
+    #[test]
+    fn schedule_change() {
+        let genesis = genesis();
+        let new_schedule = schedule(2, &[(3, 3)]);
+        let mut proposal = header(&genesis, 1, 1);
+        proposal.header_extensions.push(BlockHeaderExtension {
+            id: BlockHeaderExtension::PRODUCER_SCHEDULE_CHANGE,
+            data: new_schedule.pack().unwrap(),
+        });
+        let (signed, _) = sign(&genesis, proposal.clone(), &[key(1)]);
+        let second = genesis.next(&signed, fake_recover).unwrap();
+        assert_eq!(second.pending_schedule.schedule, new_schedule);
+        assert_eq!(second.pending_schedule.schedule_lib_num, 2);
+        assert_eq!(
+            second.pending_schedule.schedule_hash,
+            Checksum256::hash(new_schedule.pack().unwrap())
+        );
+
+        // a second proposal cannot replace the pending schedule
+        proposal.previous = second.id;
+        proposal.timestamp = BlockTimestamp::from(2);
+        let signed = unsigned(proposal);
+        assert!(matches!(
+            second.next(&signed, fake_recover),
+            Err(HeaderError::InvalidScheduleChange)
+        ));
+
+        // the old schedule stays active until a header claims the new one
+        let (signed, _) = sign(&second, header(&second, 12, 2), &[key(2)]);
+        let third = second.next(&signed, fake_recover).unwrap();
+        assert_eq!(third.active_schedule.version, 1);
+
+        // the promoting block is produced by the old schedule
+        let mut promoted = header(&third, 13, 2);
+        promoted.schedule_version = 2;
+        let (signed, _) = sign(&third, promoted.clone(), &[key(2)]);
+        let fourth = third.next(&signed, fake_recover).unwrap();
+        assert_eq!(fourth.active_schedule, new_schedule);
+        assert!(fourth.pending_schedule.schedule.producers.is_empty());
+
+        // the blocks after it are produced by the new schedule
+        let (signed, _) = sign(&fourth, header(&fourth, 14, 3), &[key(3)]);
+        assert!(fourth.next(&signed, fake_recover).is_ok());
+        assert!(matches!(
+            fourth.next(&unsigned(header(&fourth, 14, 2)), fake_recover),
+            Err(HeaderError::WrongProducer)
+        ));
+
+        // the promoting block cannot also propose the next schedule
+        let mut promote_and_propose = promoted.clone();
+        promote_and_propose
+            .header_extensions
+            .push(BlockHeaderExtension {
+                id: BlockHeaderExtension::PRODUCER_SCHEDULE_CHANGE,
+                data: schedule(3, &[(3, 3)]).pack().unwrap(),
+            });
+        assert!(matches!(
+            third.next(&unsigned(promote_and_propose), fake_recover),
+            Err(HeaderError::InvalidScheduleChange)
+        ));
+    }
+
+    #[test]
+    fn weighted_threshold() {
+        let mut genesis = genesis();
+        genesis.active_schedule = ProducerAuthoritySchedule {
+            version: 1,
+            producers: vec![ProducerAuthority {
+                producer_name: AccountName::new(1),
+                authority: BlockSigningAuthority {
+                    threshold: 2,
+                    keys: vec![
+                        KeyWeight::from(key(1)),
+                        KeyWeight::from(key(2)),
+                    ],
+                },
+            }],
+        };
+        let (signed, _) = sign(&genesis, header(&genesis, 1, 1), &[key(1)]);
+        assert!(matches!(
+            genesis.next(&signed, fake_recover),
+            Err(HeaderError::ThresholdNotMet)
+        ));
+
+        let (signed, extra) =
+            sign(&genesis, header(&genesis, 1, 1), &[key(1), key(2)]);
+        assert!(genesis
+            .next_with_signatures(&signed, &extra, fake_recover)
+            .is_ok());
+
+        let (signed, extra) =
+            sign(&genesis, header(&genesis, 1, 1), &[key(1), key(1)]);
+        assert!(matches!(
+            genesis.next_with_signatures(&signed, &extra, fake_recover),
+            Err(HeaderError::InvalidSignature)
+        ));
+    }
+
+    /// A schedule change extension laid out by hand the way nodeos packs a
+    /// `producer_authority_schedule`: version 2, `eosio` signing with
+    /// `EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV` (the default
+    /// development key), then `alice` with a threshold of 3 over that key
+    /// with weight 1 and the secp256k1 generator with weight 258. Key
+    /// weights are `uint16_t`.
+    const SCHEDULE_CHANGE: &str = concat!(
+        "02000000",         // version
+        "02",               // producer count
+        "0000000000ea3055", // eosio
+        "00",               // block_signing_authority_v0
+        "01000000",         // threshold
+        "01",               // key count
+        "00",               // K1
+        "02c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf",
+        "0100",             // weight
+        "0000000000855c34", // alice
+        "00",
+        "03000000",
+        "02",
+        "00",
+        "02c0ded2bc1f1305fb0faac5e6c03ee3a1924234985427b6167ca569d13df435cf",
+        "0100",
+        "00",
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        "0201",
+    );
+
+    /// SHA-256 of `SCHEDULE_CHANGE`, computed independently.
+    const SCHEDULE_HASH: &str =
+        "e66a91fb3fc865339adfb76965d3092881da2c5f3e79342bcf91eae5a6553355";
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(s.get(i..i + 2).unwrap(), 16).unwrap())
+            .collect()
+    }
+
+    fn schedule_change_extension() -> BlockHeaderExtension {
+        BlockHeaderExtension {
+            id: BlockHeaderExtension::PRODUCER_SCHEDULE_CHANGE,
+            data: hex(SCHEDULE_CHANGE),
+        }
+    }
+
+    #[test]
+    fn reads_packed_schedule_changes() {
+        let genesis = genesis();
+        let mut proposal = header(&genesis, 1, 1);
+        proposal.header_extensions.push(schedule_change_extension());
+
+        let schedule = proposal.new_producer_schedule().unwrap().unwrap();
+        assert_eq!(schedule.version, 2);
+        let names: Vec<_> =
+            schedule.producers.iter().map(|p| p.producer_name).collect();
+        assert_eq!(
+            names,
+            vec![AccountName::new(n!("eosio")), AccountName::new(n!("alice"))]
+        );
+        let alice = &schedule.producers.get(1).unwrap().authority;
+        assert_eq!(alice.threshold, 3);
+        let weights: Vec<_> = alice.keys.iter().map(|k| k.weight).collect();
+        assert_eq!(weights, vec![1, 258]);
+        assert_eq!(
+            alice.keys.first().unwrap().key.as_slice(),
+            hex(SCHEDULE_CHANGE).get(20..53).unwrap()
+        );
+        assert_eq!(schedule.pack().unwrap(), hex(SCHEDULE_CHANGE));
+
+        let (signed, _) = sign(&genesis, proposal, &[key(1)]);
+        let second = genesis.next(&signed, fake_recover).unwrap();
+        assert_eq!(second.pending_schedule.schedule, schedule);
+        assert_eq!(
+            second.pending_schedule.schedule_hash.to_bytes().to_vec(),
+            hex(SCHEDULE_HASH)
+        );
+    }
+
+    /// Signs a header proposing `SCHEDULE_CHANGE` with the private key of the
+    /// default development key, `5KQwrPbwdL6PhXujxW37FSSQZ1JiwsST4cqQzDeyXtP79zkvFD3`.
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn k1_schedule_change() {
+        let secret = libsecp256k1::SecretKey::parse_slice(&hex(
+            "d2653ff7cbb2d8ff129ac27ef5781ce68b2558c41a74af1f2ddca635cbeef07d",
+        ))
+        .unwrap();
+        let dev_key = PublicKey {
+            type_: UnsignedInt::from(0_u8),
+            data: libsecp256k1::PublicKey::from_secret_key(&secret)
+                .serialize_compressed(),
+        };
+        let mut genesis = genesis();
+        genesis.active_schedule.producers = vec![ProducerAuthority {
+            producer_name: AccountName::new(1),
+            authority: dev_key.clone().into(),
+        }];
+        let mut proposal = header(&genesis, 1, 1);
+        proposal.header_extensions.push(schedule_change_extension());
+        let schedule = proposal.new_producer_schedule().unwrap().unwrap();
+        assert_eq!(
+            schedule.producers.first().unwrap().authority,
+            dev_key.into()
+        );
+
+        let digest = Cell::new(None);
+        let _ = genesis.next(&unsigned(proposal.clone()), |d, _| {
+            digest.set(Some(*d));
+            None
+        });
+        let digest = digest.get().unwrap();
+        let message = libsecp256k1::Message::parse(&digest.to_bytes());
+        let (signature, recovery_id) = libsecp256k1::sign(&message, &secret);
+        let mut data = [0_u8; 65];
+        let (header_byte, rs) = data.split_at_mut(1);
+        header_byte.copy_from_slice(&[recovery_id.serialize() + 27 + 4]);
+        rs.copy_from_slice(&signature.serialize());
+        let signed = SignedBlockHeader {
+            header: proposal,
+            producer_signature: Signature {
+                type_: UnsignedInt::from(0_u8),
+                data,
+            },
+        };
+        let second = genesis.next(&signed, |d, s| s.recover_k1(d)).unwrap();
+        assert_eq!(
+            second.pending_schedule.schedule_hash.to_bytes().to_vec(),
+            hex(SCHEDULE_HASH)
+        );
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn k1_signatures() {
+        let secret = libsecp256k1::SecretKey::parse(&[7; 32]).unwrap();
+        let public = PublicKey {
+            type_: UnsignedInt::from(0_u8),
+            data: libsecp256k1::PublicKey::from_secret_key(&secret)
+                .serialize_compressed(),
+        };
+        let mut genesis = genesis();
+        genesis.active_schedule.producers = vec![ProducerAuthority {
+            producer_name: AccountName::new(1),
+            authority: public.clone().into(),
+        }];
+
+        let unsigned = unsigned(header(&genesis, 1, 1));
+        let digest = BlockHeaderState::sig_digest(
+            &unsigned.header,
+            &{
+                let mut merkle = genesis.blockroot_merkle.clone();
+                merkle.append(genesis.id);
+                merkle
+            },
+            &genesis.pending_schedule,
+        )
+        .unwrap();
+        let message = libsecp256k1::Message::parse(&digest.to_bytes());
+        let (signature, recovery_id) = libsecp256k1::sign(&message, &secret);
+        let mut data = [0_u8; 65];
+        let (header_byte, rs) = data.split_at_mut(1);
+        header_byte.copy_from_slice(&[recovery_id.serialize() + 27 + 4]);
+        rs.copy_from_slice(&signature.serialize());
+        let signed = SignedBlockHeader {
+            header: unsigned.header,
+            producer_signature: Signature {
+                type_: UnsignedInt::from(0_u8),
+                data,
+            },
+        };
+
+        assert_eq!(signed.producer_signature.recover_k1(&digest), Some(public));
+        assert!(genesis.next(&signed, |d, s| s.recover_k1(d)).is_ok());
+        assert!(matches!(
+            genesis.next(&signed, |d, s| s
+                .recover_k1(&Checksum256::hash(d.to_bytes()))),
+            Err(HeaderError::InvalidSignature)
+        ));
+    }
+}
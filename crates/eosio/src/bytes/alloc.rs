@@ -47,14 +47,14 @@ where
 {
     #[inline]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let capacity = usize::read(bytes, pos)?;
+        let len = usize::read(bytes, pos)?;
 
-        let mut results = Self::new();
-        results.resize(capacity, T::default());
-
-        for item in &mut results {
-            let r = T::read(bytes, pos)?;
-            *item = r;
+        // the length is untrusted, so only reserve what the remaining bytes
+        // could possibly hold
+        let remaining = bytes.len().saturating_sub(*pos);
+        let mut results = Self::with_capacity(len.min(remaining));
+        for _ in 0..len {
+            results.push(T::read(bytes, pos)?);
         }
 
         Ok(results)
@@ -0,0 +1,226 @@
+use super::{Read, ReadError, Write, WriteError};
+use alloc::vec::Vec;
+use std::io;
+
+const CHUNK_SIZE: usize = 4096;
+
+impl From<ReadError> for io::Error {
+    #[inline]
+    fn from(err: ReadError) -> Self {
+        let kind = match err {
+            ReadError::NotEnoughBytes => io::ErrorKind::UnexpectedEof,
+            ReadError::VarIntOverflow
+            | ReadError::NonCanonicalVarInt
+            | ReadError::UnknownVariant => io::ErrorKind::InvalidData,
+        };
+        Self::new(kind, err)
+    }
+}
+
+impl From<WriteError> for io::Error {
+    #[inline]
+    fn from(err: WriteError) -> Self {
+        let kind = match err {
+            WriteError::NotEnoughSpace => io::ErrorKind::WriteZero,
+            WriteError::TryFromIntError => io::ErrorKind::InvalidInput,
+        };
+        Self::new(kind, err)
+    }
+}
+
+/// Reads values from a [`std::io::Read`] source.
+///
+/// Bytes are buffered until a whole value can be read, and bytes past the end
+/// of a value are kept for the next one.
+#[derive(Debug)]
+pub struct IoReader<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: io::Read> IoReader<R> {
+    /// Creates a new reader.
+    #[inline]
+    pub const fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Reads the next value.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the source fails, ends before the value is
+    /// complete, or contains invalid data.
+    pub fn read<T: Read>(&mut self) -> io::Result<T> {
+        loop {
+            let mut pos = self.pos;
+            match T::read(&self.buf, &mut pos) {
+                Ok(value) => {
+                    self.pos = pos;
+                    return Ok(value);
+                }
+                Err(ReadError::NotEnoughBytes) => {
+                    if self.fill()? == 0 {
+                        return Err(ReadError::NotEnoughBytes.into());
+                    }
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Returns the bytes that have been buffered but not read yet.
+    #[inline]
+    pub fn buffer(&self) -> &[u8] {
+        self.buf.get(self.pos..).unwrap_or_default()
+    }
+
+    /// Unwraps this reader, discarding any buffered bytes.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads more bytes from the source, returning how many were read.
+    fn fill(&mut self) -> io::Result<usize> {
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+        let len = self.buf.len();
+        self.buf.resize(len + CHUNK_SIZE, 0);
+        let result = loop {
+            match self.inner.read(self.buf.get_mut(len..).unwrap_or_default()) {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                result => break result,
+            }
+        };
+        self.buf.truncate(len + *result.as_ref().unwrap_or(&0));
+        result
+    }
+}
+
+/// Writes values to a [`std::io::Write`] sink.
+#[derive(Debug)]
+pub struct IoWriter<W> {
+    inner: W,
+}
+
+impl<W: io::Write> IoWriter<W> {
+    /// Creates a new writer.
+    #[inline]
+    pub const fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Serializes and writes a value.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the value could not be serialized or the sink
+    /// fails.
+    #[inline]
+    pub fn write<T: Write>(&mut self, value: &T) -> io::Result<()> {
+        self.inner.write_all(&value.pack()?)
+    }
+
+    /// Flushes the sink.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the sink fails.
+    #[inline]
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Unwraps this writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IoReader, IoWriter};
+    use crate::{n, AccountName, UnsignedInt};
+    use alloc::{string::String, vec::Vec};
+    use std::io;
+
+    struct OneByte<'a>(&'a [u8]);
+
+    impl io::Read for OneByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match (self.0.split_first(), buf.first_mut()) {
+                (Some((byte, rest)), Some(out)) => {
+                    *out = *byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let account = AccountName::from(n!("eosio.token"));
+        let memo = String::from("hello");
+        let mut writer = IoWriter::new(Vec::new());
+        writer.write(&account).unwrap();
+        writer.write(&memo).unwrap();
+        writer.write(&UnsignedInt::from(300_u32)).unwrap();
+        let bytes = writer.into_inner();
+
+        // values span several reads from a source that yields one byte
+        // at a time
+        let mut reader = IoReader::new(OneByte(&bytes));
+        assert_eq!(reader.read::<AccountName>().unwrap(), account);
+        assert_eq!(reader.read::<String>().unwrap(), memo);
+        assert_eq!(
+            reader.read::<UnsignedInt>().unwrap(),
+            UnsignedInt::from(300_u32)
+        );
+        let err = reader.read::<u8>().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn keeps_remaining_bytes() {
+        let mut reader = IoReader::new(&[1_u8, 2, 3][..]);
+        assert_eq!(reader.read::<u16>().unwrap(), 0x0201);
+        assert_eq!(reader.buffer(), &[3]);
+        assert_eq!(reader.read::<u8>().unwrap(), 3);
+        assert_eq!(reader.buffer(), &[] as &[u8]);
+    }
+
+    #[test]
+    fn errors() {
+        let mut reader = IoReader::new(&[0x80_u8, 0x00][..]);
+        let err = reader.read::<UnsignedInt>().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut buf = [0_u8; 4];
+        let mut writer = IoWriter::new(&mut buf[..]);
+        let err = writer.write(&0_u64).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn std_errors() {
+        use crate::{ParsePermissionLevelError, PermissionLevel};
+        use std::{boxed::Box, error::Error};
+
+        fn parse(s: &str) -> Result<PermissionLevel, Box<dyn Error>> {
+            Ok(s.parse::<PermissionLevel>()?)
+        }
+
+        let err = parse("alice@ACTIVE").unwrap_err();
+        let err = err.downcast_ref::<ParsePermissionLevelError>().unwrap();
+        assert!(err.source().is_some());
+    }
+}
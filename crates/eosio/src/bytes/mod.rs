@@ -2,12 +2,16 @@
 mod alloc;
 mod collections;
 mod data_stream;
+#[cfg(feature = "std")]
+mod io;
 mod marker;
 mod num;
 mod option;
 mod primitives;
 
 pub use self::data_stream::DataStream;
+#[cfg(feature = "std")]
+pub use self::io::{IoReader, IoWriter};
 pub use eosio_macros::{NumBytes, Read, Write};
 
 use ::alloc::{vec, vec::Vec};
@@ -39,10 +43,16 @@ pub trait Read: Sized + NumBytes {
 }
 
 /// Error that can be returned when reading bytes.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReadError {
     /// Not enough bytes.
     NotEnoughBytes,
+    /// A variable length integer does not fit in its type.
+    VarIntOverflow,
+    /// A variable length integer is not encoded in its shortest form.
+    NonCanonicalVarInt,
+    /// A variant has a tag that does not match any of its alternatives.
+    UnknownVariant,
 }
 
 impl fmt::Display for ReadError {
@@ -50,10 +60,16 @@ impl fmt::Display for ReadError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::NotEnoughBytes => write!(f, "not enough bytes"),
+            Self::VarIntOverflow => write!(f, "varint overflow"),
+            Self::NonCanonicalVarInt => write!(f, "non-canonical varint"),
+            Self::UnknownVariant => write!(f, "unknown variant"),
         }
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ReadError {}
+
 /// Write bytes.
 pub trait Write: Sized + NumBytes {
     /// Write bytes.
@@ -99,9 +115,12 @@ impl fmt::Display for WriteError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for WriteError {}
+
 #[cfg(test)]
 mod tests {
-    use super::{vec, NumBytes, Read, Write};
+    use super::{vec, NumBytes, Read, ReadError, Write};
     use ::alloc::{
         string::{String, ToString},
         vec::Vec,
@@ -145,6 +164,7 @@ mod tests {
         test_i64, i64, -1_i64
         test_bool_true, bool, true
         test_bool_false, bool, false
+        test_unit, (), ()
         // test_option_none, Option<u8>, None as Option<u8>
         // test_option_some, Option<u8>, Some(1)
         test_string, String, "neat".to_string()
@@ -185,6 +205,15 @@ mod tests {
         // test_non_zero_isize, NonZeroIsize, NonZeroIsize::new(-1_isize).unwrap()
     );
 
+    #[test]
+    fn test_vec_untrusted_len() {
+        // claims u32::MAX items but only holds one
+        let bytes = [0xff, 0xff, 0xff, 0xff, 0x0f, 1];
+        assert_eq!(Vec::<u8>::unpack(bytes), Err(ReadError::NotEnoughBytes));
+        let bytes = [0x80, 0x80, 0x80, 0x80, 0x80, 0x01];
+        assert_eq!(Vec::<u8>::unpack(bytes), Err(ReadError::VarIntOverflow));
+    }
+
     // #[test]
     // fn test_struct_named_fields() {
     //     #[derive(Read, Write, PartialEq, Debug)]
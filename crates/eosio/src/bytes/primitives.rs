@@ -1,6 +1,6 @@
 use super::{NumBytes, Read, ReadError, Write, WriteError};
 use crate::varint::{SignedInt, UnsignedInt};
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
 
 macro_rules! impl_nums {
     ($($t:ty, $s:expr)*) => ($(
@@ -133,6 +133,31 @@ impl Write for f64 {
     }
 }
 
+impl NumBytes for () {
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        0
+    }
+}
+
+impl Read for () {
+    #[inline]
+    fn read(_bytes: &[u8], _pos: &mut usize) -> Result<Self, ReadError> {
+        Ok(())
+    }
+}
+
+impl Write for () {
+    #[inline]
+    fn write(
+        &self,
+        _bytes: &mut [u8],
+        _pos: &mut usize,
+    ) -> Result<(), WriteError> {
+        Ok(())
+    }
+}
+
 impl NumBytes for bool {
     #[inline]
     #[must_use]
@@ -190,7 +215,8 @@ impl NumBytes for usize {
     #[inline]
     #[must_use]
     fn num_bytes(&self) -> usize {
-        UnsignedInt::from(*self).num_bytes()
+        // values that don't fit fail to write, so the length is irrelevant
+        UnsignedInt::try_from(*self).map_or(5, |v| v.num_bytes())
     }
 }
 
@@ -208,7 +234,9 @@ impl Write for usize {
         bytes: &mut [u8],
         pos: &mut usize,
     ) -> Result<(), WriteError> {
-        UnsignedInt::from(*self).write(bytes, pos)
+        UnsignedInt::try_from(*self)
+            .map_err(|_| WriteError::TryFromIntError)?
+            .write(bytes, pos)
     }
 }
 
@@ -216,7 +244,8 @@ impl NumBytes for isize {
     #[inline]
     #[must_use]
     fn num_bytes(&self) -> usize {
-        SignedInt::from(*self).num_bytes()
+        // values that don't fit fail to write, so the length is irrelevant
+        SignedInt::try_from(*self).map_or(5, |v| v.num_bytes())
     }
 }
 
@@ -234,7 +263,9 @@ impl Write for isize {
         bytes: &mut [u8],
         pos: &mut usize,
     ) -> Result<(), WriteError> {
-        SignedInt::from(*self).write(bytes, pos)
+        SignedInt::try_from(*self)
+            .map_err(|_| WriteError::TryFromIntError)?
+            .write(bytes, pos)
     }
 }
 
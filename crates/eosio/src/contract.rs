@@ -0,0 +1,39 @@
+//! Contracts with methods as actions, like `eosio::contract` in C++.
+use crate::{account::AccountName, action::ActionName};
+
+/// The accounts and action that a contract is running with.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ContractContext {
+    /// The account whose code is running
+    pub receiver: AccountName,
+    /// The account the action was sent to. This differs from `receiver`
+    /// when handling a notification.
+    pub first_receiver: AccountName,
+    /// The name of the action
+    pub action: ActionName,
+}
+
+impl ContractContext {
+    /// The account the action was sent to, called `code` by `apply`. Same
+    /// as `first_receiver`.
+    #[inline]
+    #[must_use]
+    pub const fn code(&self) -> AccountName {
+        self.first_receiver
+    }
+}
+
+/// A contract whose actions are methods, declared with
+/// `#[eosio::contract]`.
+///
+/// The contract is created once per action, before the action runs, so it
+/// can hold state such as cached tables. `finalize` runs after the action
+/// succeeds.
+pub trait Contract: Sized {
+    /// Creates the contract.
+    fn new(context: ContractContext) -> Self;
+
+    /// Runs after an action succeeds, e.g. to save cached state.
+    #[inline]
+    fn finalize(self) {}
+}
@@ -0,0 +1,44 @@
+//! Typed errors that abort actions with stable error codes.
+pub use eosio_macros::ContractError;
+
+/// An error that aborts an action with a stable code, derived with
+/// `#[derive(ContractError)]` on an enum.
+///
+/// Codes count up from 0 like enum discriminants, and can be set with
+/// `#[eosio(code = 10)]` or `Variant = 10`. Messages default to the variant
+/// name in lowercase words, and can be set with `#[eosio(message = "...")]`.
+///
+/// Actions returning `Result<T, E>` abort with the code of the error, or its
+/// message when the `verbose` feature of `eosio_cdt` is enabled.
+///
+/// ```
+/// use eosio::ContractError;
+///
+/// #[derive(ContractError)]
+/// enum TokenError {
+///     Overdrawn,
+///     #[eosio(code = 10, message = "symbol precision mismatch")]
+///     SymbolMismatch,
+///     MemoTooLong { len: usize },
+/// }
+///
+/// assert_eq!(TokenError::Overdrawn.error_code(), 0);
+/// assert_eq!(
+///     TokenError::SymbolMismatch.error_message(),
+///     "symbol precision mismatch"
+/// );
+/// let error = TokenError::MemoTooLong { len: 300 };
+/// assert_eq!(error.error_code(), 11);
+/// assert_eq!(error.error_message(), "memo too long");
+/// ```
+pub trait ContractError {
+    /// The code and message of every error, for the `error_messages` of
+    /// ABIs.
+    const ERRORS: &'static [(u64, &'static str)];
+
+    /// Returns the code of the error.
+    fn error_code(&self) -> u64;
+
+    /// Returns the message of the error.
+    fn error_message(&self) -> &'static str;
+}
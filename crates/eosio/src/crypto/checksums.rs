@@ -1,5 +1,6 @@
 use crate::{NumBytes, Read, ReadError, Write, WriteError};
 use core::{cmp::PartialEq, mem::size_of};
+use sha2::{Digest, Sha256};
 
 macro_rules! declare_checksum_type {
     ($ident:ident, $num_words:expr, $num_bytes:expr) => {
@@ -12,6 +13,12 @@ macro_rules! declare_checksum_type {
                 self.0
             }
 
+            /// Creates a checksum from its raw words.
+            #[must_use]
+            pub const fn from_words(words: [u128; $num_words]) -> Self {
+                Self(words)
+            }
+
             /// TODO docs.
             #[must_use]
             pub fn to_bytes(&self) -> [u8; $num_bytes] {
@@ -182,9 +189,18 @@ declare_checksum_type!(Checksum160, 2, 20);
 declare_checksum_type!(Checksum256, 2, 32);
 declare_checksum_type!(Checksum512, 4, 64);
 
+impl Checksum256 {
+    /// Hashes `data` using SHA256.
+    #[must_use]
+    pub fn hash<T: AsRef<[u8]>>(data: T) -> Self {
+        let digest: [u8; 32] = Sha256::digest(data.as_ref()).into();
+        Self::from_bytes(digest)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Checksum160;
+    use super::{Checksum160, Checksum256};
 
     #[test]
     fn checksum160_from_to_bytes() {
@@ -202,4 +218,14 @@ mod tests {
             assert_eq!(input, output);
         }
     }
+
+    #[test]
+    fn checksum256_hash() {
+        let expected: [u8; 32] = [
+            0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40,
+            0xde, 0x5d, 0xae, 0x22, 0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17,
+            0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00, 0x15, 0xad,
+        ];
+        assert_eq!(Checksum256::hash("abc").to_bytes(), expected);
+    }
 }
@@ -62,8 +62,32 @@ macro_rules! key_type {
     };
 }
 
-key_type!(PublicKey, 34);
-key_type!(Signature, 66);
+key_type!(PublicKey, 33);
+key_type!(Signature, 65);
+
+#[cfg(feature = "secp256k1")]
+impl Signature {
+    /// Recovers the K1 public key that produced this signature over `digest`.
+    /// Returns `None` for non-K1 signatures or if recovery fails.
+    #[must_use]
+    pub fn recover_k1(&self, digest: &crate::Checksum256) -> Option<PublicKey> {
+        if self.type_ != UnsignedInt::from(0_u8) {
+            return None;
+        }
+        let (header, rs) = self.data.split_first()?;
+        let recovery_id =
+            libsecp256k1::RecoveryId::parse_rpc(header.checked_sub(4)?).ok()?;
+        let signature =
+            libsecp256k1::Signature::parse_standard_slice(rs).ok()?;
+        let message = libsecp256k1::Message::parse(&digest.to_bytes());
+        let key =
+            libsecp256k1::recover(&message, &signature, &recovery_id).ok()?;
+        Some(PublicKey {
+            type_: UnsignedInt::from(0_u8),
+            data: key.serialize_compressed(),
+        })
+    }
+}
 
 /// TODO docs
 #[derive(Read, Write, NumBytes, Clone)]
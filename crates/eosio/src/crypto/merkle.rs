@@ -0,0 +1,272 @@
+//! <https://github.com/EOSIO/eos/blob/1418543149b7caf8fc69a23621e3db7f3c6d18ad/libraries/chain/merkle.cpp>
+//! <https://github.com/EOSIO/eos/blob/1418543149b7caf8fc69a23621e3db7f3c6d18ad/libraries/chain/include/eosio/chain/incremental_merkle.hpp>
+use super::Checksum256;
+use crate::bytes::{NumBytes, Read, Write};
+use alloc::vec::Vec;
+
+/// Bit used to flag a digest as the right-hand side of a pair.
+const CANONICAL_FLAG: u128 = 1 << 127;
+
+/// Clears the canonical flag bit, marking a digest as a left-hand node.
+#[inline]
+#[must_use]
+pub const fn make_canonical_left(value: &Checksum256) -> Checksum256 {
+    let [high, low] = value.words();
+    Checksum256::from_words([high & !CANONICAL_FLAG, low])
+}
+
+/// Sets the canonical flag bit, marking a digest as a right-hand node.
+#[inline]
+#[must_use]
+pub const fn make_canonical_right(value: &Checksum256) -> Checksum256 {
+    let [high, low] = value.words();
+    Checksum256::from_words([high | CANONICAL_FLAG, low])
+}
+
+/// Returns true if the digest has been marked as a left-hand node.
+#[inline]
+#[must_use]
+pub const fn is_canonical_left(value: &Checksum256) -> bool {
+    value.words()[0] & CANONICAL_FLAG == 0
+}
+
+/// Returns true if the digest has been marked as a right-hand node.
+#[inline]
+#[must_use]
+pub const fn is_canonical_right(value: &Checksum256) -> bool {
+    value.words()[0] & CANONICAL_FLAG != 0
+}
+
+/// Flags a pair of digests as left and right nodes.
+#[inline]
+#[must_use]
+pub const fn make_canonical_pair(
+    left: &Checksum256,
+    right: &Checksum256,
+) -> (Checksum256, Checksum256) {
+    (make_canonical_left(left), make_canonical_right(right))
+}
+
+/// Hashes a canonical pair of digests into their parent node.
+#[inline]
+#[must_use]
+fn hash_pair(left: &Checksum256, right: &Checksum256) -> Checksum256 {
+    let (left, right) = make_canonical_pair(left, right);
+    let mut bytes = [0_u8; 64];
+    let (l, r) = bytes.split_at_mut(32);
+    l.copy_from_slice(&left.to_bytes());
+    r.copy_from_slice(&right.to_bytes());
+    Checksum256::hash(&bytes[..])
+}
+
+/// Calculates the merkle root of a set of digests, as used for the
+/// `action_mroot` and `transaction_mroot` fields of block headers. Returns the
+/// zero digest if `ids` is empty.
+#[must_use]
+pub fn merkle(mut ids: Vec<Checksum256>) -> Checksum256 {
+    while ids.len() > 1 {
+        if ids.len() % 2 == 1 {
+            if let Some(last) = ids.last().copied() {
+                ids.push(last);
+            }
+        }
+        ids = ids
+            .chunks_exact(2)
+            .map(|pair| match pair {
+                [left, right] => hash_pair(left, right),
+                _ => unreachable!("chunks_exact(2) yields pairs"),
+            })
+            .collect();
+    }
+    ids.pop().unwrap_or_default()
+}
+
+/// Merkle tree that can be appended to without keeping every leaf. Block
+/// header state uses this to track the merkle root of all previous block IDs.
+#[derive(Read, Write, NumBytes, Clone, Debug, Default, PartialEq)]
+#[eosio(crate_path = "crate::bytes")]
+pub struct IncrementalMerkle {
+    /// The nodes that are still needed to compute future roots
+    active_nodes: Vec<Checksum256>,
+    /// The number of leaves that have been appended
+    node_count: u64,
+}
+
+impl IncrementalMerkle {
+    /// Creates an empty tree.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            active_nodes: Vec::new(),
+            node_count: 0,
+        }
+    }
+
+    /// Returns the number of leaves that have been appended.
+    #[inline]
+    #[must_use]
+    pub const fn node_count(&self) -> u64 {
+        self.node_count
+    }
+
+    /// Returns the nodes that are still needed to compute future roots.
+    #[inline]
+    #[must_use]
+    pub fn active_nodes(&self) -> &[Checksum256] {
+        &self.active_nodes
+    }
+
+    /// Returns the current merkle root, or the zero digest if the tree is
+    /// empty.
+    #[inline]
+    #[must_use]
+    pub fn root(&self) -> Checksum256 {
+        self.active_nodes.last().copied().unwrap_or_default()
+    }
+
+    /// Appends a leaf to the tree and returns the new root.
+    pub fn append(&mut self, digest: Checksum256) -> Checksum256 {
+        let max_depth = max_depth(self.node_count + 1);
+        let mut updated = Vec::with_capacity(max_depth);
+        let mut active = self.active_nodes.iter();
+        let mut index = self.node_count;
+        let mut top = digest;
+        let mut partial = false;
+
+        for _ in 1..max_depth {
+            if index & 1 == 0 {
+                // A "left" node with an implied "right" twin; only keep it if
+                // it is fully realized.
+                if !partial {
+                    updated.push(top);
+                }
+                top = hash_pair(&top, &top);
+                partial = true;
+            } else {
+                // A "right" node collapsing into a realized "left" node.
+                let left = active.next().copied().unwrap_or_default();
+                if partial {
+                    updated.push(left);
+                }
+                top = hash_pair(&left, &top);
+            }
+            index >>= 1;
+        }
+
+        updated.push(top);
+        self.active_nodes = updated;
+        self.node_count += 1;
+        top
+    }
+}
+
+/// Returns the depth of a tree holding `node_count` leaves, including the
+/// root.
+const fn max_depth(node_count: u64) -> usize {
+    if node_count == 0 {
+        0
+    } else {
+        node_count.next_power_of_two().trailing_zeros() as usize + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_canonical_left, is_canonical_right, make_canonical_pair, merkle,
+        Checksum256, IncrementalMerkle,
+    };
+    use crate::bytes::{NumBytes, Read, Write};
+    use alloc::vec::Vec;
+
+    fn hex(s: &str) -> Checksum256 {
+        let mut bytes = [0_u8; 32];
+        for (byte, i) in bytes.iter_mut().zip((0..s.len()).step_by(2)) {
+            *byte = u8::from_str_radix(s.get(i..i + 2).unwrap(), 16).unwrap();
+        }
+        Checksum256::from_bytes(bytes)
+    }
+
+    fn leaves(n: usize) -> Vec<Checksum256> {
+        b"abcde"
+            .iter()
+            .take(n)
+            .map(|c| Checksum256::hash([*c]))
+            .collect()
+    }
+
+    const ROOTS: [&str; 5] = [
+        "ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb",
+        "39cd7110283b5980c22c81cbfc13698f0991d4d5d9fd2ef5d198ba2352bfb007",
+        "ec5e7a8bc1d6d737228c3d16a84db6703a9ecc7a7a0bc3b02c05264f9c4f1eda",
+        "cbb1f294149aa0aafec52946abff2aa5828b39d4fc1f75de8d851a54da18cdfc",
+        "b00459fcd0f70d36fc4c9241be0bf6df1ac2ac02b18a3bc55179bb3e5743293a",
+    ];
+
+    #[test]
+    fn canonical_pair() {
+        let a = hex(ROOTS[0]);
+        let b = hex(ROOTS[1]);
+        let (left, right) = make_canonical_pair(&a, &b);
+        assert!(is_canonical_left(&left));
+        assert!(is_canonical_right(&right));
+        assert_eq!(left.to_bytes().first(), Some(&0x4a));
+        assert_eq!(right.to_bytes().first(), Some(&0xb9));
+        assert_eq!(left.to_bytes().get(1..), a.to_bytes().get(1..));
+        assert_eq!(right.to_bytes().get(1..), b.to_bytes().get(1..));
+    }
+
+    #[test]
+    fn merkle_empty() {
+        assert_eq!(merkle(Vec::new()), Checksum256::default());
+        assert_eq!(IncrementalMerkle::new().root(), Checksum256::default());
+    }
+
+    #[test]
+    fn merkle_known_answers() {
+        for (n, expected) in ROOTS.iter().enumerate() {
+            assert_eq!(merkle(leaves(n + 1)), hex(expected), "{} leaves", n);
+        }
+    }
+
+    #[test]
+    fn incremental_merkle_known_answers() {
+        let mut tree = IncrementalMerkle::new();
+        for (leaf, expected) in leaves(5).into_iter().zip(ROOTS.iter()) {
+            let root = tree.append(leaf);
+            assert_eq!(root, hex(expected));
+            assert_eq!(tree.root(), root);
+        }
+        assert_eq!(tree.node_count(), 5);
+    }
+
+    #[test]
+    fn incremental_merkle_matches_merkle() {
+        let ids: Vec<Checksum256> = (0_u16..70)
+            .map(|i| Checksum256::hash(i.to_le_bytes()))
+            .collect();
+        let mut tree = IncrementalMerkle::new();
+        for (i, id) in ids.iter().enumerate() {
+            tree.append(*id);
+            assert_eq!(
+                tree.root(),
+                merkle(ids.iter().take(i + 1).copied().collect())
+            );
+        }
+    }
+
+    #[test]
+    fn incremental_merkle_read_write() {
+        let mut tree = IncrementalMerkle::new();
+        for leaf in leaves(3) {
+            tree.append(leaf);
+        }
+        // the last leaf, its realized left sibling and the root, plus a
+        // varint length prefix and the u64 node count
+        assert_eq!(tree.active_nodes().len(), 3);
+        assert_eq!(tree.num_bytes(), 1 + 3 * 32 + 8);
+        let bytes = tree.pack().unwrap();
+        assert_eq!(IncrementalMerkle::unpack(&bytes).unwrap(), tree);
+    }
+}
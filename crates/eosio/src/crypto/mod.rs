@@ -1,7 +1,12 @@
 mod checksums;
 mod keys;
+mod merkle;
 
 pub use self::{
     checksums::{Checksum160, Checksum256, Checksum512},
     keys::{PrivateKey, PublicKey, Signature},
+    merkle::{
+        is_canonical_left, is_canonical_right, make_canonical_left,
+        make_canonical_pair, make_canonical_right, merkle, IncrementalMerkle,
+    },
 };
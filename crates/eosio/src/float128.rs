@@ -0,0 +1,415 @@
+//! <https://github.com/EOSIO/eosio.cdt/blob/4985359a30da1f883418b7133593f835927b8046/libraries/eosiolib/contracts/eosio/multi_index.hpp>
+use crate::bytes::{NumBytes, Read, ReadError, Write, WriteError};
+use core::{cmp::Ordering, convert::TryFrom};
+
+const SIGN: u128 = 1 << 127;
+const EXPONENT_BITS: u32 = 15;
+const FRACTION_BITS: u32 = 112;
+const EXPONENT_MASK: u128 = ((1 << EXPONENT_BITS) - 1) << FRACTION_BITS;
+const FRACTION_MASK: u128 = (1 << FRACTION_BITS) - 1;
+const BIAS: i32 = 16383;
+const MAX_EXPONENT: u128 = (1 << EXPONENT_BITS) - 1;
+
+/// IEEE 754 quadruple precision floating point number.
+///
+/// This is the `long double` of contracts compiled to WebAssembly. Only
+/// conversions and comparisons are implemented, which is enough to use it as
+/// a secondary key.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct Float128(u128);
+
+impl Float128 {
+    /// Positive zero.
+    pub const ZERO: Self = Self(0);
+    /// Positive infinity.
+    pub const INFINITY: Self = Self(EXPONENT_MASK);
+    /// Negative infinity.
+    pub const NEG_INFINITY: Self = Self(SIGN | EXPONENT_MASK);
+    /// A quiet NaN.
+    pub const NAN: Self = Self(EXPONENT_MASK | 1 << (FRACTION_BITS - 1));
+
+    /// Creates a number from its raw IEEE 754 bits.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u128) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw IEEE 754 bits.
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u128 {
+        self.0
+    }
+
+    /// Returns true if this value is NaN.
+    #[inline]
+    #[must_use]
+    pub const fn is_nan(self) -> bool {
+        self.0 & EXPONENT_MASK == EXPONENT_MASK && self.0 & FRACTION_MASK != 0
+    }
+
+    /// Returns true if this value has its sign bit set, including `-0.0`.
+    #[inline]
+    #[must_use]
+    pub const fn is_sign_negative(self) -> bool {
+        self.0 & SIGN != 0
+    }
+
+    /// Calculates `numerator / denominator`, correctly rounded to the nearest
+    /// representable value. Returns `None` if `denominator` is zero.
+    ///
+    /// This keeps the full precision of prices built from two amounts, which
+    /// would be lost by dividing them as `f64`s first.
+    #[must_use]
+    pub fn from_ratio(numerator: u64, denominator: u64) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        if numerator == 0 {
+            return Some(Self::ZERO);
+        }
+
+        // find `exponent` so that remainder / divisor is in [1, 2)
+        let mut exponent = i32::try_from(denominator.leading_zeros()).ok()?
+            - i32::try_from(numerator.leading_zeros()).ok()?;
+        let shifted = |exponent: i32| -> (u128, u128) {
+            let shift = exponent.unsigned_abs();
+            if exponent >= 0 {
+                (u128::from(numerator), u128::from(denominator) << shift)
+            } else {
+                (u128::from(numerator) << shift, u128::from(denominator))
+            }
+        };
+        let (mut remainder, mut divisor) = shifted(exponent);
+        if remainder < divisor {
+            exponent -= 1;
+            let (r, d) = shifted(exponent);
+            remainder = r;
+            divisor = d;
+        }
+
+        // long division, one bit of the significand at a time, plus a guard
+        // bit for rounding
+        let mut significand = 0_u128;
+        for _ in 0..=FRACTION_BITS + 1 {
+            significand <<= 1;
+            if remainder >= divisor {
+                significand |= 1;
+                remainder -= divisor;
+            }
+            remainder <<= 1;
+        }
+        let guard = significand & 1;
+        significand >>= 1;
+        if guard == 1 && (remainder != 0 || significand & 1 == 1) {
+            significand += 1;
+            if significand >> (FRACTION_BITS + 1) != 0 {
+                significand >>= 1;
+                exponent += 1;
+            }
+        }
+
+        let biased = u128::try_from(exponent + BIAS).ok()?;
+        Some(Self(
+            biased << FRACTION_BITS | (significand & FRACTION_MASK),
+        ))
+    }
+
+    /// Converts to the nearest `f64`, rounding ties to even.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn to_f64(self) -> f64 {
+        let sign = if self.is_sign_negative() {
+            1_u64 << 63
+        } else {
+            0
+        };
+        let biased = (self.0 & EXPONENT_MASK) >> FRACTION_BITS;
+        let fraction = self.0 & FRACTION_MASK;
+
+        let bits = if biased == MAX_EXPONENT {
+            if fraction == 0 {
+                f64::INFINITY.to_bits()
+            } else {
+                f64::NAN.to_bits()
+            }
+        } else if biased == 0 {
+            // subnormal quadruple precision values are far below the f64 range
+            0
+        } else {
+            let significand = fraction | 1 << FRACTION_BITS;
+            let exponent = i32::try_from(biased).unwrap_or(0) - BIAS;
+            if exponent > 1023 {
+                f64::INFINITY.to_bits()
+            } else if exponent >= -1022 {
+                let mut rounded = round_shift(significand, FRACTION_BITS - 52);
+                let mut exponent = exponent;
+                if rounded >> 53 != 0 {
+                    rounded >>= 1;
+                    exponent += 1;
+                }
+                if exponent > 1023 {
+                    f64::INFINITY.to_bits()
+                } else {
+                    let biased = u64::try_from(exponent + 1023).unwrap_or(0);
+                    biased << 52 | (rounded as u64 & ((1 << 52) - 1))
+                }
+            } else {
+                // subnormal f64; carrying into the exponent field is correct
+                let shift = u32::try_from(-1022 - exponent)
+                    .map_or(u32::MAX, |s| s.saturating_add(FRACTION_BITS - 52));
+                round_shift(significand, shift) as u64
+            }
+        };
+        f64::from_bits(sign | bits)
+    }
+}
+
+/// Shifts `value` right by `shift` bits, rounding to nearest with ties to
+/// even.
+const fn round_shift(value: u128, shift: u32) -> u128 {
+    if shift == 0 {
+        return value;
+    }
+    if shift > 127 {
+        return 0;
+    }
+    let quotient = value >> shift;
+    let remainder = value & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    if remainder > half || (remainder == half && quotient & 1 == 1) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+impl From<f64> for Float128 {
+    /// Converts exactly, since every `f64` is representable.
+    #[inline]
+    fn from(value: f64) -> Self {
+        let bits = value.to_bits();
+        let sign = u128::from(bits >> 63) << 127;
+        let biased = (bits >> 52) & 0x7ff;
+        let fraction = u128::from(bits & ((1 << 52) - 1));
+        let magnitude = if biased == 0x7ff {
+            if fraction == 0 {
+                Self::INFINITY.0
+            } else {
+                Self::NAN.0
+            }
+        } else if biased == 0 {
+            if fraction == 0 {
+                0
+            } else {
+                // normalize the subnormal significand
+                let shift = fraction.leading_zeros() - (127 - 52);
+                let fraction = (fraction << shift) & ((1 << 52) - 1);
+                let exponent = -1022 - i32::try_from(shift).unwrap_or(0);
+                let biased = u128::try_from(exponent + BIAS).unwrap_or(0);
+                biased << FRACTION_BITS | fraction << (FRACTION_BITS - 52)
+            }
+        } else {
+            let exponent = i32::try_from(biased).unwrap_or(0) - 1023;
+            let biased = u128::try_from(exponent + BIAS).unwrap_or(0);
+            biased << FRACTION_BITS | fraction << (FRACTION_BITS - 52)
+        };
+        Self(sign | magnitude)
+    }
+}
+
+impl From<f32> for Float128 {
+    #[inline]
+    fn from(value: f32) -> Self {
+        f64::from(value).into()
+    }
+}
+
+impl From<u64> for Float128 {
+    /// Converts exactly, since the significand has 113 bits.
+    #[inline]
+    fn from(value: u64) -> Self {
+        Self::from_ratio(value, 1).unwrap_or_default()
+    }
+}
+
+impl From<i64> for Float128 {
+    /// Converts exactly, since the significand has 113 bits.
+    #[inline]
+    fn from(value: i64) -> Self {
+        let magnitude = Self::from(value.unsigned_abs());
+        if value < 0 {
+            Self(magnitude.0 | SIGN)
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl From<Float128> for f64 {
+    #[inline]
+    fn from(value: Float128) -> Self {
+        value.to_f64()
+    }
+}
+
+impl PartialEq for Float128 {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Float128 {
+    /// Compares like the IEEE 754 comparison operators: NaN is unordered and
+    /// `-0.0` equals `0.0`.
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+        if (self.0 | other.0) & !SIGN == 0 {
+            return Some(Ordering::Equal);
+        }
+        let key = |bits: u128| {
+            if bits & SIGN == 0 {
+                bits | SIGN
+            } else {
+                !bits
+            }
+        };
+        Some(key(self.0).cmp(&key(other.0)))
+    }
+}
+
+impl NumBytes for Float128 {
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        16
+    }
+}
+
+impl Read for Float128 {
+    #[inline]
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        <[u8; 16]>::read(bytes, pos).map(|b| Self(u128::from_le_bytes(b)))
+    }
+}
+
+impl Write for Float128 {
+    #[inline]
+    fn write(
+        &self,
+        bytes: &mut [u8],
+        pos: &mut usize,
+    ) -> Result<(), WriteError> {
+        self.0.to_le_bytes().write(bytes, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Float128;
+    use crate::bytes::{Read, Write};
+
+    #[test]
+    fn from_f64_known_bits() {
+        for (value, bits) in &[
+            (0.0_f64, 0_u128),
+            (1.0, 0x3fff_0000_0000_0000_0000_0000_0000_0000),
+            (-2.0, 0xc000_0000_0000_0000_0000_0000_0000_0000),
+            (0.5, 0x3ffe_0000_0000_0000_0000_0000_0000_0000),
+            (1.5, 0x3fff_8000_0000_0000_0000_0000_0000_0000),
+            (f64::INFINITY, 0x7fff_0000_0000_0000_0000_0000_0000_0000),
+            (
+                f64::MIN_POSITIVE / 4.0,
+                0x3bff_0000_0000_0000_0000_0000_0000_0000,
+            ),
+        ] {
+            assert_eq!(Float128::from(*value).to_bits(), *bits);
+        }
+        assert!(Float128::from(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn f64_round_trip() {
+        for value in &[
+            0.0_f64,
+            -0.0,
+            1.0,
+            -123.456,
+            1e300,
+            -1e-300,
+            f64::MIN_POSITIVE,
+            f64::MIN_POSITIVE / 3.0,
+            f64::MAX,
+            f64::INFINITY,
+        ] {
+            let converted = Float128::from(*value).to_f64();
+            assert_eq!(converted.to_bits(), value.to_bits());
+        }
+    }
+
+    #[test]
+    fn from_ratio() {
+        assert_eq!(Float128::from_ratio(1, 0), None);
+        assert_eq!(Float128::from_ratio(0, 3), Some(Float128::ZERO));
+        assert_eq!(Float128::from_ratio(3, 2), Some(Float128::from(1.5)));
+        assert_eq!(
+            Float128::from(u64::MAX).to_bits(),
+            0x403e_ffff_ffff_ffff_fffe_0000_0000_0000
+        );
+        // 1/3 rounds down, 2/3 rounds up
+        assert_eq!(
+            Float128::from_ratio(1, 3).unwrap().to_bits(),
+            0x3ffd_5555_5555_5555_5555_5555_5555_5555
+        );
+        assert_eq!(
+            Float128::from_ratio(2, 3).unwrap().to_bits(),
+            0x3ffe_5555_5555_5555_5555_5555_5555_5555
+        );
+        assert_eq!(Float128::from_ratio(1, 10).unwrap().to_f64(), 0.1);
+
+        // prices that collide as f64 stay distinct
+        let a = Float128::from_ratio(u64::MAX - 1, u64::MAX).unwrap();
+        let b = Float128::from_ratio(u64::MAX - 2, u64::MAX).unwrap();
+        assert_eq!(a.to_f64(), b.to_f64());
+        assert!(b < a);
+    }
+
+    #[test]
+    fn ordering() {
+        let values = [
+            Float128::NEG_INFINITY,
+            Float128::from(-2.5),
+            Float128::from(-1_i64),
+            Float128::from(0.0),
+            Float128::from_ratio(1, 3).unwrap(),
+            Float128::from(1.0),
+            Float128::from(u64::MAX),
+            Float128::INFINITY,
+        ];
+        for pair in values.windows(2) {
+            if let [a, b] = pair {
+                assert!(a < b);
+            }
+        }
+        assert_eq!(Float128::from(-0.0), Float128::ZERO);
+        assert_ne!(Float128::NAN, Float128::NAN);
+        assert_eq!(Float128::NAN.partial_cmp(&Float128::ZERO), None);
+    }
+
+    #[test]
+    fn read_write() {
+        let value = Float128::from(1.0);
+        let bytes = value.pack().unwrap();
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(bytes.last(), Some(&0x3f));
+        assert_eq!(
+            Float128::unpack(&bytes).unwrap().to_bits(),
+            value.to_bits()
+        );
+    }
+}
@@ -35,11 +35,14 @@
 
 extern crate alloc;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 #[macro_use]
 extern crate std;
 
-pub use eosio_macros::{abi, action, n, s, table};
+pub use eosio_macros::{
+    abi, action, asset, contract, ext_asset, n, on_notify, ricardian_clauses,
+    s, table,
+};
 
 mod abi;
 pub use self::abi::*;
@@ -49,11 +52,14 @@ pub use self::account::AccountName;
 
 mod action;
 pub use self::action::{
-    Action, ActionFn, ActionName, PermissionLevel, PermissionName,
+    Action, ActionFn, ActionName, NotifyFn, ParsePermissionLevelError,
+    PermissionLevel, PermissionName,
 };
 
 mod asset;
-pub use self::asset::{Asset, ExtendedAsset};
+pub use self::asset::{
+    Asset, AssetDivOpError, AssetOpError, ExtendedAsset, ParseAssetError,
+};
 
 mod binary_extension;
 pub use self::binary_extension::BinaryExtension;
@@ -61,6 +67,16 @@ pub use self::binary_extension::BinaryExtension;
 mod block;
 pub use self::block::*;
 
+mod block_header;
+pub use self::block_header::{
+    BlockHeader, BlockHeaderExtension, SignedBlockHeader,
+};
+
+mod block_header_state;
+pub use self::block_header_state::{
+    BlockHeaderState, HeaderError, PendingSchedule,
+};
+
 mod blockchain_parameters;
 pub use self::blockchain_parameters::*;
 
@@ -68,13 +84,27 @@ mod bytes;
 pub use self::bytes::{
     DataStream, NumBytes, Read, ReadError, Write, WriteError,
 };
+#[cfg(feature = "std")]
+pub use self::bytes::{IoReader, IoWriter};
+
+mod contract;
+pub use self::contract::{Contract, ContractContext};
+
+mod contract_error;
+pub use self::contract_error::ContractError;
 
 mod crypto;
 pub use self::crypto::{
-    Checksum160, Checksum256, Checksum512, PrivateKey, PublicKey, Signature,
+    is_canonical_left, is_canonical_right, make_canonical_left,
+    make_canonical_pair, make_canonical_right, merkle, Checksum160,
+    Checksum256, Checksum512, IncrementalMerkle, PrivateKey, PublicKey,
+    Signature,
 };
 
 #[macro_use]
+mod float128;
+pub use self::float128::Float128;
+
 mod name;
 pub use self::name::Name;
 pub use eosio_numstr::{ParseNameError, NAME_CHARS, NAME_MAX_LEN};
@@ -87,6 +117,9 @@ pub use self::ops::{
 mod producer_schedule;
 pub use self::producer_schedule::*;
 
+mod protocol_feature;
+pub use self::protocol_feature::ProtocolFeature;
+
 mod resources;
 pub use self::resources::{CpuWeight, NetWeight, RamBytes};
 
@@ -99,8 +132,9 @@ pub use eosio_numstr::{
 
 mod table;
 pub use self::table::{
-    PrimaryTableIndex, ScopeName, SecondaryKey, SecondaryKeys,
-    SecondaryTableIndex, SecondaryTableName, Table, TableName,
+    CompositeKey, CompositeKeyPart, KvKey, KvKeys, KvPrimaryIndex,
+    KvSecondaryIndex, KvTable, PrimaryTableIndex, ScopeName, SecondaryKey,
+    SecondaryKeys, SecondaryTableIndex, SecondaryTableName, Table, TableName,
 };
 
 mod time;
@@ -112,4 +146,4 @@ pub use self::transaction::{
 };
 
 mod varint;
-pub use self::varint::{SignedInt, UnsignedInt};
+pub use self::varint::{SignedInt, UnsignedInt, VarInt64, VarUint64};
@@ -1,10 +1,14 @@
 //! <https://github.com/EOSIO/eosio.cdt/blob/796ff8bee9a0fc864f665a0a4d018e0ff18ac383/libraries/eosiolib/contracts/eosio/producer_schedule.hpp#L54-L69>
-use crate::{AccountName, NumBytes, PublicKey, Read, Write};
+use crate::{
+    AccountName, BlockTimestamp, NumBytes, PublicKey, Read, ReadError,
+    UnsignedInt, Write, WriteError,
+};
 use alloc::{vec, vec::Vec};
+use core::convert::TryFrom;
 
 /// Maps producer with its signing key, used for producer schedule
 /// <https://github.com/EOSIO/eosio.cdt/blob/796ff8bee9a0fc864f665a0a4d018e0ff18ac383/libraries/eosiolib/contracts/eosio/producer_schedule.hpp#L15-L45>
-#[derive(Read, Write, NumBytes, Clone, Default, Debug)]
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq)]
 #[eosio(crate_path = "crate::bytes")]
 pub struct ProducerKey {
     /// Name of the producer
@@ -15,7 +19,7 @@ pub struct ProducerKey {
 
 /// Defines both the order, account name, and signing keys of the active set
 /// of producers.
-#[derive(Read, Write, NumBytes, Clone, Default, Debug)]
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq)]
 #[eosio(crate_path = "crate::bytes")]
 pub struct ProducerSchedule {
     /// Version number of the schedule. It is sequentially incrementing
@@ -26,14 +30,14 @@ pub struct ProducerSchedule {
 }
 
 /// pairs a public key with an integer weight
-#[derive(Read, Write, NumBytes, Clone, Default, Debug)]
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq)]
 #[eosio(crate_path = "crate::bytes")]
 pub struct KeyWeight {
     /// public key used in a weighted threshold multi-sig authority
     pub key: PublicKey,
     /// weight associated with a signature from the private key associated with
     /// the accompanying public key
-    pub weight: u64,
+    pub weight: u16,
 }
 
 impl From<PublicKey> for KeyWeight {
@@ -44,7 +48,7 @@ impl From<PublicKey> for KeyWeight {
 
 /// block signing authority version 0
 /// this authority allows for a weighted threshold multi-sig per-producer
-#[derive(Read, Write, NumBytes, Clone, Default, Debug)]
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq)]
 #[eosio(crate_path = "crate::bytes")]
 pub struct BlockSigningAuthority {
     /// minimum threshold of accumulated weights from component keys that
@@ -65,11 +69,174 @@ impl From<PublicKey> for BlockSigningAuthority {
 }
 
 /// Maps producer with its signing key, used for producer schedule
-#[derive(Read, Write, NumBytes, Clone, Default, Debug)]
-#[eosio(crate_path = "crate::bytes")]
+#[derive(Clone, Default, Debug, PartialEq)]
 pub struct ProducerAuthority {
     /// Name of the producer
     pub producer_name: AccountName,
     /// The block signing authority used by this producer
     pub authority: BlockSigningAuthority,
 }
+
+/// The only block signing authority variant, used as the serialized tag of
+/// `ProducerAuthority::authority`.
+const BLOCK_SIGNING_AUTHORITY_V0: u8 = 0;
+
+impl NumBytes for ProducerAuthority {
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        self.producer_name.num_bytes()
+            + UnsignedInt::from(BLOCK_SIGNING_AUTHORITY_V0).num_bytes()
+            + self.authority.num_bytes()
+    }
+}
+
+impl Read for ProducerAuthority {
+    #[inline]
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let producer_name = AccountName::read(bytes, pos)?;
+        // the authority is a variant with only one alternative
+        let tag = UnsignedInt::read(bytes, pos)?;
+        if u32::from(tag) != u32::from(BLOCK_SIGNING_AUTHORITY_V0) {
+            return Err(ReadError::UnknownVariant);
+        }
+        let authority = BlockSigningAuthority::read(bytes, pos)?;
+        Ok(Self {
+            producer_name,
+            authority,
+        })
+    }
+}
+
+impl Write for ProducerAuthority {
+    #[inline]
+    fn write(
+        &self,
+        bytes: &mut [u8],
+        pos: &mut usize,
+    ) -> Result<(), WriteError> {
+        self.producer_name.write(bytes, pos)?;
+        UnsignedInt::from(BLOCK_SIGNING_AUTHORITY_V0).write(bytes, pos)?;
+        self.authority.write(bytes, pos)
+    }
+}
+
+impl From<ProducerKey> for ProducerAuthority {
+    #[inline]
+    fn from(key: ProducerKey) -> Self {
+        Self {
+            producer_name: key.producer_name,
+            authority: key.block_signing_key.into(),
+        }
+    }
+}
+
+/// Defines the order, account names, and signing authorities of a set of
+/// producers. Replaces `ProducerSchedule` once weighted threshold multi-sig
+/// block signing is activated.
+#[derive(Read, Write, NumBytes, Clone, Default, Debug, PartialEq)]
+#[eosio(crate_path = "crate::bytes")]
+pub struct ProducerAuthoritySchedule {
+    /// Version number of the schedule. It is sequentially incrementing
+    /// version number.
+    pub version: u32,
+    /// List of producers for this schedule, including its signing authority
+    pub producers: Vec<ProducerAuthority>,
+}
+
+impl ProducerAuthoritySchedule {
+    /// Number of consecutive blocks each producer creates in a round.
+    pub const PRODUCER_REPETITIONS: u32 = 12;
+
+    /// Returns the producer scheduled to create the block at `timestamp`, or
+    /// `None` if the schedule is empty.
+    #[must_use]
+    pub fn scheduled_producer(
+        &self,
+        timestamp: BlockTimestamp,
+    ) -> Option<&ProducerAuthority> {
+        let round = u32::try_from(self.producers.len())
+            .ok()?
+            .checked_mul(Self::PRODUCER_REPETITIONS)?;
+        let slot = u32::from(timestamp).checked_rem(round)?;
+        let index = slot / Self::PRODUCER_REPETITIONS;
+        self.producers.get(usize::try_from(index).ok()?)
+    }
+}
+
+impl From<ProducerSchedule> for ProducerAuthoritySchedule {
+    #[inline]
+    fn from(schedule: ProducerSchedule) -> Self {
+        Self {
+            version: schedule.version,
+            producers: schedule.producers.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ProducerAuthority, ProducerAuthoritySchedule, ProducerKey,
+        ProducerSchedule,
+    };
+    use crate::{
+        AccountName, BlockTimestamp, NumBytes, PublicKey, Read, ReadError,
+        Write,
+    };
+
+    fn schedule(names: &[u64]) -> ProducerAuthoritySchedule {
+        ProducerSchedule {
+            version: 1,
+            producers: names
+                .iter()
+                .map(|name| ProducerKey {
+                    producer_name: AccountName::new(*name),
+                    block_signing_key: PublicKey::default(),
+                })
+                .collect(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn producer_authority_read_write() {
+        let producer: ProducerAuthority = ProducerKey::default().into();
+        let bytes = producer.pack().unwrap();
+        // name, variant tag, threshold, key count, key type, key, weight
+        assert_eq!(bytes.len(), 8 + 1 + 4 + 1 + 1 + 33 + 2);
+        assert_eq!(producer.num_bytes(), bytes.len());
+        assert_eq!(bytes.get(8), Some(&0));
+        assert_eq!(ProducerAuthority::unpack(&bytes).unwrap(), producer);
+    }
+
+    #[test]
+    fn producer_authority_unknown_variant() {
+        let producer: ProducerAuthority = ProducerKey::default().into();
+        let mut bytes = producer.pack().unwrap();
+        *bytes.get_mut(8).unwrap() = 1;
+        assert_eq!(
+            ProducerAuthority::unpack(&bytes),
+            Err(ReadError::UnknownVariant)
+        );
+    }
+
+    #[test]
+    fn scheduled_producer() {
+        let schedule = schedule(&[1, 2, 3]);
+        let producer_at = |slot: u32| {
+            schedule
+                .scheduled_producer(BlockTimestamp::from(slot))
+                .map(|p| p.producer_name)
+        };
+        assert_eq!(producer_at(0), Some(AccountName::new(1)));
+        assert_eq!(producer_at(11), Some(AccountName::new(1)));
+        assert_eq!(producer_at(12), Some(AccountName::new(2)));
+        assert_eq!(producer_at(35), Some(AccountName::new(3)));
+        assert_eq!(producer_at(36), Some(AccountName::new(1)));
+        assert_eq!(
+            ProducerAuthoritySchedule::default()
+                .scheduled_producer(BlockTimestamp::from(0)),
+            None
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! <https://github.com/EOSIO/eos/blob/1418543149b7caf8fc69a23621e3db7f3c6d18ad/libraries/chain/protocol_feature_manager.cpp>
+use crate::Checksum256;
+use alloc::vec::Vec;
+use core::fmt;
+
+macro_rules! protocol_features {
+    ($(
+        $(#[$meta:meta])*
+        $variant:ident, $codename:literal,
+        [$high:literal, $low:literal],
+        [$($dep:ident),*];
+    )*) => {
+        /// Builtin protocol features that can be activated on a chain.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum ProtocolFeature {
+            $(
+                $(#[$meta])*
+                $variant,
+            )*
+        }
+
+        impl ProtocolFeature {
+            /// All builtin protocol features.
+            pub const ALL: &'static [Self] = &[$(Self::$variant,)*];
+
+            /// Returns the codename nodeos uses for this feature.
+            #[must_use]
+            pub const fn codename(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $codename,)*
+                }
+            }
+
+            /// Returns the digest used to activate this feature.
+            #[must_use]
+            pub const fn digest(self) -> Checksum256 {
+                match self {
+                    $(Self::$variant => Checksum256::from_words([$high, $low]),)*
+                }
+            }
+
+            /// Returns the features that must be activated before this one.
+            #[must_use]
+            pub const fn dependencies(self) -> &'static [Self] {
+                match self {
+                    $(Self::$variant => &[$(Self::$dep),*],)*
+                }
+            }
+        }
+
+        impl AsRef<Checksum256> for ProtocolFeature {
+            #[inline]
+            fn as_ref(&self) -> &Checksum256 {
+                $(
+                    #[allow(non_upper_case_globals)]
+                    const $variant: Checksum256 =
+                        ProtocolFeature::$variant.digest();
+                )*
+                match self {
+                    $(Self::$variant => &$variant,)*
+                }
+            }
+        }
+    };
+}
+
+protocol_features! {
+    /// Allows features to be pre-activated by privileged contracts.
+    PreactivateFeature, "PREACTIVATE_FEATURE",
+    [0x0ec7_e080_177b_2c02_b278_d508_8611_686b, 0x49d7_3992_5a92_d9bf_cacd_7fc6_b740_53bd],
+    [];
+    /// Restricts `linkauth` to permissions that already exist.
+    OnlyLinkToExistingPermission, "ONLY_LINK_TO_EXISTING_PERMISSION",
+    [0x1a99_a59d_87e0_6e09_ec5b_028a_9cbb_7749, 0xb4a5_ad88_1900_4365_d02d_c437_9a8b_7241],
+    [];
+    /// Lets deferred transactions be replaced without losing their RAM.
+    ReplaceDeferred, "REPLACE_DEFERRED",
+    [0xef43_112c_6543_b88d_b228_3a2e_0772_78c3, 0x15ae_2c84_719a_8b25_f25c_c885_65fb_ea99],
+    [];
+    /// Prevents deferred transactions from sharing an ID.
+    NoDuplicateDeferredId, "NO_DUPLICATE_DEFERRED_ID",
+    [0x4a90_c00d_5545_4dc5_b059_055c_a213_579c, 0x6ea8_5696_7712_a560_1748_7886_a4d4_cc0f],
+    [ReplaceDeferred];
+    /// Prevents `linkauth` from linking to some native actions.
+    FixLinkauthRestriction, "FIX_LINKAUTH_RESTRICTION",
+    [0xe0fb_64b1_085c_c553_8970_158d_05a0_09c2, 0x4e27_6fb9_4e1a_0bf6_a528_b48f_bc4f_f526],
+    [];
+    /// Rejects empty producer schedules.
+    DisallowEmptyProducerSchedule, "DISALLOW_EMPTY_PRODUCER_SCHEDULE",
+    [0x68dc_aa34_c051_7d19_666e_6b33_add6_7351, 0xd8c5_f69e_999c_a1e3_7931_bc41_0a29_7428],
+    [];
+    /// Requires actions to be authorized by their own account when sent
+    /// from the same account.
+    RestrictActionToSelf, "RESTRICT_ACTION_TO_SELF",
+    [0xad9e_3d8f_6506_8770_9fd6_8f4b_90b4_1f7d, 0x825a_365b_02c2_3a63_6cef_88ac_2ac0_0c43],
+    [];
+    /// Only bills CPU and NET to the first authorizer of a transaction.
+    OnlyBillFirstAuthorizer, "ONLY_BILL_FIRST_AUTHORIZER",
+    [0x8ba5_2fe7_a395_6c5c_d3a6_56a3_174b_931d, 0x3bb2_abb4_5578_befc_59f2_83ec_d816_a405],
+    [];
+    /// Lets contracts run actions that were sent before a `setcode`.
+    ForwardSetcode, "FORWARD_SETCODE",
+    [0x2652_f5f9_6006_2941_09b3_dd0b_bde6_3693, 0xf553_24af_452b_799e_e137_a81a_905e_ed25],
+    [];
+    /// Adds the `get_sender` intrinsic.
+    GetSender, "GET_SENDER",
+    [0xf0af_56d2_c5a4_8d60_a4a5_b5c9_03ed_fb7d, 0xb3a7_36a9_4ed5_89d0_b797_df33_ff9d_3e1d],
+    [];
+    /// Restricts which accounts can pay for RAM.
+    RamRestrictions, "RAM_RESTRICTIONS",
+    [0x4e7b_f348_da00_a945_489b_2a68_1749_eb56, 0xf5de_00b9_0001_4e13_7dda_e39f_48f6_9d67],
+    [];
+    /// Adds support for web authentication public keys and signatures.
+    WebauthnKey, "WEBAUTHN_KEY",
+    [0x4fca_8bd8_2bbd_181e_714e_283f_83e1_b45d, 0x95ca_5af4_0fb8_9ad3_977b_653c_448f_78c2],
+    [];
+    /// Adds weighted threshold multi-sig block signing authorities.
+    WtmsigBlockSignatures, "WTMSIG_BLOCK_SIGNATURES",
+    [0x299d_cb6a_f692_324b_899b_39f1_6d5a_530a, 0x3306_2804_e41f_09dc_97e9_f156_b447_6707],
+    [];
+    /// Lets actions return values to their senders.
+    ActionReturnValue, "ACTION_RETURN_VALUE",
+    [0xc3a6_138c_5061_cf29_1310_887c_0b5c_71fc, 0xaffe_ab90_d5de_b50d_3b9e_687c_ead4_5071],
+    [];
+    /// Adds intrinsics for reading and setting blockchain parameters by ID.
+    BlockchainParameters, "BLOCKCHAIN_PARAMETERS",
+    [0x5443_fcf8_8330_c586_bc0e_5f3d_ee10_e7f6, 0x3c76_c002_49c8_7fe4_fbf7_f38c_0820_06b4],
+    [];
+    /// Makes the WebAssembly limits configurable.
+    ConfigurableWasmLimits, "CONFIGURABLE_WASM_LIMITS2",
+    [0xd528_b9f6_e969_3f45_ed27_7af9_3474_fd47, 0x3ce7_d831_dae2_180c_ca35_d907_bd10_cb40],
+    [];
+    /// Adds the `get_code_hash` intrinsic.
+    GetCodeHash, "GET_CODE_HASH",
+    [0xbcd2_a263_94b3_6614_fd48_9424_1d3c_451a, 0xb0f6_fd11_0958_c342_3073_621a_7082_6e99],
+    [];
+    /// Adds the `get_block_num` intrinsic.
+    GetBlockNum, "GET_BLOCK_NUM",
+    [0x35c2_186c_c36f_7bb4_aeaf_4487_b36e_5703, 0x9ccf_45a9_136a_a856_a5d5_69ec_ca55_ef2b],
+    [];
+    /// Adds hashing, elliptic curve, and modular exponentiation intrinsics.
+    CryptoPrimitives, "CRYPTO_PRIMITIVES",
+    [0x6bcb_40a2_4e49_c26d_0a60_513b_6aeb_8551, 0xd264_e471_7f30_6b81_a37a_5afb_3b47_cedc],
+    [];
+}
+
+impl ProtocolFeature {
+    /// Returns the builtin feature with the given digest, if any.
+    #[must_use]
+    pub fn from_digest(digest: &Checksum256) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|feature| &feature.digest() == digest)
+    }
+
+    /// Returns `features` and all of their dependencies, ordered so that
+    /// every feature comes after its dependencies. `PreactivateFeature` is
+    /// always first since the others can only be activated through it.
+    #[must_use]
+    pub fn activation_plan(features: &[Self]) -> Vec<Self> {
+        fn visit(feature: ProtocolFeature, plan: &mut Vec<ProtocolFeature>) {
+            if plan.contains(&feature) {
+                return;
+            }
+            for dependency in feature.dependencies() {
+                visit(*dependency, plan);
+            }
+            plan.push(feature);
+        }
+
+        let mut plan = Vec::with_capacity(features.len() + 1);
+        visit(Self::PreactivateFeature, &mut plan);
+        for feature in features {
+            visit(*feature, &mut plan);
+        }
+        plan
+    }
+}
+
+impl From<ProtocolFeature> for Checksum256 {
+    #[inline]
+    fn from(feature: ProtocolFeature) -> Self {
+        feature.digest()
+    }
+}
+
+impl fmt::Display for ProtocolFeature {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.codename())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProtocolFeature;
+    use crate::Checksum256;
+    use alloc::string::ToString;
+
+    #[test]
+    fn digests() {
+        let digest = ProtocolFeature::PreactivateFeature.digest().to_bytes();
+        assert_eq!(digest.first(), Some(&0x0e));
+        assert_eq!(digest.last(), Some(&0xbd));
+        for (i, a) in ProtocolFeature::ALL.iter().enumerate() {
+            assert_eq!(ProtocolFeature::from_digest(&a.digest()), Some(*a));
+            assert_eq!(AsRef::<Checksum256>::as_ref(a), &a.digest());
+            for b in ProtocolFeature::ALL.iter().skip(i + 1) {
+                assert_ne!(a.digest(), b.digest());
+            }
+        }
+        assert_eq!(ProtocolFeature::from_digest(&Checksum256::default()), None);
+    }
+
+    #[test]
+    fn activation_plan() {
+        assert_eq!(
+            ProtocolFeature::activation_plan(&[
+                ProtocolFeature::GetSender,
+                ProtocolFeature::NoDuplicateDeferredId,
+                ProtocolFeature::ReplaceDeferred,
+                ProtocolFeature::GetSender,
+            ]),
+            vec![
+                ProtocolFeature::PreactivateFeature,
+                ProtocolFeature::GetSender,
+                ProtocolFeature::ReplaceDeferred,
+                ProtocolFeature::NoDuplicateDeferredId,
+            ]
+        );
+
+        let plan = ProtocolFeature::activation_plan(ProtocolFeature::ALL);
+        assert_eq!(plan.len(), ProtocolFeature::ALL.len());
+        for (i, feature) in plan.iter().enumerate() {
+            for dependency in feature.dependencies() {
+                assert!(plan.iter().take(i).any(|f| f == dependency));
+            }
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            ProtocolFeature::WtmsigBlockSignatures.to_string(),
+            "WTMSIG_BLOCK_SIGNATURES"
+        );
+    }
+}
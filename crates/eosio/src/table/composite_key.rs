@@ -0,0 +1,340 @@
+use crate::{
+    AccountName, ActionName, Checksum256, Name, PermissionName, ScopeName,
+    Symbol, SymbolCode, TableName, TimePointSec,
+};
+
+/// A value that can be packed into a [`CompositeKey`].
+///
+/// Parts are encoded as unsigned integers of `BITS` bits that sort in the
+/// same order as the values they encode.
+pub trait CompositeKeyPart: Sized {
+    /// The number of bits used by the encoded value.
+    const BITS: u32;
+
+    /// Encodes this value. Only the lowest `BITS` bits may be set.
+    fn to_key_part(&self) -> u128;
+
+    /// Decodes a value encoded by `to_key_part`.
+    fn from_key_part(part: u128) -> Self;
+}
+
+macro_rules! impl_unsigned_part {
+    ($($t:ty)*) => ($(
+        impl CompositeKeyPart for $t {
+            const BITS: u32 = <$t>::BITS;
+
+            #[inline]
+            fn to_key_part(&self) -> u128 {
+                u128::from(*self)
+            }
+
+            #[inline]
+            #[allow(clippy::cast_possible_truncation)]
+            fn from_key_part(part: u128) -> Self {
+                part as Self
+            }
+        }
+    )*)
+}
+
+impl_unsigned_part! { u8 u16 u32 u64 u128 }
+
+macro_rules! impl_signed_part {
+    ($($t:ty, $u:ty)*) => ($(
+        impl CompositeKeyPart for $t {
+            const BITS: u32 = <$t>::BITS;
+
+            // flipping the sign bit sorts negative numbers first
+            #[inline]
+            #[allow(clippy::cast_sign_loss)]
+            fn to_key_part(&self) -> u128 {
+                u128::from((*self as $u) ^ (1 << (Self::BITS - 1)))
+            }
+
+            #[inline]
+            #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+            fn from_key_part(part: u128) -> Self {
+                ((part as $u) ^ (1 << (Self::BITS - 1))) as Self
+            }
+        }
+    )*)
+}
+
+impl_signed_part! {
+    i8, u8
+    i16, u16
+    i32, u32
+    i64, u64
+}
+
+macro_rules! impl_u64_part {
+    ($($t:ty)*) => ($(
+        impl CompositeKeyPart for $t {
+            const BITS: u32 = 64;
+
+            #[inline]
+            fn to_key_part(&self) -> u128 {
+                u128::from(self.as_u64())
+            }
+
+            #[inline]
+            #[allow(clippy::cast_possible_truncation)]
+            fn from_key_part(part: u128) -> Self {
+                Self::new(part as u64)
+            }
+        }
+    )*)
+}
+
+impl_u64_part! {
+    Name
+    AccountName
+    ActionName
+    PermissionName
+    ScopeName
+    TableName
+    Symbol
+    SymbolCode
+}
+
+impl CompositeKeyPart for bool {
+    const BITS: u32 = 1;
+
+    #[inline]
+    fn to_key_part(&self) -> u128 {
+        u128::from(*self)
+    }
+
+    #[inline]
+    fn from_key_part(part: u128) -> Self {
+        part != 0
+    }
+}
+
+impl CompositeKeyPart for TimePointSec {
+    const BITS: u32 = 32;
+
+    #[inline]
+    fn to_key_part(&self) -> u128 {
+        u128::from(self.as_secs())
+    }
+
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_key_part(part: u128) -> Self {
+        Self::from_secs(part as u32)
+    }
+}
+
+/// A 256-bit unsigned integer as big-endian `[high, low]` words.
+type Words = [u128; 2];
+
+const fn push(words: Words, bits: u32, part: u128) -> Words {
+    let [high, low] = words;
+    if bits == 128 {
+        [low, part]
+    } else {
+        [high << bits | low >> (128 - bits), low << bits | part]
+    }
+}
+
+const fn pop(words: Words, bits: u32) -> (Words, u128) {
+    let [high, low] = words;
+    if bits == 128 {
+        ([0, high], low)
+    } else {
+        let mask = (1 << bits) - 1;
+        (
+            [high >> bits, low >> bits | (high & mask) << (128 - bits)],
+            low & mask,
+        )
+    }
+}
+
+/// Several values packed into a single secondary key, so that keys sort by
+/// the first value, then by the second, and so on.
+///
+/// This is implemented for tuples of [`CompositeKeyPart`]s. Keys of up to 128
+/// bits fit in a `u128` index, and keys of up to 256 bits fit in a
+/// `Checksum256` index.
+///
+/// # Examples
+///
+/// ```
+/// use eosio::{n, AccountName, CompositeKey, Name};
+/// let key = (Name::new(n!("proposal")), AccountName::new(n!("alice")));
+/// let packed = key.to_u128();
+/// assert_eq!(packed >> 64, u128::from(n!("proposal")));
+/// assert_eq!(<(Name, AccountName)>::from_u128(packed), key);
+/// ```
+pub trait CompositeKey: Sized {
+    /// The number of bits used by the packed key.
+    const BITS: u32;
+
+    /// Packs this key into big-endian `[high, low]` words.
+    fn to_words(&self) -> [u128; 2];
+
+    /// Unpacks a key packed by `to_words`.
+    fn from_words(words: [u128; 2]) -> Self;
+
+    /// Packs this key into a `u128`. Keys wider than 128 bits fail to
+    /// compile:
+    ///
+    /// ```compile_fail
+    /// use eosio::CompositeKey;
+    /// let key = (1_u64, 2_u64, 3_u8).to_u128();
+    /// ```
+    #[inline]
+    #[must_use]
+    fn to_u128(&self) -> u128 {
+        let () = AssertFitsU128::<Self>::OK;
+        self.to_words()[1]
+    }
+
+    /// Unpacks a key packed by `to_u128`.
+    #[inline]
+    #[must_use]
+    fn from_u128(value: u128) -> Self {
+        let () = AssertFitsU128::<Self>::OK;
+        Self::from_words([0, value])
+    }
+
+    /// Packs this key into a `Checksum256`.
+    #[inline]
+    #[must_use]
+    fn to_checksum256(&self) -> Checksum256 {
+        Checksum256::from_words(self.to_words())
+    }
+
+    /// Unpacks a key packed by `to_checksum256`.
+    #[inline]
+    #[must_use]
+    fn from_checksum256(value: &Checksum256) -> Self {
+        Self::from_words(value.words())
+    }
+}
+
+struct AssertFitsU128<K>(K);
+
+impl<K: CompositeKey> AssertFitsU128<K> {
+    const OK: () = assert!(K::BITS <= 128, "composite key is too wide");
+}
+
+macro_rules! impl_composite_key {
+    ($(($first:ident $first_var:ident) $(($t:ident $var:ident))*;)*) => ($(
+        impl<$first, $($t),*> CompositeKey for ($first, $($t),*)
+        where
+            $first: CompositeKeyPart,
+            $($t: CompositeKeyPart),*
+        {
+            const BITS: u32 = {
+                let bits = $first::BITS $(+ $t::BITS)*;
+                assert!(bits <= 256, "composite key is too wide");
+                bits
+            };
+
+            #[inline]
+            fn to_words(&self) -> Words {
+                // evaluating `BITS` rejects keys wider than 256 bits
+                let _ = Self::BITS;
+                let ($first_var, $($var),*) = self;
+                let words = [0, $first_var.to_key_part()];
+                $(let words = push(words, $t::BITS, $var.to_key_part());)*
+                words
+            }
+
+            #[inline]
+            fn from_words(words: Words) -> Self {
+                let _ = Self::BITS;
+                impl_composite_key!(
+                    @pop words; ($first $first_var) $(($t $var))*;
+                );
+                ($first_var, $($var),*)
+            }
+        }
+    )*);
+    // reverse the parts, then pop them from the least significant end
+    (
+        @pop $words:ident; ($t:ident $var:ident) $(($rest:ident $rest_var:ident))*;
+        $(($rev:ident $rev_var:ident))*
+    ) => {
+        impl_composite_key!(
+            @pop $words; $(($rest $rest_var))*; ($t $var) $(($rev $rev_var))*
+        );
+    };
+    (@pop $words:ident; ; $(($rev:ident $rev_var:ident))*) => {
+        $(
+            let ($words, part) = pop($words, $rev::BITS);
+            let $rev_var = $rev::from_key_part(part);
+        )*
+        let _ = $words;
+    };
+}
+
+impl_composite_key! {
+    (A a) (B b);
+    (A a) (B b) (C c);
+    (A a) (B b) (C c) (D d);
+    (A a) (B b) (C c) (D d) (E e);
+    (A a) (B b) (C c) (D d) (E e) (F f);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompositeKey;
+    use crate::{n, s, AccountName, Name, Symbol, TimePointSec};
+
+    #[test]
+    fn u128_matches_manual_packing() {
+        let proposal = Name::new(n!("myproposal"));
+        let voter = AccountName::new(n!("alice"));
+        assert_eq!(
+            (proposal, voter).to_u128(),
+            u128::from(proposal.as_u64()) << 64 | u128::from(voter.as_u64())
+        );
+        assert_eq!((1_u8, 2_u16, 3_u32).to_u128(), 0x01_0002_0000_0003);
+    }
+
+    #[test]
+    fn round_trip() {
+        let key = (-5_i32, true, Symbol::new(s!(4, "EOS")), 7_u16);
+        assert_eq!(<(i32, bool, Symbol, u16)>::from_u128(key.to_u128()), key);
+
+        let key = (
+            u64::MAX - 1,
+            AccountName::new(n!("bob")),
+            TimePointSec::from_secs(42),
+            -1_i64,
+        );
+        let packed = key.to_checksum256();
+        assert_eq!(
+            <(u64, AccountName, TimePointSec, i64)>::from_checksum256(&packed),
+            key
+        );
+        let [high, _] = packed.words();
+        assert_eq!(high >> 32, u128::from(u64::MAX - 1));
+        assert_eq!(high & 0xffff_ffff, u128::from(n!("bob") >> 32));
+    }
+
+    #[test]
+    fn order_preserving() {
+        let keys = [
+            (i64::MIN, 0_u64),
+            (-1, u64::MAX),
+            (0, 0),
+            (0, 1),
+            (1, 0),
+            (i64::MAX, 0),
+        ];
+        for pair in keys.windows(2) {
+            if let [a, b] = pair {
+                assert!(a.to_u128() < b.to_u128());
+                assert!(a.to_checksum256() < b.to_checksum256());
+            }
+        }
+
+        let low = (Name::new(1), AccountName::new(u64::MAX), u128::MAX);
+        let high = (Name::new(2), AccountName::new(0), 0_u128);
+        assert!(low.to_checksum256() < high.to_checksum256());
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    AccountName, ActionName, Checksum160, Checksum256, Checksum512, Name,
+    PermissionName, ScopeName, Symbol, SymbolCode, TableName, TimePoint,
+    TimePointSec,
+};
+use alloc::{string::String, vec::Vec};
+
+/// A value that can be encoded as a key in the key-value database.
+///
+/// Encoded keys compare as bytes in the same order as the values they
+/// encode, and no encoded key is a prefix of another, so keys can be
+/// concatenated into composite keys that sort by each part in turn.
+///
+/// # Examples
+///
+/// ```
+/// use eosio::{n, AccountName, KvKey};
+/// let low = (AccountName::new(n!("alice")), -1_i32).to_kv_key();
+/// let high = (AccountName::new(n!("alice")), 1_i32).to_kv_key();
+/// assert!(low < high);
+/// assert!("a".to_kv_key() < "ab".to_kv_key());
+/// ```
+pub trait KvKey {
+    /// Appends the encoded key to `bytes`.
+    fn write_kv_key(&self, bytes: &mut Vec<u8>);
+
+    /// Encodes this key.
+    #[inline]
+    #[must_use]
+    fn to_kv_key(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.write_kv_key(&mut bytes);
+        bytes
+    }
+}
+
+impl<T: KvKey + ?Sized> KvKey for &T {
+    #[inline]
+    fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+        (**self).write_kv_key(bytes);
+    }
+}
+
+macro_rules! impl_unsigned_key {
+    ($($t:ty)*) => ($(
+        impl KvKey for $t {
+            #[inline]
+            fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+                bytes.extend_from_slice(&self.to_be_bytes());
+            }
+        }
+    )*)
+}
+
+impl_unsigned_key! { u8 u16 u32 u64 u128 }
+
+macro_rules! impl_signed_key {
+    ($($t:ty, $u:ty)*) => ($(
+        impl KvKey for $t {
+            // flipping the sign bit sorts negative numbers first
+            #[inline]
+            #[allow(clippy::cast_sign_loss)]
+            fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+                let value = (*self as $u) ^ (1 << (<$u>::BITS - 1));
+                bytes.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    )*)
+}
+
+impl_signed_key! {
+    i8, u8
+    i16, u16
+    i32, u32
+    i64, u64
+    i128, u128
+}
+
+macro_rules! impl_float_key {
+    ($($t:ty, $u:ty)*) => ($(
+        impl KvKey for $t {
+            // positive numbers get their sign bit set, negative numbers are
+            // inverted so that larger magnitudes sort first
+            #[inline]
+            fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+                const SIGN: $u = 1 << (<$u>::BITS - 1);
+                let bits = self.to_bits();
+                let value = if bits & SIGN == 0 { bits | SIGN } else { !bits };
+                bytes.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    )*)
+}
+
+impl_float_key! {
+    f32, u32
+    f64, u64
+}
+
+macro_rules! impl_u64_key {
+    ($($t:ty)*) => ($(
+        impl KvKey for $t {
+            #[inline]
+            fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+                self.as_u64().write_kv_key(bytes);
+            }
+        }
+    )*)
+}
+
+impl_u64_key! {
+    Name
+    AccountName
+    ActionName
+    PermissionName
+    ScopeName
+    TableName
+    Symbol
+    SymbolCode
+}
+
+macro_rules! impl_checksum_key {
+    ($($t:ty)*) => ($(
+        impl KvKey for $t {
+            #[inline]
+            fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+                bytes.extend_from_slice(&self.to_bytes());
+            }
+        }
+    )*)
+}
+
+impl_checksum_key! { Checksum160 Checksum256 Checksum512 }
+
+impl KvKey for bool {
+    #[inline]
+    fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+        bytes.push(u8::from(*self));
+    }
+}
+
+impl KvKey for TimePoint {
+    #[inline]
+    fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+        self.as_micros().write_kv_key(bytes);
+    }
+}
+
+impl KvKey for TimePointSec {
+    #[inline]
+    fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+        self.as_secs().write_kv_key(bytes);
+    }
+}
+
+impl KvKey for [u8] {
+    // zero bytes are escaped as `0x00 0x01` and the key ends with
+    // `0x00 0x00`, so that shorter keys sort first
+    #[inline]
+    fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+        for byte in self {
+            bytes.push(*byte);
+            if *byte == 0 {
+                bytes.push(1);
+            }
+        }
+        bytes.extend_from_slice(&[0, 0]);
+    }
+}
+
+impl KvKey for Vec<u8> {
+    #[inline]
+    fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+        self.as_slice().write_kv_key(bytes);
+    }
+}
+
+impl KvKey for str {
+    #[inline]
+    fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+        self.as_bytes().write_kv_key(bytes);
+    }
+}
+
+impl KvKey for String {
+    #[inline]
+    fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+        self.as_bytes().write_kv_key(bytes);
+    }
+}
+
+macro_rules! impl_tuple_key {
+    ($($(($t:ident $var:ident))*;)*) => ($(
+        impl<$($t),*> KvKey for ($($t,)*)
+        where
+            $($t: KvKey),*
+        {
+            #[inline]
+            fn write_kv_key(&self, bytes: &mut Vec<u8>) {
+                let ($($var,)*) = self;
+                $($var.write_kv_key(bytes);)*
+            }
+        }
+    )*)
+}
+
+impl_tuple_key! {
+    (A a) (B b);
+    (A a) (B b) (C c);
+    (A a) (B b) (C c) (D d);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KvKey;
+    use crate::{n, Name};
+
+    fn assert_sorted<T: KvKey>(values: &[T]) {
+        for pair in values.windows(2) {
+            if let [a, b] = pair {
+                assert!(a.to_kv_key() < b.to_kv_key());
+            }
+        }
+    }
+
+    #[test]
+    fn order_preserving() {
+        assert_sorted(&[i64::MIN, -1, 0, 1, i64::MAX]);
+        assert_sorted(&[0_u32, 1, 256, u32::MAX]);
+        assert_sorted(&[f64::NEG_INFINITY, -2.5, -0.0, 0.0, 1e-9, 3.0]);
+        assert_sorted(&["", "\0", "\0\0", "a", "a\0", "ab", "b"]);
+        assert_sorted(&[
+            ("a", Name::new(n!("zed"))),
+            ("ab", Name::new(n!("alice"))),
+            ("b", Name::new(0)),
+        ]);
+        assert_sorted(&[(false, -1_i8), (false, 1), (true, i8::MIN)]);
+    }
+
+    #[test]
+    fn encodings() {
+        assert_eq!(0x0102_u16.to_kv_key(), [1, 2]);
+        assert_eq!((-1_i16).to_kv_key(), [0x7f, 0xff]);
+        assert_eq!(Name::new(n!("a")).to_kv_key(), n!("a").to_be_bytes());
+        assert_eq!("a\0".to_kv_key(), [b'a', 0, 1, 0, 0]);
+    }
+}
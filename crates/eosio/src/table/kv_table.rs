@@ -0,0 +1,153 @@
+use super::{KvKey, TableName};
+use crate::{
+    account::AccountName,
+    bytes::{NumBytes, Read, Write},
+    name::Name,
+};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// The encoded secondary keys of a row.
+pub type KvKeys = Vec<Vec<u8>>;
+
+/// A table in the key-value database.
+///
+/// Rows are stored under `table name, primary index name, primary key`, with
+/// both names as big-endian `u64`s and keys encoded with [`KvKey`]. Each
+/// secondary index stores the primary key of a row under `table name, index
+/// name, secondary key, primary key`.
+pub trait KvTable: Sized {
+    /// The name of the table
+    const NAME: TableName;
+    /// The name of the primary index
+    const PRIMARY_INDEX: Name;
+    /// The names of the secondary indexes
+    const SECONDARY_INDEXES: &'static [Name] = &[];
+    /// The ABI type name of the rows
+    const ROW_ABI_TYPE: &'static str = "";
+    /// The ABI type name of the primary key
+    const PRIMARY_ABI_TYPE: &'static str = "";
+    /// The ABI type names of the secondary keys, in the same order as
+    /// `SECONDARY_INDEXES`
+    const SECONDARY_ABI_TYPES: &'static [&'static str] = &[];
+    /// The type of the rows
+    type Row: Read + Write + NumBytes;
+    /// The type of the primary key
+    type PrimaryKey: KvKey;
+    /// Returns the primary key of a row.
+    fn primary_key(row: &Self::Row) -> Self::PrimaryKey;
+    /// Returns the encoded secondary keys of a row, in the same order as
+    /// `SECONDARY_INDEXES`.
+    #[inline]
+    fn secondary_keys(_row: &Self::Row) -> KvKeys {
+        KvKeys::new()
+    }
+    /// Returns the prefix of the keys stored by an index of this table.
+    #[inline]
+    #[must_use]
+    fn index_prefix(index: Name) -> Vec<u8> {
+        (Self::NAME, index).to_kv_key()
+    }
+    /// Returns the primary index of this table.
+    #[inline]
+    fn kv_table<C>(contract: C) -> KvPrimaryIndex<Self>
+    where
+        C: Into<AccountName>,
+    {
+        KvPrimaryIndex::new(contract)
+    }
+}
+
+/// The primary index of a key-value table.
+#[derive(Copy, Clone, Debug)]
+pub struct KvPrimaryIndex<T>
+where
+    T: KvTable,
+{
+    /// The contract that owns the table
+    pub contract: AccountName,
+    _data: PhantomData<T>,
+}
+
+impl<T> KvPrimaryIndex<T>
+where
+    T: KvTable,
+{
+    /// Creates a primary index for the table of a contract.
+    #[inline]
+    pub fn new<C>(contract: C) -> Self
+    where
+        C: Into<AccountName>,
+    {
+        Self {
+            contract: contract.into(),
+            _data: PhantomData,
+        }
+    }
+
+    /// Returns the prefix of the keys of every row.
+    #[inline]
+    #[must_use]
+    pub fn prefix(&self) -> Vec<u8> {
+        T::index_prefix(T::PRIMARY_INDEX)
+    }
+
+    /// Returns the key a row is stored under.
+    #[inline]
+    #[must_use]
+    pub fn key(&self, primary_key: &T::PrimaryKey) -> Vec<u8> {
+        let mut key = self.prefix();
+        primary_key.write_kv_key(&mut key);
+        key
+    }
+}
+
+/// A secondary index of a key-value table, keyed by `K`.
+#[derive(Copy, Clone, Debug)]
+pub struct KvSecondaryIndex<K, T>
+where
+    K: KvKey,
+    T: KvTable,
+{
+    /// The contract that owns the table
+    pub contract: AccountName,
+    /// The name of the index
+    pub name: Name,
+    _data: PhantomData<(K, T)>,
+}
+
+impl<K, T> KvSecondaryIndex<K, T>
+where
+    K: KvKey,
+    T: KvTable,
+{
+    /// Creates a secondary index for the table of a contract.
+    #[inline]
+    pub fn new<C, N>(contract: C, name: N) -> Self
+    where
+        C: Into<AccountName>,
+        N: Into<Name>,
+    {
+        Self {
+            contract: contract.into(),
+            name: name.into(),
+            _data: PhantomData,
+        }
+    }
+
+    /// Returns the prefix of the keys of every entry.
+    #[inline]
+    #[must_use]
+    pub fn prefix(&self) -> Vec<u8> {
+        T::index_prefix(self.name)
+    }
+
+    /// Returns the prefix of the entries with a secondary key.
+    #[inline]
+    #[must_use]
+    pub fn key(&self, secondary_key: &K) -> Vec<u8> {
+        let mut key = self.prefix();
+        secondary_key.write_kv_key(&mut key);
+        key
+    }
+}
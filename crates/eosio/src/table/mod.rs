@@ -1,3 +1,6 @@
+mod composite_key;
+mod kv_key;
+mod kv_table;
 mod primary_table_index;
 mod secondary_key;
 mod secondary_keys;
@@ -5,11 +8,16 @@ mod secondary_table_index;
 mod secondary_table_name;
 
 pub use self::{
-    primary_table_index::PrimaryTableIndex, secondary_key::SecondaryKey,
-    secondary_keys::SecondaryKeys, secondary_table_index::SecondaryTableIndex,
+    composite_key::{CompositeKey, CompositeKeyPart},
+    kv_key::KvKey,
+    kv_table::{KvKeys, KvPrimaryIndex, KvSecondaryIndex, KvTable},
+    primary_table_index::PrimaryTableIndex,
+    secondary_key::SecondaryKey,
+    secondary_keys::SecondaryKeys,
+    secondary_table_index::SecondaryTableIndex,
     secondary_table_name::SecondaryTableName,
 };
-pub use eosio_macros::Table;
+pub use eosio_macros::{KvTable, Table};
 
 use crate::{
     account::AccountName,
@@ -46,6 +54,10 @@ impl From<SymbolCode> for ScopeName {
 pub trait Table: Sized {
     /// TODO docs
     const NAME: TableName;
+    /// The type of the scopes of the table, set with
+    /// `#[eosio::table("name", scope = Type)]`. Used to describe the table in
+    /// documentation and ABIs.
+    const SCOPE_TYPE: Option<&'static str> = None;
     /// TODO docs
     type Row: Read + Write + NumBytes;
     /// TODO docs
@@ -1,7 +1,7 @@
-use crate::{Checksum160, Checksum256};
+use crate::{Checksum160, Checksum256, Float128};
 
 /// TODO docs
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SecondaryKey {
     /// TODO docs
     U64(u64),
@@ -11,6 +11,8 @@ pub enum SecondaryKey {
     U128(u128),
     /// TODO docs
     H256([u128; 2]),
+    /// TODO docs
+    F128(Float128),
 }
 
 impl From<u64> for SecondaryKey {
@@ -41,6 +43,13 @@ impl From<[u128; 2]> for SecondaryKey {
     }
 }
 
+impl From<Float128> for SecondaryKey {
+    #[inline]
+    fn from(v: Float128) -> Self {
+        Self::F128(v)
+    }
+}
+
 impl From<Checksum256> for SecondaryKey {
     #[must_use]
     fn from(v: Checksum256) -> Self {
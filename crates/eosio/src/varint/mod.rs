@@ -1,4 +1,221 @@
 mod signed;
 mod unsigned;
+mod var_int64;
+mod var_uint64;
 
-pub use self::{signed::SignedInt, unsigned::UnsignedInt};
+pub use self::{
+    signed::SignedInt, unsigned::UnsignedInt, var_int64::VarInt64,
+    var_uint64::VarUint64,
+};
+use crate::bytes::{ReadError, WriteError};
+use crate::{
+    CheckedAdd, CheckedDiv, CheckedMul, CheckedRem, CheckedSub, Read, Write,
+};
+
+/// Returns the number of bytes needed to LEB128 encode `value`.
+const fn leb128_len(mut value: u64) -> usize {
+    let mut len = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+/// Reads an LEB128 encoded integer that must fit in `bits` bits. Encodings
+/// with bits set beyond that width or with redundant trailing zero bytes are
+/// rejected, so every value has exactly one accepted encoding.
+fn read_leb128(
+    bytes: &[u8],
+    pos: &mut usize,
+    bits: u32,
+) -> Result<u64, ReadError> {
+    let mut value = 0_u64;
+    let mut shift = 0_u32;
+    loop {
+        let byte = u8::read(bytes, pos)?;
+        let group = u64::from(byte & 0x7f);
+        let remaining = bits.saturating_sub(shift);
+        if remaining == 0 || (remaining < 7 && group >> remaining != 0) {
+            return Err(ReadError::VarIntOverflow);
+        }
+        value |= group << shift;
+        if byte & 0x80 == 0 {
+            if byte == 0 && shift > 0 {
+                return Err(ReadError::NonCanonicalVarInt);
+            }
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+/// Writes `value` as an LEB128 encoded integer.
+#[allow(clippy::cast_possible_truncation)]
+fn write_leb128(
+    mut value: u64,
+    bytes: &mut [u8],
+    pos: &mut usize,
+) -> Result<(), WriteError> {
+    loop {
+        let mut byte = (value as u8) & 0x7f;
+        value >>= 7;
+        if value > 0 {
+            byte |= 0x80;
+        }
+        byte.write(bytes, pos)?;
+        if value == 0 {
+            return Ok(());
+        }
+    }
+}
+
+macro_rules! impl_checked_ops {
+    ($($ident:ident: $inner:ty)*) => ($(
+        impl CheckedAdd for $ident {
+            type Output = Option<Self>;
+
+            #[inline]
+            fn checked_add(self, other: Self) -> Self::Output {
+                <$inner>::from(self)
+                    .checked_add(<$inner>::from(other))
+                    .map(Self::from)
+            }
+        }
+
+        impl CheckedSub for $ident {
+            type Output = Option<Self>;
+
+            #[inline]
+            fn checked_sub(self, other: Self) -> Self::Output {
+                <$inner>::from(self)
+                    .checked_sub(<$inner>::from(other))
+                    .map(Self::from)
+            }
+        }
+
+        impl CheckedMul for $ident {
+            type Output = Option<Self>;
+
+            #[inline]
+            fn checked_mul(self, other: Self) -> Self::Output {
+                <$inner>::from(self)
+                    .checked_mul(<$inner>::from(other))
+                    .map(Self::from)
+            }
+        }
+
+        impl CheckedDiv for $ident {
+            type Output = Option<Self>;
+
+            #[inline]
+            fn checked_div(self, other: Self) -> Self::Output {
+                <$inner>::from(self)
+                    .checked_div(<$inner>::from(other))
+                    .map(Self::from)
+            }
+        }
+
+        impl CheckedRem for $ident {
+            type Output = Option<Self>;
+
+            #[inline]
+            fn checked_rem(self, other: Self) -> Self::Output {
+                <$inner>::from(self)
+                    .checked_rem(<$inner>::from(other))
+                    .map(Self::from)
+            }
+        }
+    )*)
+}
+
+impl_checked_ops! {
+    UnsignedInt: u32
+    SignedInt: i32
+    VarUint64: u64
+    VarInt64: i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_leb128, write_leb128};
+    use crate::{
+        CheckedAdd, CheckedSub, ReadError, SignedInt, UnsignedInt, VarInt64,
+        VarUint64,
+    };
+
+    fn read(bytes: &[u8], bits: u32) -> Result<u64, ReadError> {
+        let mut pos = 0;
+        let value = read_leb128(bytes, &mut pos, bits)?;
+        assert_eq!(pos, bytes.len());
+        Ok(value)
+    }
+
+    #[test]
+    fn leb128_known_encodings() {
+        for (value, expected) in &[
+            (0_u64, &[0x00][..]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x01]),
+            (300, &[0xac, 0x02]),
+            (u64::from(u32::MAX), &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+        ] {
+            let mut bytes = [0_u8; 10];
+            let mut pos = 0;
+            write_leb128(*value, &mut bytes, &mut pos).unwrap();
+            assert_eq!(bytes.get(..pos), Some(*expected));
+            assert_eq!(read(expected, 32), Ok(*value));
+        }
+    }
+
+    #[test]
+    fn leb128_rejects_overflow() {
+        assert_eq!(
+            read(&[0xff, 0xff, 0xff, 0xff, 0x1f], 32),
+            Err(ReadError::VarIntOverflow)
+        );
+        assert_eq!(
+            read(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00], 32),
+            Err(ReadError::VarIntOverflow)
+        );
+        let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        assert_eq!(read(&max, 64), Ok(u64::MAX));
+        let too_big =
+            [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        assert_eq!(read(&too_big, 64), Err(ReadError::VarIntOverflow));
+    }
+
+    #[test]
+    fn leb128_rejects_non_canonical() {
+        assert_eq!(read(&[0x80, 0x00], 32), Err(ReadError::NonCanonicalVarInt));
+        assert_eq!(
+            read(&[0xff, 0x80, 0x00], 64),
+            Err(ReadError::NonCanonicalVarInt)
+        );
+    }
+
+    #[test]
+    fn leb128_truncated() {
+        assert_eq!(read(&[0x80], 32), Err(ReadError::NotEnoughBytes));
+    }
+
+    #[test]
+    fn checked_ops() {
+        assert_eq!(
+            UnsignedInt::from(u32::MAX).checked_add(UnsignedInt::from(1_u32)),
+            None
+        );
+        assert_eq!(
+            SignedInt::from(-1).checked_sub(SignedInt::from(1)),
+            Some(SignedInt::from(-2))
+        );
+        assert_eq!(
+            VarUint64::from(2_u64).checked_add(VarUint64::from(3_u64)),
+            Some(VarUint64::from(5_u64))
+        );
+        assert_eq!(
+            VarInt64::from(i64::MIN).checked_sub(VarInt64::from(1)),
+            None
+        );
+    }
+}
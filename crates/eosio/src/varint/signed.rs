@@ -1,18 +1,29 @@
+use super::{leb128_len, read_leb128, write_leb128};
 use crate::bytes::{NumBytes, Read, ReadError, Write, WriteError};
+use core::{convert::TryFrom, fmt, num::TryFromIntError};
 
 /// Variable Length Signed Integer. This provides more efficient serialization
 /// of 32-bit signed int. It serializes a 32-bit signed integer in as few bytes
 /// as possible. `SignedInt` is signed and uses
 /// [Zig-Zag encoding](https://developers.google.com/protocol-buffers/docs/encoding#signed-integers)
 /// <https://github.com/EOSIO/eosio.cdt/blob/4985359a30da1f883418b7133593f835927b8046/libraries/eosiolib/core/eosio/varint.hpp#L239-L465>
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Hash, Default)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash, Default)]
 pub struct SignedInt(i32);
 
-impl From<isize> for SignedInt {
-    #[must_use]
-    #[allow(clippy::cast_possible_truncation)]
-    fn from(v: isize) -> Self {
-        Self(v as i32)
+impl SignedInt {
+    #[inline]
+    #[allow(clippy::cast_sign_loss)]
+    const fn zigzag(self) -> u32 {
+        ((self.0 << 1) ^ (self.0 >> 31)) as u32
+    }
+}
+
+impl TryFrom<isize> for SignedInt {
+    type Error = TryFromIntError;
+
+    #[inline]
+    fn try_from(v: isize) -> Result<Self, Self::Error> {
+        i32::try_from(v).map(Self)
     }
 }
 
@@ -51,21 +62,18 @@ impl From<i8> for SignedInt {
     }
 }
 
+impl fmt::Display for SignedInt {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
 impl NumBytes for SignedInt {
     #[inline]
     #[must_use]
-    #[allow(clippy::cast_sign_loss)]
     fn num_bytes(&self) -> usize {
-        let mut val = ((self.0 << 1) ^ (self.0 >> 31)) as u32;
-        let mut bytes = 0_usize;
-        loop {
-            val >>= 7;
-            bytes += 1;
-            if val == 0 {
-                break;
-            }
-        }
-        bytes
+        leb128_len(self.zigzag().into())
     }
 }
 
@@ -73,40 +81,19 @@ impl Read for SignedInt {
     #[inline]
     #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let mut v = 0_u32;
-        let mut by = 0_u32;
-        loop {
-            let b = u8::read(bytes, pos)?;
-            v |= u32::from(b & 0x7f) << by;
-            by += 7;
-            if b & 0x80 == 0 {
-                break;
-            }
-        }
-        let value = (v >> 1) ^ ((u64::from(!(v & 1)) + 1_u64) as u32);
-        Ok(Self(value as i32))
+        let v = read_leb128(bytes, pos, 32)? as u32;
+        Ok(Self(((v >> 1) as i32) ^ -((v & 1) as i32)))
     }
 }
 
 impl Write for SignedInt {
     #[inline]
-    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     fn write(
         &self,
         bytes: &mut [u8],
         pos: &mut usize,
     ) -> Result<(), WriteError> {
-        let mut val = ((self.0 << 1) ^ (self.0 >> 31)) as u32;
-        loop {
-            let mut b = (val as u8) & 0x7f;
-            val >>= 7;
-            b |= ((val > 0) as u8) << 7;
-            b.write(bytes, pos)?;
-            if val == 0 {
-                break;
-            }
-        }
-        Ok(())
+        write_leb128(self.zigzag().into(), bytes, pos)
     }
 }
 
@@ -149,4 +136,11 @@ mod signed_int_tests {
         read_write_i32_zero, 0_i32, 1
         read_write_i32_max, i32::max_value(), 5
     }
+
+    #[test]
+    fn zigzag_encoding() {
+        for (value, expected) in &[(0, 0_u8), (-1, 1), (1, 2), (-2, 3)] {
+            assert_eq!(SignedInt::from(*value).pack().unwrap(), [*expected]);
+        }
+    }
 }
@@ -1,18 +1,21 @@
+use super::{leb128_len, read_leb128, write_leb128};
 use crate::bytes::{NumBytes, Read, ReadError, Write, WriteError};
+use core::{convert::TryFrom, fmt, num::TryFromIntError};
 
 /// Variable Length Unsigned Integer. This provides more efficient
 /// serialization of 32-bit unsigned int. It serialuzes a 32-bit unsigned
 /// integer in as few bytes as possible. `UnsignedInt` is unsigned and uses
 /// [VLQ or Base-128 encoding](https://en.wikipedia.org/wiki/Variable-length_quantity)
 /// <https://github.com/EOSIO/eosio.cdt/blob/4985359a30da1f883418b7133593f835927b8046/libraries/eosiolib/core/eosio/varint.hpp#L15-L237>
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Hash, Default)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash, Default)]
 pub struct UnsignedInt(u32);
 
-impl From<usize> for UnsignedInt {
-    #[must_use]
-    #[allow(clippy::cast_possible_truncation)]
-    fn from(v: usize) -> Self {
-        Self(v as u32)
+impl TryFrom<usize> for UnsignedInt {
+    type Error = TryFromIntError;
+
+    #[inline]
+    fn try_from(v: usize) -> Result<Self, Self::Error> {
+        u32::try_from(v).map(Self)
     }
 }
 
@@ -30,6 +33,12 @@ impl From<u32> for UnsignedInt {
     }
 }
 
+impl From<UnsignedInt> for u32 {
+    fn from(v: UnsignedInt) -> Self {
+        v.0
+    }
+}
+
 impl From<u16> for UnsignedInt {
     #[must_use]
     fn from(v: u16) -> Self {
@@ -44,20 +53,18 @@ impl From<u8> for UnsignedInt {
     }
 }
 
+impl fmt::Display for UnsignedInt {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
 impl NumBytes for UnsignedInt {
     #[inline]
     #[must_use]
     fn num_bytes(&self) -> usize {
-        let mut val = u64::from(self.0);
-        let mut bytes = 0_usize;
-        loop {
-            val >>= 7;
-            bytes += 1;
-            if val == 0 {
-                break;
-            }
-        }
-        bytes
+        leb128_len(self.0.into())
     }
 }
 
@@ -65,39 +72,18 @@ impl Read for UnsignedInt {
     #[inline]
     #[allow(clippy::cast_possible_truncation)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let mut v = 0_u64;
-        let mut by = 0_u8;
-        loop {
-            let b = u8::read(bytes, pos)?;
-            v |= u64::from(u32::from(b & 0x7f) << by);
-            by += 7;
-            if b & 0x80 == 0 {
-                break;
-            }
-        }
-        Ok(Self(v as u32))
+        read_leb128(bytes, pos, 32).map(|v| Self(v as u32))
     }
 }
 
 impl Write for UnsignedInt {
     #[inline]
-    #[allow(clippy::cast_possible_truncation)]
     fn write(
         &self,
         bytes: &mut [u8],
         pos: &mut usize,
     ) -> Result<(), WriteError> {
-        let mut val = u64::from(self.0);
-        loop {
-            let mut b = (val as u8) & 0x7f;
-            val >>= 7;
-            b |= ((val > 0) as u8) << 7;
-            b.write(bytes, pos)?;
-            if val == 0 {
-                break;
-            }
-        }
-        Ok(())
+        write_leb128(self.0.into(), bytes, pos)
     }
 }
 
@@ -105,6 +91,7 @@ impl Write for UnsignedInt {
 mod unsigned_int_tests {
     use super::UnsignedInt;
     use crate::bytes::{NumBytes, Read, Write};
+    use core::convert::TryFrom;
 
     macro_rules! write_read_tests {
         ($($i:ident, $v:expr, $n:expr)*) => ($(
@@ -135,4 +122,13 @@ mod unsigned_int_tests {
         read_write_u32_min, u32::min_value(), 1
         read_write_u32_max, u32::max_value(), 5
     }
+
+    #[test]
+    fn try_from_usize() {
+        assert_eq!(
+            UnsignedInt::try_from(7_usize),
+            Ok(UnsignedInt::from(7_u32))
+        );
+        assert!(UnsignedInt::try_from(usize::MAX).is_err());
+    }
 }
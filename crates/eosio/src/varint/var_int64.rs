@@ -0,0 +1,140 @@
+use super::{leb128_len, read_leb128, write_leb128, SignedInt};
+use crate::bytes::{NumBytes, Read, ReadError, Write, WriteError};
+use core::{convert::TryFrom, fmt, num::TryFromIntError};
+
+/// Variable Length Signed Integer. Like `SignedInt`, but holds values up to
+/// 64 bits wide, using up to 10 bytes of zig-zag encoding.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash, Default)]
+pub struct VarInt64(i64);
+
+impl VarInt64 {
+    #[inline]
+    #[allow(clippy::cast_sign_loss)]
+    const fn zigzag(self) -> u64 {
+        ((self.0 << 1) ^ (self.0 >> 63)) as u64
+    }
+}
+
+impl TryFrom<isize> for VarInt64 {
+    type Error = TryFromIntError;
+
+    #[inline]
+    fn try_from(v: isize) -> Result<Self, Self::Error> {
+        i64::try_from(v).map(Self)
+    }
+}
+
+impl From<i64> for VarInt64 {
+    fn from(v: i64) -> Self {
+        Self(v)
+    }
+}
+
+impl From<VarInt64> for i64 {
+    fn from(v: VarInt64) -> Self {
+        v.0
+    }
+}
+
+impl From<i32> for VarInt64 {
+    fn from(v: i32) -> Self {
+        Self(v.into())
+    }
+}
+
+impl From<SignedInt> for VarInt64 {
+    fn from(v: SignedInt) -> Self {
+        Self(i32::from(v).into())
+    }
+}
+
+impl TryFrom<VarInt64> for SignedInt {
+    type Error = TryFromIntError;
+
+    #[inline]
+    fn try_from(v: VarInt64) -> Result<Self, Self::Error> {
+        i32::try_from(v.0).map(Self::from)
+    }
+}
+
+impl fmt::Display for VarInt64 {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl NumBytes for VarInt64 {
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        leb128_len(self.zigzag())
+    }
+}
+
+impl Read for VarInt64 {
+    #[inline]
+    #[allow(clippy::cast_possible_wrap)]
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let v = read_leb128(bytes, pos, 64)?;
+        Ok(Self(((v >> 1) as i64) ^ -((v & 1) as i64)))
+    }
+}
+
+impl Write for VarInt64 {
+    #[inline]
+    fn write(
+        &self,
+        bytes: &mut [u8],
+        pos: &mut usize,
+    ) -> Result<(), WriteError> {
+        write_leb128(self.zigzag(), bytes, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VarInt64;
+    use crate::{
+        bytes::{NumBytes, Read, Write},
+        SignedInt,
+    };
+    use alloc::string::ToString;
+    use core::convert::TryFrom;
+
+    macro_rules! write_read_tests {
+        ($($i:ident, $v:expr, $n:expr)*) => ($(
+            #[test]
+            fn $i() {
+                let mut bytes = [0_u8; 10];
+                let mut write_pos = 0;
+                let varint: VarInt64 = $v.into();
+                assert_eq!(varint.num_bytes(), $n);
+
+                varint.write(&mut bytes, &mut write_pos).unwrap();
+                assert_eq!(write_pos, $n);
+                let mut read_pos = 0;
+                let result = VarInt64::read(&bytes, &mut read_pos).unwrap();
+                assert_eq!(result, varint);
+                assert_eq!(read_pos, write_pos);
+            }
+        )*)
+    }
+
+    write_read_tests! {
+        read_write_1_neg, -1_i64, 1
+        read_write_i32_min, i32::MIN, 5
+        read_write_i64_min, i64::MIN, 10
+        read_write_i64_zero, 0_i64, 1
+        read_write_i64_max, i64::MAX, 10
+    }
+
+    #[test]
+    fn conversions() {
+        assert!(SignedInt::try_from(VarInt64::from(i64::MIN)).is_err());
+        assert_eq!(
+            SignedInt::try_from(VarInt64::from(-5)),
+            Ok(SignedInt::from(-5))
+        );
+        assert_eq!(VarInt64::from(-42).to_string(), "-42");
+    }
+}
@@ -0,0 +1,139 @@
+use super::{leb128_len, read_leb128, write_leb128, UnsignedInt};
+use crate::bytes::{NumBytes, Read, ReadError, Write, WriteError};
+use core::{convert::TryFrom, fmt, num::TryFromIntError};
+
+/// Variable Length Unsigned Integer. Like `UnsignedInt`, but holds values up
+/// to 64 bits wide, using up to 10 bytes.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Hash, Default)]
+pub struct VarUint64(u64);
+
+impl TryFrom<usize> for VarUint64 {
+    type Error = TryFromIntError;
+
+    #[inline]
+    fn try_from(v: usize) -> Result<Self, Self::Error> {
+        u64::try_from(v).map(Self)
+    }
+}
+
+impl TryFrom<VarUint64> for usize {
+    type Error = TryFromIntError;
+
+    #[inline]
+    fn try_from(v: VarUint64) -> Result<Self, Self::Error> {
+        Self::try_from(v.0)
+    }
+}
+
+impl From<u64> for VarUint64 {
+    fn from(v: u64) -> Self {
+        Self(v)
+    }
+}
+
+impl From<VarUint64> for u64 {
+    fn from(v: VarUint64) -> Self {
+        v.0
+    }
+}
+
+impl From<u32> for VarUint64 {
+    fn from(v: u32) -> Self {
+        Self(v.into())
+    }
+}
+
+impl From<UnsignedInt> for VarUint64 {
+    fn from(v: UnsignedInt) -> Self {
+        Self(u32::from(v).into())
+    }
+}
+
+impl TryFrom<VarUint64> for UnsignedInt {
+    type Error = TryFromIntError;
+
+    #[inline]
+    fn try_from(v: VarUint64) -> Result<Self, Self::Error> {
+        u32::try_from(v.0).map(Self::from)
+    }
+}
+
+impl fmt::Display for VarUint64 {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl NumBytes for VarUint64 {
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        leb128_len(self.0)
+    }
+}
+
+impl Read for VarUint64 {
+    #[inline]
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        read_leb128(bytes, pos, 64).map(Self)
+    }
+}
+
+impl Write for VarUint64 {
+    #[inline]
+    fn write(
+        &self,
+        bytes: &mut [u8],
+        pos: &mut usize,
+    ) -> Result<(), WriteError> {
+        write_leb128(self.0, bytes, pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VarUint64;
+    use crate::{
+        bytes::{NumBytes, Read, Write},
+        UnsignedInt,
+    };
+    use alloc::string::ToString;
+    use core::convert::TryFrom;
+
+    macro_rules! write_read_tests {
+        ($($i:ident, $v:expr, $n:expr)*) => ($(
+            #[test]
+            fn $i() {
+                let mut bytes = [0_u8; 10];
+                let mut write_pos = 0;
+                let varint: VarUint64 = $v.into();
+                assert_eq!(varint.num_bytes(), $n);
+
+                varint.write(&mut bytes, &mut write_pos).unwrap();
+                assert_eq!(write_pos, $n);
+                let mut read_pos = 0;
+                let result = VarUint64::read(&bytes, &mut read_pos).unwrap();
+                assert_eq!(result, varint);
+                assert_eq!(read_pos, write_pos);
+            }
+        )*)
+    }
+
+    write_read_tests! {
+        read_write_u32_max, u32::MAX, 5
+        read_write_u64_min, u64::MIN, 1
+        read_write_u56_max, 0x00ff_ffff_ffff_ffff_u64, 8
+        read_write_u64_max, u64::MAX, 10
+    }
+
+    #[test]
+    fn conversions() {
+        let big = VarUint64::from(u64::from(u32::MAX) + 1);
+        assert!(UnsignedInt::try_from(big).is_err());
+        assert_eq!(
+            UnsignedInt::try_from(VarUint64::from(5_u32)),
+            Ok(UnsignedInt::from(5_u32))
+        );
+        assert_eq!(big.to_string(), "4294967296");
+    }
+}
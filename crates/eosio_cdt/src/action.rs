@@ -48,6 +48,24 @@ pub fn send_context_free_inline_action(
     Ok(())
 }
 
+/// Sets the value returned by the current action. The value is packed and
+/// included in the action trace.
+///
+/// # Errors
+///
+/// Will return `Err` if there was an issue serializing the value.
+#[inline]
+pub fn set_action_return_value<T>(value: &T) -> Result<(), WriteError>
+where
+    T: Write + NumBytes,
+{
+    let mut bytes = value.pack()?;
+    let ptr: *mut eosio_cdt_sys::c_void =
+        bytes[..].as_mut_ptr() as *mut eosio_cdt_sys::c_void;
+    unsafe { eosio_cdt_sys::set_action_return_value(ptr, bytes.len()) }
+    Ok(())
+}
+
 /// Sends a deferred transaction
 ///
 /// # Errors
@@ -1,3 +1,5 @@
+use eosio::ContractError;
+
 /// Aborts processing of this action and unwinds all pending changes if the test
 /// condition is true
 #[inline]
@@ -22,6 +24,17 @@ where
     }
 }
 
+/// Aborts processing of this action with the code of `error`, or with its
+/// message when the `verbose` feature is enabled
+#[inline]
+pub fn abort_with_error<E: ContractError>(error: &E) -> ! {
+    #[cfg(feature = "verbose")]
+    check(false, error.error_message());
+    #[cfg(not(feature = "verbose"))]
+    check_code(false, error.error_code());
+    unreachable!();
+}
+
 pub trait Check {
     type Output;
     fn check(self, msg: &str) -> Self::Output;
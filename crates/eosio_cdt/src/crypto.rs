@@ -1,6 +1,48 @@
-use eosio::{Checksum160, Checksum256, Checksum512};
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt};
+use eosio::{
+    Checksum160, Checksum256, Checksum512, NumBytes, PublicKey, Read,
+    Signature, Write,
+};
 use eosio_cdt_sys::{capi_checksum160, capi_checksum256, capi_checksum512};
 
+/// Error that can be returned by the crypto primitives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CryptoError {
+    /// An input was rejected, e.g. a point that is not on the curve or a
+    /// signature that no key can be recovered from
+    InvalidInput,
+    /// The modulus passed to `mod_exp` was empty
+    EmptyModulus,
+}
+
+impl fmt::Display for CryptoError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidInput => write!(f, "invalid crypto primitive input"),
+            Self::EmptyModulus => write!(f, "modulus must not be empty"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CryptoError {}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn len_u32(bytes: &[u8]) -> u32 {
+    bytes.len() as u32
+}
+
+/// Turns the return code of a crypto primitive into a result.
+const fn check_input(code: i32) -> Result<(), CryptoError> {
+    if code == 0 {
+        Ok(())
+    } else {
+        Err(CryptoError::InvalidInput)
+    }
+}
+
 /// Hashes `data` using RIPEMD160.
 #[must_use]
 #[inline]
@@ -142,5 +184,501 @@ where
     unsafe { eosio_cdt_sys::assert_sha512(data_ptr, data_len, c_hash_ptr) }
 }
 
-// TODO recover_key
-// TODO assert_recover_key
+/// Packs a key or signature into a buffer of exactly its size.
+fn pack_key<T: Write + NumBytes>(value: &T) -> Vec<u8> {
+    let mut bytes = vec![0_u8; value.num_bytes()];
+    value.write(&mut bytes, &mut 0).expect("write");
+    bytes
+}
+
+/// Recovers the packed public key that produced a packed signature over
+/// `digest`. Keys of any type are supported, since the buffer is resized to
+/// the length reported by the host.
+#[must_use]
+#[inline]
+pub fn recover_key_bytes<C, S>(digest: C, signature: S) -> Vec<u8>
+where
+    C: AsRef<Checksum256>,
+    S: AsRef<[u8]>,
+{
+    let c_digest = capi_checksum256 {
+        hash: digest.as_ref().to_bytes(),
+    };
+    let signature = signature.as_ref();
+    // K1 and R1 keys are 34 bytes when packed
+    let mut key = vec![0_u8; PublicKey::default().num_bytes()];
+    loop {
+        let size = unsafe {
+            eosio_cdt_sys::recover_key(
+                &c_digest,
+                signature.as_ptr().cast(),
+                signature.len(),
+                key.as_mut_ptr().cast(),
+                key.len(),
+            )
+        };
+        let size = usize::try_from(size).unwrap_or_default();
+        if size <= key.len() {
+            key.truncate(size);
+            return key;
+        }
+        key.resize(size, 0);
+    }
+}
+
+/// Recovers the public key that produced `signature` over `digest`.
+///
+/// Returns `None` if the recovered key can't be represented as a
+/// `PublicKey`, e.g. for WebAuthn keys. Use `recover_key_bytes` for those.
+#[must_use]
+#[inline]
+pub fn recover_key<C: AsRef<Checksum256>>(
+    digest: C,
+    signature: &Signature,
+) -> Option<PublicKey> {
+    let key = recover_key_bytes(digest, pack_key(signature));
+    let mut pos = 0;
+    let public_key = PublicKey::read(&key, &mut pos).ok()?;
+    if pos == key.len() {
+        Some(public_key)
+    } else {
+        None
+    }
+}
+
+/// Tests if the public key recovered from `signature` and `digest` matches
+/// `public_key`, aborting the action if it doesn't.
+#[inline]
+pub fn assert_recover_key<C: AsRef<Checksum256>>(
+    digest: C,
+    signature: &Signature,
+    public_key: &PublicKey,
+) {
+    let c_digest = capi_checksum256 {
+        hash: digest.as_ref().to_bytes(),
+    };
+    let signature = pack_key(signature);
+    let public_key = pack_key(public_key);
+    unsafe {
+        eosio_cdt_sys::assert_recover_key(
+            &c_digest,
+            signature.as_ptr().cast(),
+            signature.len(),
+            public_key.as_ptr().cast(),
+            public_key.len(),
+        )
+    }
+}
+
+fn sha3_hash(data: &[u8], keccak: bool) -> Checksum256 {
+    let mut hash = [0_u8; 32];
+    unsafe {
+        eosio_cdt_sys::sha3(
+            data.as_ptr(),
+            len_u32(data),
+            hash.as_mut_ptr(),
+            len_u32(&hash),
+            keccak.into(),
+        )
+    }
+    hash.into()
+}
+
+/// Hashes `data` using SHA3-256.
+#[must_use]
+#[inline]
+pub fn sha3<T: AsRef<[u8]>>(data: T) -> Checksum256 {
+    sha3_hash(data.as_ref(), false)
+}
+
+/// Hashes `data` using Keccak-256, as used by Ethereum.
+#[must_use]
+#[inline]
+pub fn keccak<T: AsRef<[u8]>>(data: T) -> Checksum256 {
+    sha3_hash(data.as_ref(), true)
+}
+
+/// Recovers the uncompressed secp256k1 public key that produced an
+/// Ethereum-style signature over `digest`. The signature is `v, r, s`, with
+/// `v` from 27 to 34.
+///
+/// # Errors
+///
+/// Will return `Err` if no key can be recovered from the signature.
+#[inline]
+pub fn k1_recover<C: AsRef<Checksum256>>(
+    digest: C,
+    signature: &[u8; 65],
+) -> Result<[u8; 65], CryptoError> {
+    let digest = digest.as_ref().to_bytes();
+    let mut key = [0_u8; 65];
+    check_input(unsafe {
+        eosio_cdt_sys::k1_recover(
+            signature.as_ptr(),
+            len_u32(signature),
+            digest.as_ptr(),
+            len_u32(&digest),
+            key.as_mut_ptr(),
+            len_u32(&key),
+        )
+    })?;
+    Ok(key)
+}
+
+/// Adds two alt_bn128 G1 points, each encoded as big-endian `x, y`.
+///
+/// # Errors
+///
+/// Will return `Err` if a point is not on the curve.
+#[inline]
+pub fn alt_bn128_add(
+    a: &[u8; 64],
+    b: &[u8; 64],
+) -> Result<[u8; 64], CryptoError> {
+    let mut sum = [0_u8; 64];
+    check_input(unsafe {
+        eosio_cdt_sys::alt_bn128_add(
+            a.as_ptr(),
+            len_u32(a),
+            b.as_ptr(),
+            len_u32(b),
+            sum.as_mut_ptr(),
+            len_u32(&sum),
+        )
+    })?;
+    Ok(sum)
+}
+
+/// Multiplies an alt_bn128 G1 point by a big-endian scalar.
+///
+/// # Errors
+///
+/// Will return `Err` if the point is not on the curve.
+#[inline]
+pub fn alt_bn128_mul(
+    point: &[u8; 64],
+    scalar: &[u8; 32],
+) -> Result<[u8; 64], CryptoError> {
+    let mut product = [0_u8; 64];
+    check_input(unsafe {
+        eosio_cdt_sys::alt_bn128_mul(
+            point.as_ptr(),
+            len_u32(point),
+            scalar.as_ptr(),
+            len_u32(scalar),
+            product.as_mut_ptr(),
+            len_u32(&product),
+        )
+    })?;
+    Ok(product)
+}
+
+/// Checks that the product of the pairings of alt_bn128 `(G1, G2)` pairs is
+/// one. G2 points are encoded as big-endian `x.1, x.0, y.1, y.0`, like the
+/// Ethereum precompile.
+///
+/// # Errors
+///
+/// Will return `Err` if a point is not on its curve or not in the right
+/// subgroup.
+#[inline]
+pub fn alt_bn128_pair(
+    pairs: &[([u8; 64], [u8; 128])],
+) -> Result<bool, CryptoError> {
+    let mut bytes = Vec::with_capacity(pairs.len() * 192);
+    for (g1, g2) in pairs {
+        bytes.extend_from_slice(g1);
+        bytes.extend_from_slice(g2);
+    }
+    match unsafe {
+        eosio_cdt_sys::alt_bn128_pair(bytes.as_ptr(), len_u32(&bytes))
+    } {
+        0 => Ok(true),
+        1 => Ok(false),
+        _ => Err(CryptoError::InvalidInput),
+    }
+}
+
+/// Runs `rounds` rounds of the BLAKE2b compression function `F` as specified
+/// by EIP-152, returning the new state. Words are little-endian.
+///
+/// # Errors
+///
+/// Will return `Err` if the host rejects the input.
+#[inline]
+pub fn blake2_f(
+    rounds: u32,
+    state: &[u8; 64],
+    message: &[u8; 128],
+    offset: [u64; 2],
+    final_block: bool,
+) -> Result<[u8; 64], CryptoError> {
+    let [t0, t1] = offset;
+    let (t0, t1) = (t0.to_le_bytes(), t1.to_le_bytes());
+    let mut result = [0_u8; 64];
+    check_input(unsafe {
+        eosio_cdt_sys::blake2_f(
+            rounds,
+            state.as_ptr(),
+            len_u32(state),
+            message.as_ptr(),
+            len_u32(message),
+            t0.as_ptr(),
+            len_u32(&t0),
+            t1.as_ptr(),
+            len_u32(&t1),
+            final_block.into(),
+            result.as_mut_ptr(),
+            len_u32(&result),
+        )
+    })?;
+    Ok(result)
+}
+
+/// Computes `base ^ exponent % modulus` for big-endian integers. The result
+/// has the same length as the modulus.
+///
+/// # Errors
+///
+/// Will return `Err` if the modulus is empty.
+#[inline]
+pub fn mod_exp(
+    base: &[u8],
+    exponent: &[u8],
+    modulus: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    if modulus.is_empty() {
+        return Err(CryptoError::EmptyModulus);
+    }
+    let mut result = vec![0_u8; modulus.len()];
+    check_input(unsafe {
+        eosio_cdt_sys::mod_exp(
+            base.as_ptr(),
+            len_u32(base),
+            exponent.as_ptr(),
+            len_u32(exponent),
+            modulus.as_ptr(),
+            len_u32(modulus),
+            result.as_mut_ptr(),
+            len_u32(&result),
+        )
+    })?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(s.get(i..i + 2).unwrap(), 16).unwrap())
+            .collect()
+    }
+
+    fn array<const N: usize>(s: &str) -> [u8; N] {
+        let mut bytes = [0_u8; N];
+        bytes.copy_from_slice(&hex(s));
+        bytes
+    }
+
+    /// Lays out a recoverable signature as `v, r, s`.
+    fn recoverable(v: u8, signature: &libsecp256k1::Signature) -> [u8; 65] {
+        let mut data = [0_u8; 65];
+        let (head, rs) = data.split_at_mut(1);
+        head.copy_from_slice(&[v]);
+        rs.copy_from_slice(&signature.serialize());
+        data
+    }
+
+    const G1: &str = "0000000000000000000000000000000000000000000000000000000000000001\
+                      0000000000000000000000000000000000000000000000000000000000000002";
+    const NEG_G1: &str = "0000000000000000000000000000000000000000000000000000000000000001\
+                          30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45";
+    const TWO_G1: &str = "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3\
+                          15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4";
+    const G2: &str = "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+                      1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+                      090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+                      12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
+    const TWO_G2: &str = "203e205db4f19b37b60121b83a7333706db86431c6d835849957ed8c3928ad79\
+                          27dc7234fd11d3e8c36c59277c3e6f149d5cd3cfa9a62aee49f8130962b4b3b9\
+                          195e8aa5b7827463722b8c153931579d3505566b4edf48d498e185f0509de152\
+                          04bb53b8977e5f92a0bc372742c4830944a59b4fe6b1c0466e2a6dad122b5d2e";
+
+    #[test]
+    fn hashes() {
+        assert_eq!(
+            sha256("abc").to_bytes().to_vec(),
+            hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            sha1("abc").to_bytes().to_vec(),
+            hex("a9993e364706816aba3e25717850c26c9cd0d89d")
+        );
+        assert_eq!(
+            sha512("abc").to_bytes().to_vec(),
+            hex("ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+                 2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f")
+        );
+        assert_eq!(
+            ripemd160("abc").to_bytes().to_vec(),
+            hex("8eb208f7e05d987a9b044a8e98c6b087f15a0bfc")
+        );
+    }
+
+    #[test]
+    fn sha3_and_keccak() {
+        assert_eq!(
+            sha3("").to_bytes().to_vec(),
+            hex("a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a")
+        );
+        assert_eq!(
+            sha3([b'a'; 200]).to_bytes().to_vec(),
+            hex("cce34485baf2bf2aca99b94833892a4f52896d3d153f7b840cc4f9fe695f1387")
+        );
+        assert_eq!(
+            keccak("").to_bytes().to_vec(),
+            hex("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+    }
+
+    #[test]
+    fn recovers_k1_keys() {
+        let secret = libsecp256k1::SecretKey::parse(&[7; 32]).unwrap();
+        let digest = keccak("voucher");
+        let message = libsecp256k1::Message::parse(&digest.to_bytes());
+        let (signature, recovery_id) = libsecp256k1::sign(&message, &secret);
+        let sig = recoverable(27 + recovery_id.serialize(), &signature);
+        let expected = libsecp256k1::PublicKey::from_secret_key(&secret);
+        assert_eq!(
+            k1_recover(digest, &sig).unwrap().to_vec(),
+            expected.serialize().to_vec()
+        );
+        let sig = recoverable(26, &signature);
+        assert_eq!(k1_recover(digest, &sig), Err(CryptoError::InvalidInput));
+    }
+
+    /// Signs `digest` with `secret`, returning an EOSIO K1 signature and the
+    /// matching public key.
+    fn sign_k1(
+        secret: &[u8; 32],
+        digest: &Checksum256,
+    ) -> (Signature, PublicKey) {
+        let secret = libsecp256k1::SecretKey::parse(secret).unwrap();
+        let message = libsecp256k1::Message::parse(&digest.to_bytes());
+        let (signature, recovery_id) = libsecp256k1::sign(&message, &secret);
+        let data = recoverable(27 + 4 + recovery_id.serialize(), &signature);
+        let public_key = PublicKey {
+            data: libsecp256k1::PublicKey::from_secret_key(&secret)
+                .serialize_compressed(),
+            ..PublicKey::default()
+        };
+        (
+            Signature {
+                data,
+                ..Signature::default()
+            },
+            public_key,
+        )
+    }
+
+    #[test]
+    fn recovers_keys() {
+        let digest = sha256("transfer");
+        let (signature, public_key) = sign_k1(&[7; 32], &digest);
+        assert_eq!(recover_key(digest, &signature), Some(public_key.clone()));
+        assert_eq!(
+            recover_key_bytes(digest, pack_key(&signature)),
+            pack_key(&public_key)
+        );
+        assert_ne!(
+            recover_key(sha256("other"), &signature),
+            Some(public_key.clone())
+        );
+
+        let host = crate::mock::MockHost::new();
+        assert_eq!(
+            host.call(|| assert_recover_key(digest, &signature, &public_key)),
+            Ok(())
+        );
+        let (_, other_key) = sign_k1(&[8; 32], &digest);
+        assert_eq!(
+            host.call(|| assert_recover_key(digest, &signature, &other_key)),
+            Err("Error expected key different than recovered key".into())
+        );
+    }
+
+    #[test]
+    fn alt_bn128_arithmetic() {
+        let g1 = array(G1);
+        let two: [u8; 64] = array(TWO_G1);
+        assert_eq!(alt_bn128_add(&g1, &g1).unwrap().to_vec(), two.to_vec());
+        let scalar = array(
+            "0000000000000000000000000000000000000000000000000000000000000002",
+        );
+        assert_eq!(alt_bn128_mul(&g1, &scalar).unwrap().to_vec(), two.to_vec());
+        let sum = alt_bn128_add(&g1, &array(NEG_G1)).unwrap();
+        assert_eq!(sum.to_vec(), vec![0; 64]);
+        let mut invalid = g1;
+        if let Some(y) = invalid.last_mut() {
+            *y = 1;
+        }
+        assert_eq!(
+            alt_bn128_add(&invalid, &g1),
+            Err(CryptoError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn alt_bn128_pairing() {
+        let (g1, neg_g1, g2) = (array(G1), array(NEG_G1), array(G2));
+        assert_eq!(alt_bn128_pair(&[]), Ok(true));
+        assert_eq!(alt_bn128_pair(&[(g1, g2)]), Ok(false));
+        assert_eq!(alt_bn128_pair(&[(g1, g2), (neg_g1, g2)]), Ok(true));
+        // e(2P, Q) = e(P, 2Q)
+        assert_eq!(
+            alt_bn128_pair(&[(array(TWO_G1), g2), (neg_g1, array(TWO_G2))]),
+            Ok(true)
+        );
+        let mut invalid = g2;
+        if let Some(byte) = invalid.last_mut() {
+            *byte ^= 1;
+        }
+        assert_eq!(
+            alt_bn128_pair(&[(g1, invalid)]),
+            Err(CryptoError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn blake2_compression() {
+        // EIP-152 test vector 5
+        let state = array(
+            "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+             d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+        );
+        let mut message = [0_u8; 128];
+        message.iter_mut().zip(b"abc").for_each(|(m, c)| *m = *c);
+        let result = blake2_f(12, &state, &message, [3, 0], true).unwrap();
+        assert_eq!(
+            result.to_vec(),
+            hex("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+                 7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923")
+        );
+    }
+
+    #[test]
+    fn modular_exponentiation() {
+        let modulus = hex(
+            "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed",
+        );
+        assert_eq!(
+            mod_exp(&[3], &[200], &modulus).unwrap(),
+            hex("421a937a76f3432ffd73d97e447606b683ecf6f6e4a7ae2715acec0ff9232c28")
+        );
+        assert_eq!(mod_exp(&[3], &[1], &[0, 0]).unwrap(), vec![0, 0]);
+        assert_eq!(mod_exp(&[3], &[1], &[]), Err(CryptoError::EmptyModulus));
+    }
+}
@@ -0,0 +1,395 @@
+use crate::{Check, TableError};
+use alloc::vec::Vec;
+use core::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    ptr::null_mut,
+};
+use eosio::{
+    AccountName, KvKey, KvPrimaryIndex, KvSecondaryIndex, KvTable, Read,
+    ReadError, Write,
+};
+use eosio_cdt_sys::{
+    kv_erase, kv_get, kv_get_data, kv_it_compare, kv_it_create, kv_it_destroy,
+    kv_it_lower_bound, kv_it_next, kv_it_prev, kv_it_value, kv_set,
+};
+
+#[allow(clippy::cast_possible_truncation)]
+const fn len_u32(bytes: &[u8]) -> u32 {
+    bytes.len() as u32
+}
+
+/// Returns the value stored under `key`.
+fn get_value(contract: AccountName, key: &[u8]) -> Option<Vec<u8>> {
+    let mut size = 0;
+    let found = unsafe {
+        kv_get(contract.as_u64(), key.as_ptr(), len_u32(key), &mut size)
+    };
+    if !found {
+        return None;
+    }
+    let mut value = vec![0_u8; size as usize];
+    unsafe {
+        kv_get_data(0, value.as_mut_ptr(), size);
+    }
+    Some(value)
+}
+
+fn set_value(contract: AccountName, key: &[u8], value: &[u8], payer: u64) {
+    unsafe {
+        kv_set(
+            contract.as_u64(),
+            key.as_ptr(),
+            len_u32(key),
+            value.as_ptr(),
+            len_u32(value),
+            payer,
+        );
+    }
+}
+
+fn erase_value(contract: AccountName, key: &[u8]) {
+    unsafe {
+        kv_erase(contract.as_u64(), key.as_ptr(), len_u32(key));
+    }
+}
+
+/// Returns the smallest key that is greater than every key starting with
+/// `prefix`, or `None` if there is no such key.
+fn prefix_end(mut prefix: Vec<u8>) -> Option<Vec<u8>> {
+    while let Some(last) = prefix.pop() {
+        if last < u8::MAX {
+            prefix.push(last + 1);
+            return Some(prefix);
+        }
+    }
+    None
+}
+
+/// Returns the key of the secondary index entry for a row.
+fn secondary_entry<T: KvTable>(
+    index: eosio::Name,
+    secondary_key: &[u8],
+    primary_key: &T::PrimaryKey,
+) -> Vec<u8> {
+    let mut key = T::index_prefix(index);
+    key.extend_from_slice(secondary_key);
+    primary_key.write_kv_key(&mut key);
+    key
+}
+
+/// Double-ended iterator over the rows of a key-value table, in index order.
+///
+/// The table must not be modified while iterating.
+pub struct KvTableRange<T>
+where
+    T: KvTable,
+{
+    contract: AccountName,
+    front: u32,
+    back: u32,
+    /// Whether the iterators point to secondary index entries
+    secondary: bool,
+    data: PhantomData<T>,
+}
+
+impl<T> KvTableRange<T>
+where
+    T: KvTable,
+{
+    /// Creates a range over the keys starting with `prefix` that are within
+    /// `start` and `end`. Bounds are full keys, including the prefix.
+    fn new(
+        contract: AccountName,
+        prefix: &[u8],
+        start: Bound<Vec<u8>>,
+        end: Bound<Vec<u8>>,
+        secondary: bool,
+    ) -> Self {
+        let create = || unsafe {
+            kv_it_create(contract.as_u64(), prefix.as_ptr(), len_u32(prefix))
+        };
+        let (front, back) = (create(), create());
+        let lower_bound = |itr: u32, key: Option<Vec<u8>>| {
+            // new iterators are at the end
+            if let Some(key) = key {
+                let (mut key_size, mut value_size) = (0, 0);
+                unsafe {
+                    kv_it_lower_bound(
+                        itr,
+                        key.as_ptr(),
+                        len_u32(&key),
+                        &mut key_size,
+                        &mut value_size,
+                    );
+                }
+            }
+        };
+        match start {
+            Bound::Included(key) => lower_bound(front, Some(key)),
+            Bound::Excluded(key) => lower_bound(front, prefix_end(key)),
+            Bound::Unbounded => lower_bound(front, Some(prefix.to_vec())),
+        }
+        match end {
+            Bound::Included(key) => lower_bound(back, prefix_end(key)),
+            Bound::Excluded(key) => lower_bound(back, Some(key)),
+            Bound::Unbounded => (),
+        }
+        Self {
+            contract,
+            front,
+            back,
+            secondary,
+            data: PhantomData,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        unsafe { kv_it_compare(self.front, self.back) >= 0 }
+    }
+
+    fn read(&self, itr: u32) -> Result<T::Row, ReadError> {
+        let mut size = 0;
+        unsafe {
+            kv_it_value(itr, 0, null_mut(), 0, &mut size);
+        }
+        let mut value = vec![0_u8; size as usize];
+        unsafe {
+            kv_it_value(itr, 0, value.as_mut_ptr(), size, &mut size);
+        }
+        if self.secondary {
+            value = get_value(self.contract, &value)
+                .check("secondary index entry has no row");
+        }
+        T::Row::unpack(&value)
+    }
+}
+
+impl<T> Iterator for KvTableRange<T>
+where
+    T: KvTable,
+{
+    type Item = Result<T::Row, ReadError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_empty() {
+            return None;
+        }
+        let row = self.read(self.front);
+        let (mut key_size, mut value_size) = (0, 0);
+        unsafe {
+            kv_it_next(self.front, &mut key_size, &mut value_size);
+        }
+        Some(row)
+    }
+}
+
+impl<T> DoubleEndedIterator for KvTableRange<T>
+where
+    T: KvTable,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.is_empty() {
+            return None;
+        }
+        let (mut key_size, mut value_size) = (0, 0);
+        unsafe {
+            kv_it_prev(self.back, &mut key_size, &mut value_size);
+        }
+        Some(self.read(self.back))
+    }
+}
+
+impl<T> Drop for KvTableRange<T>
+where
+    T: KvTable,
+{
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            kv_it_destroy(self.front);
+            kv_it_destroy(self.back);
+        }
+    }
+}
+
+/// Maps a range of keys to a range of full keys in an index.
+fn key_bounds<K, R, F>(range: &R, key: F) -> (Bound<Vec<u8>>, Bound<Vec<u8>>)
+where
+    R: RangeBounds<K>,
+    F: Fn(&K) -> Vec<u8>,
+{
+    let map = |bound: Bound<&K>| match bound {
+        Bound::Included(k) => Bound::Included(key(k)),
+        Bound::Excluded(k) => Bound::Excluded(key(k)),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+    (map(range.start_bound()), map(range.end_bound()))
+}
+
+/// Methods for the primary index of a key-value table
+pub trait KvPrimaryIndexExt<T>
+where
+    T: KvTable,
+{
+    /// Stores a row, replacing the row with the same primary key if there is
+    /// one. `payer` pays for the row and its secondary index entries.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the replaced row could not be read, or if the new
+    /// row could not be serialized.
+    fn set(&self, payer: AccountName, row: &T::Row) -> Result<(), TableError>;
+
+    /// Returns the row with a primary key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the row could not be read.
+    fn get(&self, key: &T::PrimaryKey) -> Result<Option<T::Row>, ReadError>;
+
+    /// Returns true if there is a row with a primary key.
+    fn contains(&self, key: &T::PrimaryKey) -> bool;
+
+    /// Erases the row with a primary key, returning it.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the row could not be read. Rows must be read in
+    /// order to erase their secondary index entries.
+    fn erase(&self, key: &T::PrimaryKey) -> Result<Option<T::Row>, ReadError>;
+
+    /// Returns an iterator over rows with primary keys in `range`.
+    fn range<R>(&self, range: R) -> KvTableRange<T>
+    where
+        R: RangeBounds<T::PrimaryKey>;
+
+    /// Returns an iterator over every row.
+    #[inline]
+    fn iter(&self) -> KvTableRange<T> {
+        self.range(..)
+    }
+}
+
+impl<T> KvPrimaryIndexExt<T> for KvPrimaryIndex<T>
+where
+    T: KvTable,
+{
+    #[inline]
+    fn set(&self, payer: AccountName, row: &T::Row) -> Result<(), TableError> {
+        let primary_key = T::primary_key(row);
+        let key = self.key(&primary_key);
+        let old_keys =
+            self.get(&primary_key)?.map(|old| T::secondary_keys(&old));
+        set_value(self.contract, &key, &row.pack()?, payer.as_u64());
+
+        let new_keys = T::secondary_keys(row);
+        for (i, (index, new_key)) in
+            T::SECONDARY_INDEXES.iter().zip(&new_keys).enumerate()
+        {
+            let old_key = old_keys.as_ref().and_then(|keys| keys.get(i));
+            if let Some(old_key) = old_key.filter(|old| *old != new_key) {
+                let entry = secondary_entry::<T>(*index, old_key, &primary_key);
+                erase_value(self.contract, &entry);
+            }
+            // entries are set even if unchanged, in case the payer changed
+            let entry = secondary_entry::<T>(*index, new_key, &primary_key);
+            set_value(self.contract, &entry, &key, payer.as_u64());
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn get(&self, key: &T::PrimaryKey) -> Result<Option<T::Row>, ReadError> {
+        get_value(self.contract, &self.key(key))
+            .map(|value| T::Row::unpack(&value))
+            .transpose()
+    }
+
+    #[inline]
+    fn contains(&self, key: &T::PrimaryKey) -> bool {
+        let key = self.key(key);
+        let mut size = 0;
+        unsafe {
+            kv_get(
+                self.contract.as_u64(),
+                key.as_ptr(),
+                len_u32(&key),
+                &mut size,
+            )
+        }
+    }
+
+    #[inline]
+    fn erase(&self, key: &T::PrimaryKey) -> Result<Option<T::Row>, ReadError> {
+        let row = match self.get(key)? {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        for (index, secondary_key) in
+            T::SECONDARY_INDEXES.iter().zip(T::secondary_keys(&row))
+        {
+            let entry = secondary_entry::<T>(*index, &secondary_key, key);
+            erase_value(self.contract, &entry);
+        }
+        erase_value(self.contract, &self.key(key));
+        Ok(Some(row))
+    }
+
+    #[inline]
+    fn range<R>(&self, range: R) -> KvTableRange<T>
+    where
+        R: RangeBounds<T::PrimaryKey>,
+    {
+        let (start, end) = key_bounds(&range, |key| self.key(key));
+        KvTableRange::new(self.contract, &self.prefix(), start, end, false)
+    }
+}
+
+/// Methods for a secondary index of a key-value table
+pub trait KvSecondaryIndexExt<K, T>
+where
+    K: KvKey,
+    T: KvTable,
+{
+    /// Returns the first row with a secondary key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the row could not be read.
+    #[inline]
+    fn get(&self, key: &K) -> Result<Option<T::Row>, ReadError> {
+        self.range((Bound::Included(key), Bound::Included(key)))
+            .next()
+            .transpose()
+    }
+
+    /// Returns an iterator over rows with secondary keys in `range`, ordered
+    /// by secondary key and then by primary key.
+    fn range<R>(&self, range: R) -> KvTableRange<T>
+    where
+        R: RangeBounds<K>;
+
+    /// Returns an iterator over every row.
+    #[inline]
+    fn iter(&self) -> KvTableRange<T> {
+        self.range(..)
+    }
+}
+
+impl<K, T> KvSecondaryIndexExt<K, T> for KvSecondaryIndex<K, T>
+where
+    K: KvKey,
+    T: KvTable,
+{
+    #[inline]
+    fn range<R>(&self, range: R) -> KvTableRange<T>
+    where
+        R: RangeBounds<K>,
+    {
+        let (start, end) = key_bounds(&range, |key| self.key(key));
+        KvTableRange::new(self.contract, &self.prefix(), start, end, true)
+    }
+}
@@ -28,6 +28,9 @@
 #[macro_use]
 extern crate alloc;
 
+#[cfg(any(not(target_arch = "wasm32"), feature = "mock", feature = "std"))]
+extern crate std;
+
 mod account;
 pub use self::account::*;
 
@@ -40,6 +43,12 @@ pub use self::check::*;
 mod crypto;
 pub use self::crypto::*;
 
+mod kv_table;
+pub use self::kv_table::*;
+
+mod multi_index;
+pub use self::multi_index::*;
+
 mod permissions;
 pub use self::permissions::*;
 
@@ -65,6 +74,12 @@ pub use self::table_secondary::*;
 mod time;
 pub use self::time::*;
 
+mod transaction;
+pub use self::transaction::*;
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "mock"))]
+pub mod mock;
+
 pub use eosio_cdt_sys as sys;
 
 // pub use self::{
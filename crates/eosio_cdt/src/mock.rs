@@ -0,0 +1,463 @@
+//! Helpers for testing contracts natively with `cargo test`.
+//!
+//! When compiling for targets other than `wasm32`, or with the `mock` feature
+//! enabled, the intrinsics are implemented by an in-memory host. Every thread
+//! has its own host, so tests that run in parallel don't share state.
+//!
+//! # Examples
+//!
+//! ```
+//! use eosio::{n, AccountName, PermissionLevel, PermissionName};
+//! use eosio_cdt::{mock::MockHost, print, require_auth};
+//!
+//! let alice = AccountName::new(n!("alice"));
+//! let host = MockHost::new();
+//! host.set_receiver(n!("hello"));
+//! host.set_authorizations(&[PermissionLevel {
+//!     actor: alice,
+//!     permission: PermissionName::new(n!("active")),
+//! }]);
+//!
+//! host.call(|| {
+//!     require_auth(alice);
+//!     print!("Hello, ", alice);
+//! })
+//! .unwrap();
+//! assert_eq!(host.printed(), "Hello, alice");
+//!
+//! let err = host
+//!     .call(|| require_auth(AccountName::new(n!("bob"))))
+//!     .unwrap_err();
+//! assert_eq!(err, "missing authority of bob");
+//! ```
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use eosio::{
+    AccountName, Action, ActionFn, NotifyFn, PermissionLevel, Read, ReadError,
+    ScopeName, Table, TimePoint, Transaction, Write, WriteError,
+};
+use eosio_cdt_sys::mock::{with_host, Exit, Host};
+use std::panic::{self, AssertUnwindSafe};
+
+/// A handle to the mock host of the current thread.
+#[derive(Debug)]
+pub struct MockHost {
+    _private: (),
+}
+
+impl Default for MockHost {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockHost {
+    /// Resets the mock host and returns a handle to it.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        eosio_cdt_sys::mock::reset();
+        Self { _private: () }
+    }
+
+    /// Calls `f` with the underlying host state.
+    #[inline]
+    pub fn with<R, F: FnOnce(&mut Host) -> R>(&self, f: F) -> R {
+        with_host(f)
+    }
+
+    /// Creates an account, so that `is_account` returns true for it.
+    #[inline]
+    pub fn add_account<A: Into<AccountName>>(&self, account: A) {
+        let account = account.into().as_u64();
+        self.with(|host| host.accounts.insert(account));
+    }
+
+    /// Sets whether an account is privileged.
+    #[inline]
+    pub fn set_privileged<A: Into<AccountName>>(
+        &self,
+        account: A,
+        is_priv: bool,
+    ) {
+        let account = account.into().as_u64();
+        self.with(|host| {
+            if is_priv {
+                host.privileged.insert(account);
+            } else {
+                host.privileged.remove(&account);
+            }
+        });
+    }
+
+    /// Sets the account whose code is running.
+    #[inline]
+    pub fn set_receiver<A: Into<AccountName>>(&self, receiver: A) {
+        let receiver = receiver.into().as_u64();
+        self.with(|host| {
+            host.accounts.insert(receiver);
+            host.receiver = receiver;
+        });
+    }
+
+    /// Sets the permissions that authorized the current action.
+    #[inline]
+    pub fn set_authorizations(&self, authorization: &[PermissionLevel]) {
+        self.with(|host| {
+            host.authorizations = authorization
+                .iter()
+                .map(|p| (p.actor.as_u64(), p.permission.as_u64()))
+                .collect();
+        });
+    }
+
+    /// Sets the data of the current action.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the data could not be serialized.
+    #[inline]
+    pub fn set_action_data<T: Write>(
+        &self,
+        data: &T,
+    ) -> Result<(), WriteError> {
+        let data = data.pack()?;
+        self.with(|host| host.action_data = data);
+        Ok(())
+    }
+
+    /// Sets the current time.
+    #[inline]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn set_current_time(&self, time: TimePoint) {
+        let micros = time.as_micros() as u64;
+        self.with(|host| host.current_time = micros);
+    }
+
+    /// Sets the transaction that contains the current action.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the transaction could not be serialized.
+    #[inline]
+    pub fn set_transaction(&self, trx: &Transaction) -> Result<(), WriteError> {
+        let transaction = trx.pack()?;
+        let context_free_actions = trx
+            .context_free_actions
+            .iter()
+            .map(Write::pack)
+            .collect::<Result<_, _>>()?;
+        let actions = trx
+            .actions
+            .iter()
+            .map(Write::pack)
+            .collect::<Result<_, _>>()?;
+        self.with(|host| {
+            host.transaction = transaction;
+            host.context_free_actions = context_free_actions;
+            host.transaction_actions = actions;
+            host.expiration = trx.header.expiration.as_secs();
+            host.ref_block_num = trx.header.ref_block_num;
+            host.ref_block_prefix = trx.header.ref_block_prefix;
+        });
+        Ok(())
+    }
+
+    /// Sets the context free data of the current transaction.
+    #[inline]
+    pub fn set_context_free_data(&self, data: Vec<Vec<u8>>) {
+        self.with(|host| host.context_free_data = data);
+    }
+
+    /// Calls `f` as if it were an action.
+    ///
+    /// If the action fails, all changes made to the host are reverted and the
+    /// error message is returned. Calling `eosio_exit` ends the action
+    /// successfully.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if `f` aborts or panics.
+    #[inline]
+    pub fn call<F: FnOnce()>(&self, f: F) -> Result<(), String> {
+        let snapshot = self.with(|host| host.clone());
+        let payload = match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(()) => return Ok(()),
+            Err(payload) => payload,
+        };
+        if payload.is::<Exit>() {
+            return Ok(());
+        }
+        self.with(|host| *host = snapshot);
+        let msg = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| (*s).into()))
+            .unwrap_or_default();
+        Err(msg)
+    }
+
+    /// Pushes an action to `receiver` and runs it.
+    ///
+    /// Output, notified recipients, inline actions and the return value of
+    /// previous actions are cleared first. Like the `abi!` dispatcher, a
+    /// return value other than `()` is set with `set_action_return_value`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the action aborts or panics.
+    #[inline]
+    pub fn push_action<A, R>(
+        &self,
+        receiver: R,
+        authorization: &[PermissionLevel],
+        action: A,
+    ) -> Result<(), String>
+    where
+        A: ActionFn,
+        R: Into<AccountName>,
+    {
+        self.start_action(receiver, authorization, &action)?;
+        self.call(|| {
+            let output = action.call();
+            if core::mem::size_of::<A::Output>() > 0 {
+                crate::set_action_return_value(&output)
+                    .expect("failed to write action return value");
+            }
+        })
+    }
+
+    /// Notifies `receiver` of an action that was sent to `first_receiver`,
+    /// and runs it through `dispatch`, the dispatcher generated by `abi!`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the dispatcher rejects the notification, or the
+    /// handler aborts or panics.
+    #[inline]
+    pub fn push_notification<N, F, R>(
+        &self,
+        dispatch: fn(u64, u64, u64),
+        first_receiver: F,
+        receiver: R,
+        authorization: &[PermissionLevel],
+        data: N,
+    ) -> Result<(), String>
+    where
+        N: NotifyFn,
+        F: Into<AccountName>,
+        R: Into<AccountName>,
+    {
+        let (first_receiver, receiver) =
+            (first_receiver.into(), receiver.into());
+        self.start_action(receiver, authorization, &data)?;
+        self.call(|| {
+            dispatch(
+                receiver.as_u64(),
+                first_receiver.as_u64(),
+                N::NAME.as_u64(),
+            );
+        })
+    }
+
+    /// Sets up the host to run a new action, clearing the results of the
+    /// previous one.
+    fn start_action<A, R>(
+        &self,
+        receiver: R,
+        authorization: &[PermissionLevel],
+        data: &A,
+    ) -> Result<(), String>
+    where
+        A: Write,
+        R: Into<AccountName>,
+    {
+        self.set_receiver(receiver);
+        self.set_authorizations(authorization);
+        self.set_action_data(data).map_err(|err| {
+            let mut msg = String::from("failed to serialize action: ");
+            msg.push_str(&err.to_string());
+            msg
+        })?;
+        self.with(|host| {
+            host.output.clear();
+            host.recipients.clear();
+            host.inline_actions.clear();
+            host.context_free_inline_actions.clear();
+            host.action_return_value.clear();
+        });
+        Ok(())
+    }
+
+    /// Returns the value returned by the last action, unpacked as `T`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the return value could not be deserialized.
+    #[inline]
+    pub fn action_return_value<T: Read>(&self) -> Result<T, ReadError> {
+        self.with(|host| T::unpack(&host.action_return_value))
+    }
+
+    /// Returns everything printed so far.
+    #[inline]
+    #[must_use]
+    pub fn printed(&self) -> String {
+        self.with(|host| host.output.clone())
+    }
+
+    /// Returns the accounts notified with `require_recipient`.
+    #[inline]
+    #[must_use]
+    pub fn recipients(&self) -> Vec<AccountName> {
+        self.with(|host| {
+            host.recipients
+                .iter()
+                .map(|r| AccountName::new(*r))
+                .collect()
+        })
+    }
+
+    /// Returns the inline actions that have been sent.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an action could not be deserialized.
+    #[inline]
+    pub fn inline_actions(&self) -> Result<Vec<Action<Vec<u8>>>, ReadError> {
+        self.with(|host| {
+            host.inline_actions
+                .iter()
+                .map(|bytes| Action::unpack(bytes))
+                .collect()
+        })
+    }
+
+    /// Returns the rows of a table, ordered by primary key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if a row could not be deserialized.
+    #[inline]
+    pub fn rows<T, C, S>(
+        &self,
+        code: C,
+        scope: S,
+    ) -> Result<Vec<T::Row>, ReadError>
+    where
+        T: Table,
+        C: Into<AccountName>,
+        S: Into<ScopeName>,
+    {
+        let table = (
+            code.into().as_u64(),
+            scope.into().as_u64(),
+            T::NAME.as_u64(),
+        );
+        self.with(|host| {
+            host.db
+                .rows(table)
+                .iter()
+                .map(|(_, row)| T::Row::unpack(&row.data))
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockHost;
+    use crate::{check, eosio_exit, require_recipient, send_inline_action};
+    use eosio::{
+        n, AccountName, Action, ActionFn, ActionName, NumBytes,
+        PermissionLevel, Read, Write,
+    };
+
+    #[test]
+    fn reverts_failed_actions() {
+        let host = MockHost::new();
+        host.call(|| {
+            print!("kept");
+        })
+        .unwrap();
+        let err = host
+            .call(|| {
+                print!("reverted");
+                check(false, "oops");
+            })
+            .unwrap_err();
+        assert_eq!(err, "assertion failure with message: oops");
+        assert_eq!(host.printed(), "kept");
+
+        host.call(|| {
+            print!(" exit");
+            eosio_exit(0);
+            print!("unreachable");
+        })
+        .unwrap();
+        assert_eq!(host.printed(), "kept exit");
+    }
+
+    #[test]
+    fn records_notifications() {
+        let host = MockHost::new();
+        host.set_receiver(n!("token"));
+        let alice = AccountName::new(n!("alice"));
+        let action = Action {
+            account: AccountName::new(n!("token")),
+            name: ActionName::new(n!("transfer")),
+            authorization: vec![PermissionLevel {
+                actor: AccountName::new(n!("token")),
+                permission: n!("active").into(),
+            }],
+            data: vec![1, 2, 3],
+        };
+        host.call(|| {
+            require_recipient(alice);
+            send_inline_action(&action).unwrap();
+        })
+        .unwrap();
+        assert_eq!(host.recipients(), vec![alice]);
+        let sent = host.inline_actions().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].name, action.name);
+        assert_eq!(sent[0].data, action.data);
+    }
+
+    #[derive(Clone, Read, Write, NumBytes)]
+    struct Double {
+        value: u32,
+    }
+
+    impl ActionFn for Double {
+        const NAME: ActionName = ActionName::new(n!("double"));
+        type Output = u64;
+        fn call(self) -> u64 {
+            check(self.value < 100, "value is too large");
+            u64::from(self.value) * 2
+        }
+    }
+
+    #[test]
+    fn action_return_values() {
+        let host = MockHost::new();
+        host.push_action(n!("math"), &[], Double { value: 21 })
+            .unwrap();
+        assert_eq!(host.action_return_value::<u64>(), Ok(42));
+        let err = host
+            .push_action(n!("math"), &[], Double { value: 100 })
+            .unwrap_err();
+        assert_eq!(err, "assertion failure with message: value is too large");
+        assert!(host.with(|host| host.action_return_value.is_empty()));
+        let err = host
+            .call(|| crate::set_action_return_value(&vec![0_u8; 256]).unwrap())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            "action return value size must be less or equal to 256 bytes"
+        );
+    }
+}
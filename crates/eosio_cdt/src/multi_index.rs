@@ -0,0 +1,402 @@
+use crate::{Check, NativeSecondaryKey, Payer};
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::{
+    cell::RefCell,
+    ops::{Deref, DerefMut},
+    ptr::null_mut,
+};
+use eosio::{
+    AccountName, Read, ReadError, ScopeName, SecondaryKey, SecondaryKeys,
+    SecondaryTableName, Table, Write, WriteError,
+};
+use eosio_cdt_sys::{
+    c_void, db_end_i64, db_find_i64, db_get_i64, db_remove_i64, db_store_i64,
+    db_update_i64,
+};
+
+/// Calls a generic expression with the native value of a secondary key
+macro_rules! with_native_key {
+    ($key:expr, | $k:ident | $body:expr) => {
+        match $key {
+            SecondaryKey::U64($k) => $body,
+            SecondaryKey::F64($k) => $body,
+            SecondaryKey::U128($k) => $body,
+            SecondaryKey::H256($k) => $body,
+            SecondaryKey::F128($k) => $body,
+        }
+    };
+}
+
+fn remove_secondary<K: NativeSecondaryKey>(
+    mut key: K,
+    code: AccountName,
+    scope: ScopeName,
+    table: SecondaryTableName,
+    pk: u64,
+) {
+    let end = K::db_idx_end(code, scope, table);
+    let itr = key.db_idx_find_primary(code, scope, table, pk);
+    if itr != end {
+        K::db_idx_remove(itr);
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn read_row<R: Read>(itr: i32) -> Result<R, ReadError> {
+    let nullptr: *mut c_void = null_mut() as *mut _ as *mut c_void;
+    let size = unsafe { db_get_i64(itr, nullptr, 0) };
+    let mut bytes = vec![0_u8; size as usize];
+    let ptr: *mut c_void = &mut bytes[..] as *mut _ as *mut c_void;
+    unsafe {
+        db_get_i64(itr, ptr, size as u32);
+    }
+    R::unpack(&bytes)
+}
+
+struct CachedRow<R> {
+    itr: i32,
+    row: R,
+}
+
+/// A table that caches deserialized rows, like `multi_index` in the C++ CDT
+///
+/// Rows are read from the database at most once. Changes are made through
+/// [`RowMut`] guards, which write rows back when they are dropped and only
+/// update the secondary keys that changed.
+///
+/// The cache belongs to this `MultiIndex`, so changes made to the same table
+/// through other indexes will not be seen. Reading, modifying or erasing a row
+/// while it is borrowed for modification aborts the action.
+pub struct MultiIndex<T>
+where
+    T: Table,
+{
+    code: AccountName,
+    scope: ScopeName,
+    cache: RefCell<BTreeMap<u64, CachedRow<T::Row>>>,
+    borrowed: RefCell<BTreeSet<u64>>,
+}
+
+impl<T> MultiIndex<T>
+where
+    T: Table,
+{
+    /// Creates a table for a contract and scope
+    #[inline]
+    pub fn new<C, S>(code: C, scope: S) -> Self
+    where
+        C: Into<AccountName>,
+        S: Into<ScopeName>,
+    {
+        Self {
+            code: code.into(),
+            scope: scope.into(),
+            cache: RefCell::new(BTreeMap::new()),
+            borrowed: RefCell::new(BTreeSet::new()),
+        }
+    }
+
+    /// Returns the account name of the smart contract
+    #[inline]
+    pub const fn code(&self) -> AccountName {
+        self.code
+    }
+
+    /// Returns the table scope
+    #[inline]
+    pub const fn scope(&self) -> ScopeName {
+        self.scope
+    }
+
+    fn end(&self) -> i32 {
+        unsafe {
+            db_end_i64(
+                self.code.as_u64(),
+                self.scope.as_u64(),
+                T::NAME.as_u64(),
+            )
+        }
+    }
+
+    fn find_itr(&self, pk: u64) -> Option<i32> {
+        let itr = unsafe {
+            db_find_i64(
+                self.code.as_u64(),
+                self.scope.as_u64(),
+                T::NAME.as_u64(),
+                pk,
+            )
+        };
+        if itr == self.end() {
+            None
+        } else {
+            Some(itr)
+        }
+    }
+
+    /// Removes a row from the cache, reading it from the database if needed
+    fn take(&self, pk: u64) -> Result<Option<CachedRow<T::Row>>, ReadError> {
+        crate::check(
+            !self.borrowed.borrow().contains(&pk),
+            "cannot access a row that is borrowed for modification",
+        );
+        if let Some(cached) = self.cache.borrow_mut().remove(&pk) {
+            return Ok(Some(cached));
+        }
+        match self.find_itr(pk) {
+            Some(itr) => {
+                let row = read_row(itr)?;
+                Ok(Some(CachedRow { itr, row }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns true if the table contains a row with the specified primary key
+    #[inline]
+    pub fn exists(&self, pk: u64) -> bool {
+        self.cache.borrow().contains_key(&pk) || self.find_itr(pk).is_some()
+    }
+
+    /// Reads the row with the specified primary key
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was an issue reading the stored value.
+    #[inline]
+    pub fn get(&self, pk: u64) -> Result<Option<T::Row>, ReadError>
+    where
+        T::Row: Clone,
+    {
+        let cached = match self.take(pk)? {
+            Some(cached) => cached,
+            None => return Ok(None),
+        };
+        let row = cached.row.clone();
+        self.cache.borrow_mut().insert(pk, cached);
+        Ok(Some(row))
+    }
+
+    /// Inserts a new row into the table
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was an issue serializing the value.
+    #[inline]
+    pub fn emplace(
+        &self,
+        payer: AccountName,
+        row: T::Row,
+    ) -> Result<(), WriteError> {
+        let pk = T::primary_key(&row);
+        let bytes = row.pack()?;
+        let ptr: *const c_void = &bytes[..] as *const _ as *const c_void;
+        #[allow(clippy::cast_possible_truncation)]
+        let itr = unsafe {
+            db_store_i64(
+                self.scope.as_u64(),
+                T::NAME.as_u64(),
+                payer.as_u64(),
+                pk,
+                ptr,
+                bytes.len() as u32,
+            )
+        };
+        for (i, key) in T::secondary_keys(&row).iter().enumerate() {
+            if let Some(key) = key {
+                let table = SecondaryTableName::new(T::NAME, i);
+                with_native_key!(*key, |k| {
+                    k.db_idx_store(self.scope, table, payer, pk);
+                });
+            }
+        }
+        self.cache.borrow_mut().insert(pk, CachedRow { itr, row });
+        Ok(())
+    }
+
+    /// Borrows a row for modification. Changes are written back when the
+    /// returned guard is dropped, and the row can't be borrowed again until
+    /// then.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was an issue reading the stored value.
+    #[inline]
+    pub fn modify(
+        &self,
+        pk: u64,
+        payer: Payer,
+    ) -> Result<Option<RowMut<'_, T>>, ReadError> {
+        let payer = match payer {
+            Payer::New(payer) => payer,
+            Payer::Same => AccountName::new(0),
+        };
+        Ok(self.take(pk)?.map(|CachedRow { itr, row }| {
+            self.borrowed.borrow_mut().insert(pk);
+            RowMut {
+                index: self,
+                itr,
+                pk,
+                payer,
+                keys: T::secondary_keys(&row),
+                row: Some(row),
+            }
+        }))
+    }
+
+    /// Modifies a row with a closure and writes it back, returning the
+    /// closure's result
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was an issue reading the stored value.
+    #[inline]
+    pub fn modify_with<F, R>(
+        &self,
+        pk: u64,
+        payer: Payer,
+        f: F,
+    ) -> Result<Option<R>, ReadError>
+    where
+        F: FnOnce(&mut T::Row) -> R,
+    {
+        Ok(self.modify(pk, payer)?.map(|mut row| f(&mut row)))
+    }
+
+    /// Erases the row with the specified primary key, returning it
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was an issue reading the stored value.
+    /// Stored values must be read in order to erase secondary indexes.
+    #[inline]
+    pub fn erase(&self, pk: u64) -> Result<Option<T::Row>, ReadError> {
+        let CachedRow { itr, row } = match self.take(pk)? {
+            Some(cached) => cached,
+            None => return Ok(None),
+        };
+        unsafe { db_remove_i64(itr) };
+        for (i, key) in T::secondary_keys(&row).iter().enumerate() {
+            if let Some(key) = key {
+                let table = SecondaryTableName::new(T::NAME, i);
+                with_native_key!(*key, |k| remove_secondary(
+                    k, self.code, self.scope, table, pk
+                ));
+            }
+        }
+        Ok(Some(row))
+    }
+}
+
+/// A row borrowed for modification from a [`MultiIndex`]
+///
+/// The row is written back when the guard is dropped, aborting the action if
+/// it could not be serialized. Use [`RowMut::save`] to handle errors instead.
+/// Changing the primary key of the row aborts the action.
+pub struct RowMut<'a, T>
+where
+    T: Table,
+{
+    index: &'a MultiIndex<T>,
+    itr: i32,
+    pk: u64,
+    payer: AccountName,
+    keys: SecondaryKeys,
+    row: Option<T::Row>,
+}
+
+impl<'a, T> RowMut<'a, T>
+where
+    T: Table,
+{
+    /// Writes the row back to the table
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was an issue serializing the value.
+    #[inline]
+    pub fn save(mut self) -> Result<(), WriteError> {
+        self.write()
+    }
+
+    fn write(&mut self) -> Result<(), WriteError> {
+        let row = match self.row.take() {
+            Some(row) => row,
+            None => return Ok(()),
+        };
+        self.index.borrowed.borrow_mut().remove(&self.pk);
+        crate::check(
+            T::primary_key(&row) == self.pk,
+            "updater cannot change primary key when modifying an object",
+        );
+        let bytes = row.pack()?;
+        let ptr: *const c_void = &bytes[..] as *const _ as *const c_void;
+        #[allow(clippy::cast_possible_truncation)]
+        unsafe {
+            db_update_i64(
+                self.itr,
+                self.payer.as_u64(),
+                ptr,
+                bytes.len() as u32,
+            )
+        };
+
+        let (code, scope, payer, pk) =
+            (self.index.code, self.index.scope, self.payer, self.pk);
+        let keys = T::secondary_keys(&row);
+        for (i, (old, new)) in self.keys.iter().zip(keys.iter()).enumerate() {
+            if old == new {
+                continue;
+            }
+            let table = SecondaryTableName::new(T::NAME, i);
+            match (old, new) {
+                (_, Some(new)) => with_native_key!(*new, |k| {
+                    let mut k = k;
+                    k.db_idx_upsert(code, scope, table, payer, pk)
+                }),
+                (Some(old), None) => with_native_key!(*old, |k| {
+                    remove_secondary(k, code, scope, table, pk)
+                }),
+                (None, None) => (),
+            }
+        }
+
+        self.index
+            .cache
+            .borrow_mut()
+            .insert(pk, CachedRow { itr: self.itr, row });
+        Ok(())
+    }
+}
+
+impl<'a, T> Deref for RowMut<'a, T>
+where
+    T: Table,
+{
+    type Target = T::Row;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.row.as_ref().expect("row has already been saved")
+    }
+}
+
+impl<'a, T> DerefMut for RowMut<'a, T>
+where
+    T: Table,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.row.as_mut().expect("row has already been saved")
+    }
+}
+
+impl<'a, T> Drop for RowMut<'a, T>
+where
+    T: Table,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.write().check("failed to write modified row");
+    }
+}
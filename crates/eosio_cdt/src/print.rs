@@ -1,12 +1,12 @@
 use alloc::{string::String, vec::Vec};
 use eosio::{
-    AccountName, ActionName, Checksum160, Checksum256, Checksum512, Name,
-    PermissionName, PublicKey, ScopeName, Signature, TableName, TimePoint,
-    TimePointSec,
+    AccountName, ActionName, Checksum160, Checksum256, Checksum512, Float128,
+    Name, PermissionName, PublicKey, ScopeName, Signature, TableName,
+    TimePoint, TimePointSec,
 };
 use eosio_cdt_sys::{
-    c_void, printdf, printhex, printi, printn, prints, prints_l, printsf,
-    printui,
+    c_void, printdf, printhex, printi, printn, printqf, prints, prints_l,
+    printsf, printui,
 };
 
 /// Trait for types that can be printed from within EOSIO smart contracts
@@ -117,6 +117,14 @@ impl Print for f64 {
     }
 }
 
+impl Print for Float128 {
+    #[inline]
+    fn print(&self) {
+        let bits = self.to_bits();
+        unsafe { printqf(&bits) }
+    }
+}
+
 impl Print for char {
     #[inline]
     fn print(&self) {
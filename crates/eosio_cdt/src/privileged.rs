@@ -1,7 +1,8 @@
 use core::convert::TryInto;
 use eosio::{
     AccountName, BlockchainParameters, Checksum256, CpuWeight, NetWeight,
-    NumBytes, ProducerKey, RamBytes, Read, ReadError, Write, WriteError,
+    NumBytes, ProducerKey, ProtocolFeature, RamBytes, Read, ReadError, Write,
+    WriteError,
 };
 
 /// Check if an account is privileged
@@ -142,3 +143,15 @@ pub fn preactivate_feature<T: AsRef<Checksum256>>(feature_digest: T) {
     let ptr = &checksum as *const capi_checksum256;
     unsafe { eosio_cdt_sys::preactivate_feature(ptr) }
 }
+
+/// Pre-activates `features` and their dependencies in dependency order,
+/// skipping any that are already activated. `PREACTIVATE_FEATURE` itself must
+/// already be activated by the producers.
+#[inline]
+pub fn preactivate_features<T: AsRef<[ProtocolFeature]>>(features: T) {
+    for feature in ProtocolFeature::activation_plan(features.as_ref()) {
+        if !is_feature_activated(feature) {
+            preactivate_feature(feature);
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use alloc::vec::Vec;
-use core::borrow::Borrow;
+use core::{borrow::Borrow, fmt, ops::Bound};
 use eosio::{AccountName, DataStream, ReadError, ScopeName, Table, WriteError};
 
 pub enum Payer {
@@ -7,6 +7,50 @@ pub enum Payer {
     New(AccountName),
 }
 
+/// Error that can be returned when reading and writing table rows
+#[derive(Debug, Clone, Copy)]
+pub enum TableError {
+    /// A row could not be deserialized
+    Read(ReadError),
+    /// A row could not be serialized
+    Write(WriteError),
+}
+
+impl fmt::Display for TableError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Read(err) => write!(f, "failed to read row: {}", err),
+            Self::Write(err) => write!(f, "failed to write row: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TableError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read(err) => Some(err),
+            Self::Write(err) => Some(err),
+        }
+    }
+}
+
+impl From<ReadError> for TableError {
+    #[inline]
+    fn from(err: ReadError) -> Self {
+        Self::Read(err)
+    }
+}
+
+impl From<WriteError> for TableError {
+    #[inline]
+    fn from(err: WriteError) -> Self {
+        Self::Write(err)
+    }
+}
+
 /// Table Cursor
 pub trait TableCursor<T>: IntoIterator
 where
@@ -47,6 +91,28 @@ where
         payer: Payer,
         item: I,
     ) -> Result<usize, WriteError>;
+
+    /// Modify the current row with a closure. Changing the primary key of
+    /// the row aborts the action.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was an issue reading or writing the row.
+    #[inline]
+    fn modify_with<F: FnOnce(&mut T::Row)>(
+        &self,
+        payer: Payer,
+        f: F,
+    ) -> Result<usize, TableError> {
+        let mut row = self.get()?;
+        let pk = T::primary_key(&row);
+        f(&mut row);
+        crate::check(
+            T::primary_key(&row) == pk,
+            "updater cannot change primary key when modifying an object",
+        );
+        Ok(self.modify(payer, &row)?)
+    }
 }
 
 /// Table index
@@ -87,3 +153,17 @@ where
 
 /// Table iterator
 pub trait TableIterator: DoubleEndedIterator {}
+
+/// Returns true if no key can be within the bounds of a range.
+pub(crate) fn is_empty_range<K: PartialOrd>(
+    start: Bound<&K>,
+    end: Bound<&K>,
+) -> bool {
+    match (start, end) {
+        (Bound::Included(start), Bound::Included(end)) => start > end,
+        (Bound::Included(start), Bound::Excluded(end))
+        | (Bound::Excluded(start), Bound::Included(end))
+        | (Bound::Excluded(start), Bound::Excluded(end)) => start >= end,
+        _ => false,
+    }
+}
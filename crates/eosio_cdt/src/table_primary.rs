@@ -1,12 +1,17 @@
 use crate::{
-    NativeSecondaryKey, Payer, Print, TableCursor, TableIndex, TableIterator,
+    check, table::is_empty_range, NativeSecondaryKey, Payer, Print,
+    TableCursor, TableError, TableIndex, TableIterator,
 };
 use alloc::vec::Vec;
 use core::{
-    borrow::Borrow, iter::IntoIterator, marker::PhantomData, ptr::null_mut,
+    borrow::Borrow,
+    iter::{IntoIterator, Rev},
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    ptr::null_mut,
 };
 use eosio::{
-    AccountName, NumBytes, PrimaryTableIndex, ReadError, ScopeName,
+    AccountName, Float128, NumBytes, PrimaryTableIndex, ReadError, ScopeName,
     SecondaryKey, SecondaryTableName, Table, Write, WriteError,
 };
 use eosio_cdt_sys::{
@@ -111,6 +116,16 @@ where
                             u128::db_idx_remove(itr);
                         }
                     }
+                    SecondaryKey::F128(v) => {
+                        let end =
+                            Float128::db_idx_end(self.code, self.scope, table);
+                        let itr = v.clone().db_idx_find_primary(
+                            self.code, self.scope, table, pk,
+                        );
+                        if itr != end {
+                            Float128::db_idx_remove(itr);
+                        }
+                    }
                     SecondaryKey::H256(v) => {
                         let end = <[u128; 2]>::db_idx_end(
                             self.code, self.scope, table,
@@ -168,6 +183,9 @@ where
                     SecondaryKey::H256(v) => {
                         v.db_idx_upsert(self.code, self.scope, table, payer, pk)
                     }
+                    SecondaryKey::F128(v) => {
+                        v.db_idx_upsert(self.code, self.scope, table, payer, pk)
+                    }
                 };
             }
         }
@@ -271,6 +289,75 @@ where
 
 impl<T> TableIterator for PrimaryTableIterator<T> where T: Table {}
 
+/// Iterator over the rows in a range of a `PrimaryTableIndex`
+#[allow(clippy::missing_inline_in_public_items)]
+#[derive(Copy, Clone, Debug)]
+pub struct PrimaryTableRange<T>
+where
+    T: Table,
+{
+    front: i32,
+    back: i32,
+    code: AccountName,
+    scope: ScopeName,
+    data: PhantomData<T>,
+}
+
+impl<T> PrimaryTableRange<T>
+where
+    T: Table,
+{
+    fn cursor(&self, value: i32) -> PrimaryTableCursor<T> {
+        PrimaryTableCursor {
+            value,
+            code: self.code,
+            scope: self.scope,
+            data: PhantomData,
+        }
+    }
+
+    /// Returns a cursor to the first row in the range and moves past it. The
+    /// row can be erased without invalidating the range.
+    fn next_cursor(&mut self) -> Option<PrimaryTableCursor<T>> {
+        if self.front == self.back {
+            return None;
+        }
+        let cursor = self.cursor(self.front);
+        let mut pk = 0_u64;
+        let ptr: *mut u64 = &mut pk;
+        self.front = unsafe { db_next_i64(self.front, ptr) };
+        Some(cursor)
+    }
+}
+
+impl<T> Iterator for PrimaryTableRange<T>
+where
+    T: Table,
+{
+    type Item = Result<T::Row, ReadError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_cursor().map(|cursor| cursor.get())
+    }
+}
+
+impl<T> DoubleEndedIterator for PrimaryTableRange<T>
+where
+    T: Table,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        let mut pk = 0_u64;
+        let ptr: *mut u64 = &mut pk;
+        self.back = unsafe { db_previous_i64(self.back, ptr) };
+        Some(self.cursor(self.back).get())
+    }
+}
+
 impl<'a, T> TableIndex<'a, u64, T> for PrimaryTableIndex<T>
 where
     T: Table + 'a,
@@ -377,6 +464,9 @@ where
                     SecondaryKey::H256(v) => {
                         v.db_idx_store(self.scope, table, payer, id)
                     }
+                    SecondaryKey::F128(v) => {
+                        v.db_idx_store(self.scope, table, payer, id)
+                    }
                 };
             }
         }
@@ -428,25 +518,140 @@ where
         self.lower_bound(u64::min_value())
     }
 
-    /// Iterate over rows in the table
+    /// Iterate over the rows with primary keys in a range, in ascending order
+    /// of primary keys
     #[inline]
-    fn iter(&'a self) -> PrimaryTableIterator<T> {
-        self.begin().map_or_else(
-            || PrimaryTableIterator {
-                value: 0,
-                end: 0,
-                code: self.code(),
-                scope: self.scope(),
-                data: PhantomData,
-            },
-            IntoIterator::into_iter,
-        )
+    fn range<R: RangeBounds<u64>>(&'a self, range: R) -> PrimaryTableRange<T> {
+        let code = self.code().as_u64();
+        let scope = self.scope().as_u64();
+        let table = T::NAME.as_u64();
+        let lower = |key| unsafe { db_lowerbound_i64(code, scope, table, key) };
+        let upper = |key| unsafe { db_upperbound_i64(code, scope, table, key) };
+        let end = self.end();
+        let (front, back) =
+            if is_empty_range(range.start_bound(), range.end_bound()) {
+                (end, end)
+            } else {
+                let front = match range.start_bound() {
+                    Bound::Included(key) => lower(*key),
+                    Bound::Excluded(key) => upper(*key),
+                    Bound::Unbounded => lower(u64::min_value()),
+                };
+                let back = match range.end_bound() {
+                    Bound::Included(key) => upper(*key),
+                    Bound::Excluded(key) => lower(*key),
+                    Bound::Unbounded => end,
+                };
+                (front, back)
+            };
+        PrimaryTableRange {
+            front,
+            back,
+            code: self.code(),
+            scope: self.scope(),
+            data: PhantomData,
+        }
+    }
+
+    /// Iterate over rows in the table, in ascending order of primary keys
+    #[inline]
+    fn iter(&'a self) -> PrimaryTableRange<T> {
+        self.range(..)
+    }
+
+    /// Iterate over rows in the table, in descending order of primary keys
+    #[inline]
+    fn rev(&'a self) -> Rev<PrimaryTableRange<T>> {
+        self.iter().rev()
+    }
+
+    /// Reads the row with the lowest primary key
+    #[inline]
+    fn first(&'a self) -> Option<Result<T::Row, ReadError>> {
+        self.iter().next()
+    }
+
+    /// Reads the row with the highest primary key
+    #[inline]
+    fn last(&'a self) -> Option<Result<T::Row, ReadError>> {
+        self.iter().next_back()
     }
 
     /// Total number of rows in the table
     #[inline]
     fn count(&'a self) -> usize {
-        self.iter().count()
+        self.begin().map_or(0, |cursor| cursor.into_iter().count())
+    }
+
+    /// Counts rows in the table, stopping at `limit`
+    #[inline]
+    fn len_at_most(&'a self, limit: usize) -> usize {
+        self.begin()
+            .map_or(0, |cursor| cursor.into_iter().take(limit).count())
+    }
+
+    /// Returns true if the table has no rows
+    #[inline]
+    fn is_empty(&'a self) -> bool {
+        self.begin().is_none()
+    }
+
+    /// Modifies the row with a primary key, or inserts `default` modified by
+    /// `f` if there is no such row. `payer` pays for the row either way.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was an issue reading or writing the row.
+    #[inline]
+    fn upsert<N, F>(
+        &'a self,
+        key: N,
+        payer: AccountName,
+        default: T::Row,
+        f: F,
+    ) -> Result<(), TableError>
+    where
+        N: Into<u64>,
+        F: FnOnce(&mut T::Row),
+    {
+        let key = key.into();
+        if let Some(cursor) = self.find(key) {
+            cursor.modify_with(Payer::New(payer), f)?;
+        } else {
+            let mut row = default;
+            f(&mut row);
+            check(
+                T::primary_key(&row) == key,
+                "upserted row has a different primary key",
+            );
+            self.emplace(payer, &row)?;
+        }
+        Ok(())
+    }
+
+    /// Erases up to `limit` rows with primary keys in a range, returning how
+    /// many were erased
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was an issue reading a stored value. Stored
+    /// values must be read in order to erase secondary indexes.
+    #[inline]
+    fn erase_range<R: RangeBounds<u64>>(
+        &'a self,
+        range: R,
+        limit: usize,
+    ) -> Result<usize, ReadError> {
+        let mut range = self.range(range);
+        let mut count = 0;
+        while count < limit {
+            match range.next_cursor() {
+                Some(cursor) => cursor.erase()?,
+                None => break,
+            };
+            count += 1;
+        }
+        Ok(count)
     }
 
     /// Returns the last row in the table
@@ -1,8 +1,15 @@
-use crate::{Payer, TableCursor, TableIndex, TableIterator};
+use crate::{
+    table::is_empty_range, Payer, TableCursor, TableIndex, TableIterator,
+};
 use alloc::vec::Vec;
-use core::{borrow::Borrow, ptr::null_mut};
+use core::{
+    borrow::Borrow,
+    iter::Rev,
+    ops::{Bound, RangeBounds},
+    ptr::null_mut,
+};
 use eosio::{
-    AccountName, Checksum160, Checksum256, ReadError, ScopeName,
+    AccountName, Checksum160, Checksum256, Float128, ReadError, ScopeName,
     SecondaryTableIndex, SecondaryTableName, Table, WriteError,
 };
 use eosio_cdt_sys::{
@@ -19,7 +26,12 @@ use eosio_cdt_sys::{
     db_idx_double_find_primary, db_idx_double_find_secondary,
     db_idx_double_lowerbound, db_idx_double_next, db_idx_double_previous,
     db_idx_double_remove, db_idx_double_store, db_idx_double_update,
-    db_idx_double_upperbound,
+    db_idx_double_upperbound, db_idx_long_double_end,
+    db_idx_long_double_find_primary, db_idx_long_double_find_secondary,
+    db_idx_long_double_lowerbound, db_idx_long_double_next,
+    db_idx_long_double_previous, db_idx_long_double_remove,
+    db_idx_long_double_store, db_idx_long_double_update,
+    db_idx_long_double_upperbound, intrinsic_fn,
 };
 
 pub enum Either<A, B> {
@@ -27,112 +39,116 @@ pub enum Either<A, B> {
     B(B),
 }
 
-pub type EndFn = unsafe extern "C" fn(code: u64, scope: u64, table: u64) -> i32;
-pub type NextFn = unsafe extern "C" fn(itr: i32, primary: *mut u64) -> i32;
-pub type PreviousFn = unsafe extern "C" fn(itr: i32, primary: *mut u64) -> i32;
-pub type RemoveFn = unsafe extern "C" fn(itr: i32);
+pub type EndFn = intrinsic_fn!((code: u64, scope: u64, table: u64) -> i32);
+pub type NextFn = intrinsic_fn!((itr: i32, primary: *mut u64) -> i32);
+pub type PreviousFn = intrinsic_fn!((itr: i32, primary: *mut u64) -> i32);
+pub type RemoveFn = intrinsic_fn!((itr: i32));
 
-pub type StoreValueFn<T> = unsafe extern "C" fn(
+pub type StoreValueFn<T> = intrinsic_fn!((
     scope: u64,
     table: u64,
     payer: u64,
     id: u64,
     secondary: *const T,
-) -> i32;
+) -> i32);
 
-pub type StoreArrayFn<T> = unsafe extern "C" fn(
+pub type StoreArrayFn<T> = intrinsic_fn!((
     scope: u64,
     table: u64,
     payer: u64,
     id: u64,
     data: *const T,
     data_len: u32,
-) -> i32;
+) -> i32);
 
 pub type StoreFn<T> = Either<StoreValueFn<T>, StoreArrayFn<T>>;
 
 pub type UpdateValueFn<T> =
-    unsafe extern "C" fn(itr: i32, payer: u64, secondary: *const T);
+    intrinsic_fn!((itr: i32, payer: u64, secondary: *const T));
 
-pub type UpdateArrayFn<T> =
-    unsafe extern "C" fn(itr: i32, payer: u64, data: *const T, data_len: u32);
+pub type UpdateArrayFn<T> = intrinsic_fn!((
+    itr: i32,
+    payer: u64,
+    data: *const T,
+    data_len: u32,
+));
 
 pub type UpdateFn<T> = Either<UpdateValueFn<T>, UpdateArrayFn<T>>;
 
-pub type LowerboundValueFn<T> = unsafe extern "C" fn(
+pub type LowerboundValueFn<T> = intrinsic_fn!((
     code: u64,
     scope: u64,
     table: u64,
     secondary: *mut T,
     primary: *mut u64,
-) -> i32;
+) -> i32);
 
-pub type LowerboundArrayFn<T> = unsafe extern "C" fn(
+pub type LowerboundArrayFn<T> = intrinsic_fn!((
     code: u64,
     scope: u64,
     table: u64,
     data: *mut T,
     data_len: u32,
     primary: *mut u64,
-) -> i32;
+) -> i32);
 
 pub type LowerboundFn<T> = Either<LowerboundValueFn<T>, LowerboundArrayFn<T>>;
 
-pub type UpperboundValueFn<T> = unsafe extern "C" fn(
+pub type UpperboundValueFn<T> = intrinsic_fn!((
     code: u64,
     scope: u64,
     table: u64,
     secondary: *mut T,
     primary: *mut u64,
-) -> i32;
+) -> i32);
 
-pub type UpperboundArrayFn<T> = unsafe extern "C" fn(
+pub type UpperboundArrayFn<T> = intrinsic_fn!((
     code: u64,
     scope: u64,
     table: u64,
     data: *mut T,
     data_len: u32,
     primary: *mut u64,
-) -> i32;
+) -> i32);
 
 pub type UpperboundFn<T> = Either<UpperboundValueFn<T>, UpperboundArrayFn<T>>;
 
-pub type FindPrimaryValueFn<T> = unsafe extern "C" fn(
+pub type FindPrimaryValueFn<T> = intrinsic_fn!((
     code: u64,
     scope: u64,
     table: u64,
     secondary: *mut T,
     primary: u64,
-) -> i32;
+) -> i32);
 
-pub type FindPrimaryArrayFn<T> = unsafe extern "C" fn(
+pub type FindPrimaryArrayFn<T> = intrinsic_fn!((
     code: u64,
     scope: u64,
     table: u64,
     data: *mut T,
     data_len: u32,
     primary: u64,
-) -> i32;
+) -> i32);
 
 pub type FindPrimaryFn<T> =
     Either<FindPrimaryValueFn<T>, FindPrimaryArrayFn<T>>;
 
-pub type FindSecondaryValueFn<T> = unsafe extern "C" fn(
+pub type FindSecondaryValueFn<T> = intrinsic_fn!((
     code: u64,
     scope: u64,
     table: u64,
     secondary: *const T,
     primary: *mut u64,
-) -> i32;
+) -> i32);
 
-pub type FindSecondaryArrayFn<T> = unsafe extern "C" fn(
+pub type FindSecondaryArrayFn<T> = intrinsic_fn!((
     code: u64,
     scope: u64,
     table: u64,
     data: *const T,
     data_len: u32,
     primary: *mut u64,
-) -> i32;
+) -> i32);
 
 pub type FindSecondaryFn<T> =
     Either<FindSecondaryValueFn<T>, FindSecondaryArrayFn<T>>;
@@ -141,6 +157,8 @@ pub type FindSecondaryFn<T> =
 /// keys
 pub trait NativeSecondaryKey: Default {
     type NativeType;
+    /// The key that sorts before every other key
+    const LOWEST: Self;
     /// Unsafe native `end` function
     const END: EndFn;
     /// Unsafe native `next` function
@@ -378,7 +396,8 @@ pub trait NativeSecondaryKey: Default {
         id: u64,
     ) {
         let end = Self::db_idx_end(code, scope, table);
-        let itr = self.db_idx_find_primary(code, scope, table, id);
+        // finding by primary key overwrites the key with the stored one
+        let itr = Self::default().db_idx_find_primary(code, scope, table, id);
         if itr == end {
             self.db_idx_store(scope, table, payer, id);
         } else {
@@ -397,6 +416,7 @@ impl NativeSecondaryKey for u64 {
         Either::A(db_idx64_find_secondary);
     const LOWERBOUND: LowerboundFn<Self::NativeType> =
         Either::A(db_idx64_lowerbound);
+    const LOWEST: Self = 0;
     const NEXT: NextFn = db_idx64_next;
     const PREVIOUS: PreviousFn = db_idx64_previous;
     const REMOVE: RemoveFn = db_idx64_remove;
@@ -426,6 +446,7 @@ impl NativeSecondaryKey for f64 {
         Either::A(db_idx_double_find_secondary);
     const LOWERBOUND: LowerboundFn<Self::NativeType> =
         Either::A(db_idx_double_lowerbound);
+    const LOWEST: Self = Self::NEG_INFINITY;
     const NEXT: NextFn = db_idx_double_next;
     const PREVIOUS: PreviousFn = db_idx_double_previous;
     const REMOVE: RemoveFn = db_idx_double_remove;
@@ -455,6 +476,7 @@ impl NativeSecondaryKey for u128 {
         Either::A(db_idx128_find_secondary);
     const LOWERBOUND: LowerboundFn<Self::NativeType> =
         Either::A(db_idx128_lowerbound);
+    const LOWEST: Self = 0;
     const NEXT: NextFn = db_idx128_next;
     const PREVIOUS: PreviousFn = db_idx128_previous;
     const REMOVE: RemoveFn = db_idx128_remove;
@@ -474,6 +496,39 @@ impl NativeSecondaryKey for u128 {
     }
 }
 
+impl NativeSecondaryKey for Float128 {
+    type NativeType = u128;
+
+    const END: EndFn = db_idx_long_double_end;
+    const FIND_PRIMARY: FindPrimaryFn<Self::NativeType> =
+        Either::A(db_idx_long_double_find_primary);
+    const FIND_SECONDARY: FindSecondaryFn<Self::NativeType> =
+        Either::A(db_idx_long_double_find_secondary);
+    const LOWERBOUND: LowerboundFn<Self::NativeType> =
+        Either::A(db_idx_long_double_lowerbound);
+    const LOWEST: Self = Self::NEG_INFINITY;
+    const NEXT: NextFn = db_idx_long_double_next;
+    const PREVIOUS: PreviousFn = db_idx_long_double_previous;
+    const REMOVE: RemoveFn = db_idx_long_double_remove;
+    const STORE: StoreFn<Self::NativeType> =
+        Either::A(db_idx_long_double_store);
+    const UPDATE: UpdateFn<Self::NativeType> =
+        Either::A(db_idx_long_double_update);
+    const UPPERBOUND: UpperboundFn<Self::NativeType> =
+        Either::A(db_idx_long_double_upperbound);
+
+    // `Float128` is a transparent wrapper around the `long double` bits
+    #[inline]
+    fn as_ptr(&self) -> *const Self::NativeType {
+        (self as *const Self).cast()
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut Self::NativeType {
+        (self as *mut Self).cast()
+    }
+}
+
 impl NativeSecondaryKey for [u128; 2] {
     type NativeType = u128;
 
@@ -485,6 +540,7 @@ impl NativeSecondaryKey for [u128; 2] {
         Either::B(db_idx256_find_secondary);
     const LOWERBOUND: LowerboundFn<Self::NativeType> =
         Either::B(db_idx256_lowerbound);
+    const LOWEST: Self = [0; 2];
     const NEXT: NextFn = db_idx256_next;
     const PREVIOUS: PreviousFn = db_idx256_previous;
     const REMOVE: RemoveFn = db_idx256_remove;
@@ -542,6 +598,16 @@ impl IntoNativeSecondaryKey for u128 {
     }
 }
 
+impl IntoNativeSecondaryKey for Float128 {
+    type Native = Self;
+
+    #[must_use]
+    #[inline]
+    fn into_native_secondary_key(self) -> Self::Native {
+        self
+    }
+}
+
 impl IntoNativeSecondaryKey for Checksum256 {
     type Native = [u128; 2];
 
@@ -618,7 +684,7 @@ where
             )
         };
         let nullptr: *mut c_void = null_mut() as *mut _ as *mut c_void;
-        let size = unsafe { db_get_i64(self.value, nullptr, 0) };
+        let size = unsafe { db_get_i64(pk_itr, nullptr, 0) };
         let mut bytes = vec![0_u8; size as usize];
         let ptr: *mut c_void = &mut bytes[..] as *mut _ as *mut c_void;
         unsafe {
@@ -754,6 +820,76 @@ where
 {
 }
 
+/// Iterator over the rows in a range of a `SecondaryTableIndex`
+#[allow(clippy::missing_inline_in_public_items)]
+#[derive(Copy, Clone, Debug)]
+pub struct SecondaryTableRange<'a, K, T>
+where
+    T: Table,
+{
+    front: i32,
+    front_pk: u64,
+    back: i32,
+    index: &'a SecondaryTableIndex<K, T>,
+}
+
+impl<'a, K, T> SecondaryTableRange<'a, K, T>
+where
+    K: IntoNativeSecondaryKey,
+    T: Table,
+{
+    /// Returns a cursor to the first row in the range and moves past it. The
+    /// row can be erased without invalidating the range.
+    fn next_cursor(&mut self) -> Option<SecondaryTableCursor<'a, K, T>> {
+        if self.front == self.back {
+            return None;
+        }
+        let cursor = SecondaryTableCursor {
+            value: self.front,
+            pk: self.front_pk,
+            index: self.index,
+        };
+        let (itr, pk) = K::Native::db_idx_next(self.front);
+        self.front = itr;
+        self.front_pk = pk;
+        Some(cursor)
+    }
+}
+
+impl<'a, K, T> Iterator for SecondaryTableRange<'a, K, T>
+where
+    K: IntoNativeSecondaryKey,
+    T: Table,
+{
+    type Item = Result<T::Row, ReadError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_cursor().map(|cursor| cursor.get())
+    }
+}
+
+impl<'a, K, T> DoubleEndedIterator for SecondaryTableRange<'a, K, T>
+where
+    K: IntoNativeSecondaryKey,
+    T: Table,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+        let (itr, pk) = K::Native::db_idx_previous(self.back);
+        self.back = itr;
+        let cursor = SecondaryTableCursor {
+            value: itr,
+            pk,
+            index: self.index,
+        };
+        Some(cursor.get())
+    }
+}
+
 impl<'a, K, T> TableIndex<'a, K, T> for SecondaryTableIndex<K, T>
 where
     K: IntoNativeSecondaryKey + 'a,
@@ -837,3 +973,149 @@ where
         table.emplace(payer, item)
     }
 }
+
+/// Trait for functions of a `SecondaryTableIndex` that only apply within a
+/// smart contract
+pub trait SecondaryTableIndexExt<'a, K, T>
+where
+    K: IntoNativeSecondaryKey + Clone + 'a,
+    K::Native: PartialOrd,
+    T: Table + 'a,
+{
+    /// Iterate over the rows with secondary keys in a range, in ascending
+    /// order of secondary keys
+    fn range<R: RangeBounds<K>>(
+        &'a self,
+        range: R,
+    ) -> SecondaryTableRange<'a, K, T>;
+
+    /// Iterate over rows in the table, in ascending order of secondary keys
+    #[inline]
+    fn iter(&'a self) -> SecondaryTableRange<'a, K, T> {
+        self.range(..)
+    }
+
+    /// Iterate over rows in the table, in descending order of secondary keys
+    #[inline]
+    fn rev(&'a self) -> Rev<SecondaryTableRange<'a, K, T>> {
+        self.iter().rev()
+    }
+
+    /// Reads the row with the lowest secondary key
+    #[inline]
+    fn first(&'a self) -> Option<Result<T::Row, ReadError>> {
+        self.iter().next()
+    }
+
+    /// Reads the row with the highest secondary key
+    #[inline]
+    fn last(&'a self) -> Option<Result<T::Row, ReadError>> {
+        self.iter().next_back()
+    }
+
+    /// Counts rows in the table, stopping at `limit`
+    #[inline]
+    fn len_at_most(&'a self, limit: usize) -> usize {
+        let mut range = self.iter();
+        let mut len = 0;
+        while len < limit && range.next_cursor().is_some() {
+            len += 1;
+        }
+        len
+    }
+
+    /// Returns true if the table has no rows
+    #[inline]
+    fn is_empty(&'a self) -> bool {
+        self.len_at_most(1) == 0
+    }
+
+    /// Erases up to `limit` rows with secondary keys in a range, returning
+    /// how many were erased
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was an issue reading a stored value. Stored
+    /// values must be read in order to erase secondary indexes.
+    #[inline]
+    fn erase_range<R: RangeBounds<K>>(
+        &'a self,
+        range: R,
+        limit: usize,
+    ) -> Result<usize, ReadError> {
+        let mut range = self.range(range);
+        let mut count = 0;
+        while count < limit {
+            match range.next_cursor() {
+                Some(cursor) => cursor.erase()?,
+                None => break,
+            };
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+fn native_bound<K: IntoNativeSecondaryKey + Clone>(
+    bound: Bound<&K>,
+) -> Bound<K::Native> {
+    match bound {
+        Bound::Included(key) => {
+            Bound::Included(key.clone().into_native_secondary_key())
+        }
+        Bound::Excluded(key) => {
+            Bound::Excluded(key.clone().into_native_secondary_key())
+        }
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+impl<'a, K, T> SecondaryTableIndexExt<'a, K, T> for SecondaryTableIndex<K, T>
+where
+    K: IntoNativeSecondaryKey + Clone + 'a,
+    K::Native: PartialOrd,
+    T: Table + 'a,
+{
+    #[inline]
+    fn range<R: RangeBounds<K>>(
+        &'a self,
+        range: R,
+    ) -> SecondaryTableRange<'a, K, T> {
+        let (code, scope, table) = (self.code, self.scope, self.table);
+        let start = native_bound(range.start_bound());
+        let end = native_bound(range.end_bound());
+        let end_itr = K::Native::db_idx_end(code, scope, table);
+        let ((front, front_pk), back) =
+            if is_empty_range(start.as_ref(), end.as_ref()) {
+                ((end_itr, 0), end_itr)
+            } else {
+                let front = match start {
+                    Bound::Included(mut key) => {
+                        key.db_idx_lowerbound(code, scope, table)
+                    }
+                    Bound::Excluded(mut key) => {
+                        key.db_idx_upperbound(code, scope, table)
+                    }
+                    Bound::Unbounded => {
+                        K::Native::LOWEST.db_idx_lowerbound(code, scope, table)
+                    }
+                };
+                let back = match end {
+                    Bound::Included(mut key) => {
+                        key.db_idx_upperbound(code, scope, table).0
+                    }
+                    Bound::Excluded(mut key) => {
+                        key.db_idx_lowerbound(code, scope, table).0
+                    }
+                    Bound::Unbounded => end_itr,
+                };
+                (front, back)
+            };
+        SecondaryTableRange {
+            front,
+            front_pk,
+            back,
+            index: self,
+        }
+    }
+}
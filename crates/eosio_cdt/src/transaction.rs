@@ -0,0 +1,212 @@
+use alloc::vec::Vec;
+use eosio::{Action, Read, ReadError, Transaction};
+
+/// The kinds of actions in a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionKind {
+    /// Actions that run without access to the blockchain state
+    ContextFree,
+    /// Regular actions
+    Regular,
+}
+
+impl ActionKind {
+    const fn as_u32(self) -> u32 {
+        match self {
+            Self::ContextFree => 0,
+            Self::Regular => 1,
+        }
+    }
+}
+
+/// Gets the size of the currently executing transaction in bytes
+#[must_use]
+#[inline]
+pub fn transaction_size() -> usize {
+    unsafe { eosio_cdt_sys::transaction_size() }
+}
+
+/// Reads the currently executing transaction
+///
+/// # Errors
+///
+/// Will return `Err` if there was a problem reading the transaction.
+#[inline]
+pub fn current_transaction() -> Result<Transaction, ReadError> {
+    let size = transaction_size();
+    let mut bytes = vec![0_u8; size];
+    let read =
+        unsafe { eosio_cdt_sys::read_transaction(bytes.as_mut_ptr(), size) };
+    if read < size {
+        return Err(ReadError::NotEnoughBytes);
+    }
+    Transaction::read(&bytes, &mut 0)
+}
+
+/// Gets an action of the currently executing transaction by its index.
+/// Returns `Ok(None)` if there is no action at `index`.
+///
+/// # Errors
+///
+/// Will return `Err` if there was a problem reading the action.
+#[inline]
+#[allow(clippy::cast_sign_loss)]
+pub fn get_action(
+    kind: ActionKind,
+    index: u32,
+) -> Result<Option<Action<Vec<u8>>>, ReadError> {
+    let kind = kind.as_u32();
+    let size = unsafe {
+        eosio_cdt_sys::get_action(kind, index, core::ptr::null_mut(), 0)
+    };
+    if size < 0 {
+        return Ok(None);
+    }
+    let size = size as usize;
+    let mut bytes = vec![0_u8; size];
+    unsafe { eosio_cdt_sys::get_action(kind, index, bytes.as_mut_ptr(), size) };
+    Action::read(&bytes, &mut 0).map(Some)
+}
+
+/// Returns an iterator over the actions of the currently executing
+/// transaction.
+#[must_use]
+#[inline]
+pub const fn transaction_actions(kind: ActionKind) -> TransactionActions {
+    TransactionActions { kind, index: 0 }
+}
+
+/// An iterator over the actions of the currently executing transaction,
+/// created by [`transaction_actions`].
+#[derive(Debug, Clone)]
+pub struct TransactionActions {
+    kind: ActionKind,
+    index: u32,
+}
+
+impl Iterator for TransactionActions {
+    type Item = Result<Action<Vec<u8>>, ReadError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let action = get_action(self.kind, self.index).transpose()?;
+        self.index += 1;
+        Some(action)
+    }
+}
+
+/// Gets the context free data of the currently executing transaction at
+/// `index`, or `None` if there is none.
+#[must_use]
+#[inline]
+#[allow(clippy::cast_sign_loss)]
+pub fn context_free_data(index: u32) -> Option<Vec<u8>> {
+    let size = unsafe {
+        eosio_cdt_sys::get_context_free_data(index, core::ptr::null_mut(), 0)
+    };
+    if size < 0 {
+        return None;
+    }
+    let mut bytes = vec![0_u8; size as usize];
+    if size > 0 {
+        unsafe {
+            eosio_cdt_sys::get_context_free_data(
+                index,
+                bytes.as_mut_ptr(),
+                bytes.len(),
+            )
+        };
+    }
+    Some(bytes)
+}
+
+/// Gets the block number used for TAPoS by the currently executing
+/// transaction
+#[must_use]
+#[inline]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn tapos_block_num() -> u16 {
+    unsafe { eosio_cdt_sys::tapos_block_num() as u16 }
+}
+
+/// Gets the block prefix used for TAPoS by the currently executing
+/// transaction
+#[must_use]
+#[inline]
+#[allow(clippy::cast_sign_loss)]
+pub fn tapos_block_prefix() -> u32 {
+    unsafe { eosio_cdt_sys::tapos_block_prefix() as u32 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        context_free_data, current_transaction, get_action, tapos_block_num,
+        tapos_block_prefix, transaction_actions, ActionKind,
+    };
+    use crate::{expiration, mock::MockHost};
+    use alloc::vec::Vec;
+    use eosio::{
+        n, AccountName, Action, ActionName, TimePointSec, Transaction,
+        TransactionHeader,
+    };
+
+    fn action(name: u64, data: Vec<u8>) -> Action<Vec<u8>> {
+        Action {
+            account: AccountName::new(n!("token")),
+            name: ActionName::new(name),
+            authorization: Vec::new(),
+            data,
+        }
+    }
+
+    #[test]
+    fn reads_the_current_transaction() {
+        let host = MockHost::new();
+        let trx = Transaction {
+            header: TransactionHeader {
+                expiration: TimePointSec::from_secs(1_000),
+                ref_block_num: 42,
+                ref_block_prefix: 0xdead_beef,
+                ..TransactionHeader::default()
+            },
+            context_free_actions: vec![action(n!("nonce"), vec![7])],
+            actions: vec![
+                action(n!("transfer"), vec![1, 2]),
+                action(n!("memo"), vec![3]),
+            ],
+            transaction_extensions: Vec::new(),
+        };
+        host.set_transaction(&trx).unwrap();
+        host.set_context_free_data(vec![vec![9, 8, 7], Vec::new()]);
+
+        host.call(|| {
+            let current = current_transaction().unwrap();
+            assert_eq!(current.header, trx.header);
+            assert_eq!(current.actions.len(), 2);
+            assert_eq!(expiration(), TimePointSec::from_secs(1_000));
+            assert_eq!(tapos_block_num(), 42);
+            assert_eq!(tapos_block_prefix(), 0xdead_beef);
+
+            let names: Vec<_> = transaction_actions(ActionKind::Regular)
+                .map(|action| action.unwrap().name)
+                .collect();
+            assert_eq!(
+                names,
+                vec![
+                    ActionName::new(n!("transfer")),
+                    ActionName::new(n!("memo"))
+                ]
+            );
+            let nonce =
+                get_action(ActionKind::ContextFree, 0).unwrap().unwrap();
+            assert_eq!(nonce.data, vec![7]);
+            assert!(get_action(ActionKind::ContextFree, 1).unwrap().is_none());
+
+            assert_eq!(context_free_data(0), Some(vec![9, 8, 7]));
+            assert_eq!(context_free_data(1), Some(Vec::new()));
+            assert_eq!(context_free_data(2), None);
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+// the action macros emit `cfg(feature = "serde")` attributes
+#![allow(unexpected_cfgs)]
+
+use eosio::{
+    n, s, AbiAction, AbiActionResult, AbiErrorMessage, AbiRicardianClause,
+    AccountName, ActionFn, Asset, ContractError, PermissionLevel,
+    PermissionName, Symbol,
+};
+use eosio_cdt::{mock::MockHost, print};
+
+#[eosio::action(auth = "from", ricardian = "tests/ricardian/transfer.md")]
+fn transfer(
+    from: AccountName,
+    to: AccountName,
+    #[eosio(valid, positive)] quantity: Asset,
+    #[eosio(max_len = 8)] memo: String,
+) {
+    print!(from, " ", to, " ", quantity.amount, " ", memo.as_str());
+}
+
+#[eosio::action]
+fn retire(
+    #[eosio(
+        valid = "invalid symbol name",
+        positive = "must retire positive quantity"
+    )]
+    quantity: Asset,
+) {
+    print!(quantity.amount);
+}
+
+#[derive(ContractError)]
+enum TokenError {
+    #[eosio(code = 100)]
+    Overdrawn,
+    #[eosio(message = "cannot withdraw nothing")]
+    NothingToWithdraw,
+}
+
+#[eosio::action]
+fn withdraw(balance: u64, amount: u64) -> Result<u64, TokenError> {
+    if amount == 0 {
+        return Err(TokenError::NothingToWithdraw);
+    }
+    balance.checked_sub(amount).ok_or(TokenError::Overdrawn)
+}
+
+eosio::ricardian_clauses! {
+    "Data Storage" => "tests/ricardian/data_storage.md",
+}
+
+fn active(actor: u64) -> PermissionLevel {
+    PermissionLevel {
+        actor: AccountName::new(actor),
+        permission: PermissionName::new(n!("active")),
+    }
+}
+
+fn transfer_of(amount: i64, memo: &str) -> Transfer {
+    Transfer {
+        from: AccountName::new(n!("alice")),
+        to: AccountName::new(n!("bob")),
+        quantity: Asset {
+            amount,
+            symbol: s!(4, "EOS").into(),
+        },
+        memo: memo.into(),
+    }
+}
+
+#[test]
+fn requires_auth_and_checks_arguments() {
+    assert_eq!(Transfer::REQUIRED_AUTH, &["from"]);
+    let host = MockHost::new();
+    let alice = [active(n!("alice"))];
+    host.push_action(n!("token"), &alice, transfer_of(5, "hi"))
+        .unwrap();
+    assert_eq!(host.printed(), "alice bob 5 hi");
+
+    let err = host
+        .push_action(n!("token"), &[active(n!("bob"))], transfer_of(5, "hi"))
+        .unwrap_err();
+    assert_eq!(err, "missing authority of alice");
+    let err = host
+        .push_action(n!("token"), &alice, transfer_of(0, "hi"))
+        .unwrap_err();
+    assert_eq!(err, "quantity must be positive");
+    let mut invalid = transfer_of(5, "hi");
+    // lowercase symbol codes are invalid
+    invalid.quantity.symbol = Symbol::new(u64::from(b'a') << 8 | 4);
+    let err = host.push_action(n!("token"), &alice, invalid).unwrap_err();
+    assert_eq!(err, "invalid quantity");
+    let err = host
+        .push_action(n!("token"), &alice, transfer_of(5, "a long memo"))
+        .unwrap_err();
+    assert_eq!(err, "memo has more than 8 bytes");
+}
+
+#[test]
+fn checks_arguments_with_custom_messages() {
+    let host = MockHost::new();
+    let retire_of = |amount| Retire {
+        quantity: transfer_of(amount, "").quantity,
+    };
+    host.push_action(n!("token"), &[], retire_of(5)).unwrap();
+    assert_eq!(host.printed(), "5");
+    let err = host
+        .push_action(n!("token"), &[], retire_of(0))
+        .unwrap_err();
+    assert_eq!(err, "must retire positive quantity");
+    let mut invalid = retire_of(5);
+    invalid.quantity.symbol = Symbol::new(u64::from(b'a') << 8 | 4);
+    let err = host.push_action(n!("token"), &[], invalid).unwrap_err();
+    assert_eq!(err, "invalid symbol name");
+}
+
+#[test]
+fn includes_ricardian_contracts() {
+    let action = AbiAction::new::<Transfer>();
+    assert_eq!(action.name, "transfer");
+    assert_eq!(action.type_, "transfer");
+    assert_eq!(
+        action.ricardian_contract,
+        include_str!("ricardian/transfer.md")
+    );
+
+    let clauses: Vec<AbiRicardianClause> =
+        RICARDIAN_CLAUSES.iter().copied().map(Into::into).collect();
+    assert_eq!(
+        clauses,
+        vec![AbiRicardianClause {
+            id: "Data Storage".into(),
+            body: include_str!("ricardian/data_storage.md").into(),
+        }]
+    );
+}
+
+#[test]
+fn aborts_with_error_codes() {
+    let host = MockHost::new();
+    host.push_action(
+        n!("token"),
+        &[],
+        Withdraw {
+            balance: 5,
+            amount: 2,
+        },
+    )
+    .unwrap();
+    assert_eq!(host.action_return_value::<u64>(), Ok(3));
+
+    let err = host
+        .push_action(
+            n!("token"),
+            &[],
+            Withdraw {
+                balance: 1,
+                amount: 2,
+            },
+        )
+        .unwrap_err();
+    if cfg!(feature = "verbose") {
+        assert_eq!(err, "assertion failure with message: overdrawn");
+    } else {
+        assert_eq!(err, "assertion failure with error code: 100");
+    }
+    assert_eq!(
+        AbiErrorMessage::all::<TokenError>(),
+        vec![
+            AbiErrorMessage {
+                error_code: 100,
+                error_msg: "overdrawn".into(),
+            },
+            AbiErrorMessage {
+                error_code: 101,
+                error_msg: "cannot withdraw nothing".into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn describes_action_results() {
+    assert_eq!(
+        AbiActionResult::new::<Withdraw>(),
+        Some(AbiActionResult {
+            name: "withdraw".into(),
+            result_type: "uint64".into(),
+        })
+    );
+    assert_eq!(AbiActionResult::new::<Transfer>(), None);
+}
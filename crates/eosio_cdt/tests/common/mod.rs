@@ -0,0 +1,94 @@
+//! Tables and fixtures shared by the table tests.
+#![allow(dead_code)]
+use eosio::{n, AccountName, KvTable, NumBytes, Read, ReadError, Table, Write};
+use eosio_cdt::{mock::MockHost, KvPrimaryIndexExt, TableIndex};
+
+/// The contract that owns the tables.
+pub const CODE: u64 = n!("tables");
+
+#[derive(Table, Read, Write, NumBytes, Debug, Clone, Copy, PartialEq)]
+#[eosio(table_name = "scores")]
+pub struct Score {
+    #[eosio(primary_key)]
+    pub id: u64,
+    #[eosio(secondary_key)]
+    pub score: f64,
+}
+
+#[derive(Read, Write, NumBytes, KvTable, Debug, Clone, PartialEq)]
+#[eosio(table_name = "people")]
+pub struct Person {
+    #[eosio(primary_key)]
+    pub account: AccountName,
+    #[eosio(secondary_key = "byname")]
+    pub name: String,
+    #[eosio(secondary_key)]
+    pub age: i32,
+}
+
+pub fn person(account: u64, name: &str, age: i32) -> Person {
+    Person {
+        account: AccountName::new(account),
+        name: name.to_string(),
+        age,
+    }
+}
+
+/// Returns a fresh mock host that runs as `CODE`.
+pub fn host() -> MockHost {
+    let host = MockHost::new();
+    host.set_receiver(CODE);
+    host
+}
+
+/// Returns a host with four scores: 1 => 0.5, 3 => -2, 5 => 7, 7 => 1.5.
+pub fn scores() -> MockHost {
+    let host = host();
+    host.call(|| {
+        let table = Score::table(CODE, CODE);
+        let payer = AccountName::new(CODE);
+        for (id, score) in &[(1, 0.5), (3, -2.0), (5, 7.0), (7, 1.5)] {
+            let row = Score {
+                id: *id,
+                score: *score,
+            };
+            table.emplace(payer, row).unwrap();
+        }
+    })
+    .unwrap();
+    host
+}
+
+/// Returns a host with carol and alice aged 30, dave aged -1 and bob aged
+/// 25.
+pub fn people() -> MockHost {
+    let host = host();
+    host.call(|| {
+        let table = Person::kv_table(CODE);
+        let payer = AccountName::new(CODE);
+        for row in &[
+            person(n!("carol"), "Carol", 30),
+            person(n!("alice"), "Alice", 30),
+            person(n!("dave"), "Dave", -1),
+            person(n!("bob"), "Bob", 25),
+        ] {
+            table.set(payer, row).unwrap();
+        }
+    })
+    .unwrap();
+    host
+}
+
+pub fn ids<I>(rows: I) -> Vec<u64>
+where
+    I: Iterator<Item = Result<Score, ReadError>>,
+{
+    rows.map(|row| row.unwrap().id).collect()
+}
+
+pub fn accounts<I>(rows: I) -> Vec<AccountName>
+where
+    I: Iterator<Item = Result<Person, ReadError>>,
+{
+    rows.map(|row| row.unwrap().account).collect()
+}
@@ -0,0 +1,133 @@
+// the contract macros emit `cfg(feature = "serde")` attributes
+#![allow(unexpected_cfgs)]
+
+use eosio::{
+    n, AbiActionResult, AccountName, ActionName, Contract, ContractContext,
+    ContractError,
+};
+use eosio_cdt::{mock::MockHost, print};
+
+struct Counter {
+    context: ContractContext,
+    step: u64,
+}
+
+impl Contract for Counter {
+    fn new(context: ContractContext) -> Self {
+        print!("new ");
+        Self { context, step: 10 }
+    }
+
+    fn finalize(self) {
+        print!(" finalize");
+    }
+}
+
+#[derive(ContractError)]
+enum CounterError {
+    Underflow,
+}
+
+#[eosio::contract]
+impl Counter {
+    #[action]
+    fn increment(&mut self, value: u64) -> u64 {
+        print!(self.context.receiver, " ", self.context.action);
+        value + self.step
+    }
+
+    #[action]
+    fn decrement(&mut self, value: u64) -> Result<u64, CounterError> {
+        value.checked_sub(self.step).ok_or(CounterError::Underflow)
+    }
+
+    #[action("setstep")]
+    fn set_step(&mut self, step: u64) {
+        self.step = step;
+        print!("step ", self.step);
+    }
+
+    #[on_notify("eosio.token::transfer")]
+    fn on_transfer(&mut self, first_receiver: AccountName, amount: u64) {
+        print!(self.context.code(), " ", first_receiver, " ", amount);
+    }
+}
+
+#[test]
+fn methods_run_as_actions() {
+    let host = MockHost::new();
+    host.push_action(n!("counter"), &[], Increment { value: 5 })
+        .unwrap();
+    assert_eq!(host.printed(), "new counter increment finalize");
+    assert_eq!(host.action_return_value::<u64>(), Ok(15));
+
+    host.push_action(n!("counter"), &[], SetStep { step: 3 })
+        .unwrap();
+    assert_eq!(host.printed(), "new step 3 finalize");
+    assert_eq!(
+        <SetStep as eosio::ActionFn>::NAME,
+        ActionName::new(n!("setstep"))
+    );
+}
+
+#[test]
+fn describes_action_results() {
+    let result = |name: &str| AbiActionResult {
+        name: name.into(),
+        result_type: "uint64".into(),
+    };
+    assert_eq!(
+        AbiActionResult::new::<Increment>(),
+        Some(result("increment"))
+    );
+    assert_eq!(
+        AbiActionResult::new::<Decrement>(),
+        Some(result("decrement"))
+    );
+    assert_eq!(AbiActionResult::new::<SetStep>(), None);
+}
+
+#[test]
+fn errors_abort_the_action() {
+    let host = MockHost::new();
+    host.push_action(n!("counter"), &[], Decrement { value: 15 })
+        .unwrap();
+    assert_eq!(host.printed(), "new  finalize");
+    assert_eq!(host.action_return_value::<u64>(), Ok(5));
+
+    let err = host
+        .push_action(n!("counter"), &[], Decrement { value: 5 })
+        .unwrap_err();
+    if cfg!(feature = "verbose") {
+        assert_eq!(err, "assertion failure with message: underflow");
+    } else {
+        assert_eq!(err, "assertion failure with error code: 0");
+    }
+}
+
+#[test]
+fn methods_handle_notifications() {
+    let host = MockHost::new();
+    host.push_notification(
+        dispatch,
+        n!("eosio.token"),
+        n!("counter"),
+        &[],
+        OnTransfer { amount: 7 },
+    )
+    .unwrap();
+    assert_eq!(host.printed(), "new eosio.token eosio.token 7 finalize");
+    let err = host
+        .push_notification(
+            dispatch,
+            n!("fake.token"),
+            n!("counter"),
+            &[],
+            OnTransfer { amount: 7 },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "unexpected notification of 'transfer' from 'fake.token'"
+    );
+}
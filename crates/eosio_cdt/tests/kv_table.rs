@@ -0,0 +1,127 @@
+//! The typed kv table API, run against the mock host's kv database.
+mod common;
+
+use common::{accounts, people, person, Person, CODE};
+use eosio::{n, AbiKvIndex, AbiKvTable, AccountName, KvTable};
+use eosio_cdt::{KvPrimaryIndexExt, KvSecondaryIndexExt};
+
+#[test]
+fn primary_index() {
+    let host = people();
+    host.call(|| {
+        let table = Person::kv_table(CODE);
+        let alice = AccountName::new(n!("alice"));
+        let bob = AccountName::new(n!("bob"));
+        let carol = AccountName::new(n!("carol"));
+        let dave = AccountName::new(n!("dave"));
+        assert_eq!(
+            table.get(&alice).unwrap(),
+            Some(person(n!("alice"), "Alice", 30))
+        );
+        assert!(table.contains(&bob));
+        assert!(!table.contains(&AccountName::new(n!("eve"))));
+        assert_eq!(accounts(table.iter()), vec![alice, bob, carol, dave]);
+        assert_eq!(accounts(table.iter().rev()), vec![dave, carol, bob, alice]);
+        assert_eq!(accounts(table.range(bob..dave)), vec![bob, carol]);
+        assert_eq!(accounts(table.range(..=bob).rev()), vec![bob, alice]);
+        assert_eq!(accounts(table.range(dave..bob)), vec![]);
+
+        let mut range = table.range(alice..=dave);
+        assert_eq!(range.next().unwrap().unwrap().account, alice);
+        assert_eq!(range.next_back().unwrap().unwrap().account, dave);
+        assert_eq!(accounts(range), vec![bob, carol]);
+
+        assert_eq!(table.erase(&bob).unwrap().unwrap().account, bob);
+        assert_eq!(table.erase(&bob).unwrap(), None);
+        assert_eq!(accounts(table.iter()), vec![alice, carol, dave]);
+    })
+    .unwrap();
+}
+
+#[test]
+fn secondary_indexes() {
+    let host = people();
+    host.call(|| {
+        let by_age = Person::by_age(CODE);
+        let by_name = Person::by_name(CODE);
+        let alice = AccountName::new(n!("alice"));
+        let bob = AccountName::new(n!("bob"));
+        let carol = AccountName::new(n!("carol"));
+        let dave = AccountName::new(n!("dave"));
+        assert_eq!(accounts(by_age.iter()), vec![dave, bob, alice, carol]);
+        assert_eq!(accounts(by_age.range(25..=30)), vec![bob, alice, carol]);
+        assert_eq!(accounts(by_age.range(30..)), vec![alice, carol]);
+        assert_eq!(accounts(by_age.range(..30).rev()), vec![bob, dave]);
+        assert_eq!(by_age.get(&30).unwrap().unwrap().account, alice);
+        assert_eq!(by_age.get(&31).unwrap(), None);
+        assert_eq!(
+            accounts(by_name.range("B".to_string()..)),
+            vec![bob, carol, dave]
+        );
+
+        // changing a key moves the row in that index only
+        let table = Person::kv_table(CODE);
+        let payer = AccountName::new(CODE);
+        table.set(payer, &person(n!("alice"), "Zed", 30)).unwrap();
+        assert_eq!(accounts(by_name.iter()), vec![bob, carol, dave, alice]);
+        assert_eq!(accounts(by_age.range(30..)), vec![alice, carol]);
+        table.erase(&carol).unwrap();
+        assert_eq!(accounts(by_age.range(30..)), vec![alice]);
+    })
+    .unwrap();
+
+    let rows = host.with(|host| host.kv.rows(CODE));
+    // four rows with two secondary entries each, minus the erased row
+    assert_eq!(rows.len(), 9);
+    assert!(rows.iter().all(|(_, row)| row.payer == CODE));
+}
+
+#[test]
+fn payers_and_access() {
+    let host = people();
+    let alice = AccountName::new(n!("alice"));
+    host.call(|| {
+        Person::kv_table(CODE)
+            .set(alice, &person(n!("alice"), "Alice", 31))
+            .unwrap();
+    })
+    .unwrap();
+    let payers = host.with(|host| {
+        host.kv
+            .rows(CODE)
+            .into_iter()
+            .filter(|(_, row)| row.payer == alice.as_u64())
+            .count()
+    });
+    assert_eq!(payers, 3);
+
+    host.set_receiver(alice);
+    let err = host
+        .call(|| {
+            Person::kv_table(CODE)
+                .set(alice, &person(n!("alice"), "Alice", 32))
+                .unwrap();
+        })
+        .unwrap_err();
+    assert!(err.starts_with("kv access violation"), "{}", err);
+}
+
+#[test]
+fn describes_abi() {
+    let index = |name: &str, type_: &str| AbiKvIndex {
+        name: name.into(),
+        type_: type_.into(),
+    };
+    assert_eq!(
+        AbiKvTable::new::<Person>(),
+        AbiKvTable {
+            name: "people".into(),
+            type_: "person".into(),
+            primary_index: index("account", "name"),
+            secondary_indices: vec![
+                index("byname", "string"),
+                index("age", "int32")
+            ],
+        }
+    );
+}
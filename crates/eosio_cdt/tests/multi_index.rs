@@ -0,0 +1,109 @@
+//! Row caching and write-back of `MultiIndex`.
+mod common;
+
+use common::{host, Score, CODE};
+use eosio::{AccountName, Table};
+use eosio_cdt::{
+    MultiIndex, Payer, PrimaryTableIndexExt, TableCursor, TableIndex,
+};
+
+#[test]
+fn caches_and_writes_back() {
+    let host = host();
+    host.call(|| {
+        let scores = MultiIndex::<Score>::new(CODE, CODE);
+        let payer = AccountName::new(CODE);
+        scores.emplace(payer, Score { id: 1, score: 10.0 }).unwrap();
+        scores.emplace(payer, Score { id: 2, score: 20.0 }).unwrap();
+
+        {
+            let mut score = scores.modify(1, Payer::Same).unwrap().unwrap();
+            score.score = 30.0;
+        }
+        let result = scores
+            .modify_with(2, Payer::Same, |score| {
+                score.score += 5.0;
+                score.score
+            })
+            .unwrap();
+        assert_eq!(result, Some(25.0));
+        assert_eq!(scores.modify_with(3, Payer::Same, |_| ()), Ok(None));
+
+        let table = Score::table(CODE, CODE);
+        let by_score = Score::by_score(CODE, CODE);
+        assert_eq!(table.find(1_u64).unwrap().get().unwrap().score, 30.0);
+        assert!(by_score.find(10.0).is_none());
+        assert_eq!(by_score.find(30.0).unwrap().get().unwrap().id, 1);
+        assert_eq!(by_score.find(25.0).unwrap().get().unwrap().id, 2);
+
+        // rows are served from the cache
+        let cursor = table.find(1_u64).unwrap();
+        cursor
+            .modify(Payer::Same, Score { id: 1, score: 40.0 })
+            .unwrap();
+        assert_eq!(scores.get(1).unwrap().unwrap().score, 30.0);
+        assert_eq!(
+            MultiIndex::<Score>::new(CODE, CODE).get(1).unwrap(),
+            Some(Score { id: 1, score: 40.0 })
+        );
+
+        assert_eq!(scores.erase(2).unwrap().unwrap().score, 25.0);
+        assert!(!scores.exists(2));
+        assert!(by_score.find(25.0).is_none());
+        assert_eq!(table.count(), 1);
+    })
+    .unwrap();
+}
+
+#[test]
+fn rejects_primary_key_changes() {
+    let host = host();
+    let err = host
+        .call(|| {
+            let scores = MultiIndex::<Score>::new(CODE, CODE);
+            let payer = AccountName::new(CODE);
+            scores.emplace(payer, Score { id: 1, score: 10.0 }).unwrap();
+            scores
+                .modify_with(1, Payer::Same, |score| score.id = 2)
+                .unwrap();
+        })
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "assertion failure with message: updater cannot change primary key \
+         when modifying an object"
+    );
+}
+
+#[test]
+fn rejects_double_borrows() {
+    let host = host();
+    let err = host
+        .call(|| {
+            let scores = MultiIndex::<Score>::new(CODE, CODE);
+            let payer = AccountName::new(CODE);
+            scores.emplace(payer, Score { id: 1, score: 10.0 }).unwrap();
+            let mut first = scores.modify(1, Payer::Same).unwrap().unwrap();
+            first.score = 20.0;
+            let _second = scores.modify(1, Payer::Same);
+        })
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "assertion failure with message: cannot access a row that is \
+         borrowed for modification"
+    );
+
+    host.call(|| {
+        let scores = MultiIndex::<Score>::new(CODE, CODE);
+        let payer = AccountName::new(CODE);
+        scores.emplace(payer, Score { id: 1, score: 10.0 }).unwrap();
+        scores.modify(1, Payer::Same).unwrap().unwrap().score = 20.0;
+        // the guard was dropped, so the row can be borrowed again
+        scores
+            .modify_with(1, Payer::Same, |row| row.score += 1.0)
+            .unwrap();
+        assert_eq!(scores.get(1).unwrap().unwrap().score, 21.0);
+    })
+    .unwrap();
+}
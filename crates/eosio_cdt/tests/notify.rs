@@ -0,0 +1,80 @@
+//! Notification handlers, run through the dispatcher generated by `abi!`.
+// the action macros emit `cfg(feature = "serde")` attributes
+#![allow(unexpected_cfgs)]
+
+use eosio::{n, AccountName};
+use eosio_cdt::{mock::MockHost, print};
+
+#[eosio::on_notify("eosio.token::transfer")]
+fn deposit(first_receiver: AccountName, amount: u64) {
+    print!("deposit of ", amount, " from ", first_receiver);
+}
+
+#[eosio::on_notify("*::transfer")]
+fn any_transfer(first_receiver: AccountName, amount: u64) {
+    print!("transfer of ", amount, " from ", first_receiver);
+}
+
+#[eosio::on_notify("*::issue")]
+fn any_issue(first_receiver: AccountName, amount: u64) {
+    print!("issue of ", amount, " from ", first_receiver);
+}
+
+#[eosio::on_notify("eosio.token::retire")]
+fn retire(first_receiver: AccountName, amount: u64) {
+    print!("retire of ", amount, " from ", first_receiver);
+}
+
+eosio::abi!(notify Deposit, notify AnyTransfer, notify AnyIssue);
+
+#[test]
+fn specific_handlers_take_precedence() {
+    let host = MockHost::new();
+    host.push_notification(
+        dispatch,
+        n!("eosio.token"),
+        n!("exchange"),
+        &[],
+        Deposit { amount: 5 },
+    )
+    .unwrap();
+    assert_eq!(host.printed(), "deposit of 5 from eosio.token");
+
+    host.push_notification(
+        dispatch,
+        n!("fake.token"),
+        n!("exchange"),
+        &[],
+        Deposit { amount: 5 },
+    )
+    .unwrap();
+    assert_eq!(host.printed(), "transfer of 5 from fake.token");
+}
+
+#[test]
+fn wildcard_handlers_accept_any_contract() {
+    let host = MockHost::new();
+    host.push_notification(
+        dispatch,
+        n!("other.token"),
+        n!("exchange"),
+        &[],
+        AnyIssue { amount: 3 },
+    )
+    .unwrap();
+    assert_eq!(host.printed(), "issue of 3 from other.token");
+}
+
+#[test]
+fn ignores_unhandled_notifications() {
+    let host = MockHost::new();
+    host.push_notification(
+        dispatch,
+        n!("eosio.token"),
+        n!("exchange"),
+        &[],
+        Retire { amount: 1 },
+    )
+    .unwrap();
+    assert_eq!(host.printed(), "");
+}
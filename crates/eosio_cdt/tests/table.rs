@@ -0,0 +1,103 @@
+use eosio::{
+    n, s, AccountName, NumBytes, Read, ReadError, Symbol, SymbolCode, Table,
+    Write,
+};
+use eosio_cdt::{
+    mock::MockHost, PrimaryTableIndexExt, SecondaryTableIndexExt, TableIndex,
+};
+
+const CODE: u64 = n!("market");
+
+#[derive(Table, Read, Write, NumBytes, Debug, Clone)]
+#[eosio(table_name = "orders")]
+#[eosio(secondary_key(
+    name = "byprice",
+    with = "Self::price_key",
+    ty = "u128",
+    index = 1
+))]
+struct Order {
+    #[eosio(primary_key)]
+    id: u64,
+    #[eosio(secondary_key(index = 0))]
+    owner: u64,
+    price: u64,
+    amount: u64,
+}
+
+impl Order {
+    /// Orders by descending price, then by id.
+    fn price_key(row: &Self) -> u128 {
+        u128::from(u64::MAX - row.price) << 64 | u128::from(row.id)
+    }
+}
+
+#[eosio::table("accounts", scope = AccountName)]
+struct Balance {
+    #[eosio(primary_key)]
+    symbol: SymbolCode,
+    amount: u64,
+}
+
+#[eosio::table("config", singleton, scope = SymbolCode)]
+struct Config {
+    fee: u64,
+}
+
+fn ids<I>(rows: I) -> Vec<u64>
+where
+    I: Iterator<Item = Result<Order, ReadError>>,
+{
+    rows.map(|row| row.unwrap().id).collect()
+}
+
+#[test]
+fn computed_and_positioned_secondary_keys() {
+    let host = MockHost::new();
+    host.set_receiver(CODE);
+    host.call(|| {
+        let table = Order::table(CODE, CODE);
+        let payer = AccountName::new(CODE);
+        for &(id, owner, price) in &[(1, 30, 5), (2, 10, 9), (3, 20, 5)] {
+            let order = Order {
+                id,
+                owner,
+                price,
+                amount: 1,
+            };
+            table.emplace(payer, order).unwrap();
+        }
+
+        let by_price = Order::by_byprice(CODE, CODE);
+        assert_eq!(by_price.table.index(), 1);
+        assert_eq!(ids(by_price.iter()), vec![2, 1, 3]);
+        let by_owner = Order::by_owner(CODE, CODE);
+        assert_eq!(by_owner.table.index(), 0);
+        assert_eq!(ids(by_owner.iter()), vec![2, 3, 1]);
+    })
+    .unwrap();
+}
+
+#[test]
+fn typed_scopes() {
+    assert_eq!(Balance::SCOPE_TYPE, Some("AccountName"));
+    assert_eq!(Config::SCOPE_TYPE, Some("SymbolCode"));
+    assert_eq!(Order::SCOPE_TYPE, None);
+
+    let host = MockHost::new();
+    host.set_receiver(CODE);
+    host.call(|| {
+        let alice = AccountName::new(n!("alice"));
+        let symbol = Symbol::from(s!(4, "EOS")).code();
+        let payer = AccountName::new(CODE);
+        let balance = Balance { symbol, amount: 5 };
+        Balance::table(CODE, alice).emplace(payer, balance).unwrap();
+        let rows = Balance::table(CODE, alice).iter().count();
+        assert_eq!(rows, 1);
+
+        let config = Config::singleton(CODE, symbol);
+        config.set(&Config { fee: 3 }, payer).unwrap();
+        assert_eq!(config.get().unwrap().unwrap().fee, 3);
+    })
+    .unwrap();
+}
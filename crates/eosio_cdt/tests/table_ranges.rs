@@ -0,0 +1,139 @@
+//! Ranges, reverse iteration and bulk helpers of table indexes.
+mod common;
+
+use common::{ids, scores, Score, CODE};
+use eosio::{n, AccountName, Table};
+use eosio_cdt::{
+    Payer, PrimaryTableIndexExt, SecondaryTableIndexExt, TableCursor,
+    TableIndex,
+};
+
+#[test]
+fn primary_ranges() {
+    let host = scores();
+    host.call(|| {
+        let table = Score::table(CODE, CODE);
+        assert_eq!(ids(table.iter()), vec![1, 3, 5, 7]);
+        assert_eq!(ids(table.rev()), vec![7, 5, 3, 1]);
+        assert_eq!(ids(table.range(3..7)), vec![3, 5]);
+        assert_eq!(ids(table.range(2..=7)), vec![3, 5, 7]);
+        assert_eq!(ids(table.range(..=3).rev()), vec![3, 1]);
+        assert_eq!(ids(table.range(4..)), vec![5, 7]);
+        assert!(ids(table.range(6..4)).is_empty());
+        assert!(ids(table.range(4..5)).is_empty());
+        assert_eq!(table.first().unwrap().unwrap().id, 1);
+        assert_eq!(table.last().unwrap().unwrap().id, 7);
+        assert_eq!(table.count(), 4);
+
+        let mut range = table.range(1..=5);
+        assert_eq!(range.next().unwrap().unwrap().id, 1);
+        assert_eq!(range.next_back().unwrap().unwrap().id, 5);
+        assert_eq!(range.next_back().unwrap().unwrap().id, 3);
+        assert!(range.next().is_none());
+
+        let empty = Score::table(CODE, n!("empty"));
+        assert!(empty.iter().next().is_none());
+        assert!(empty.last().is_none());
+    })
+    .unwrap();
+}
+
+#[test]
+fn secondary_ranges() {
+    let host = scores();
+    host.call(|| {
+        let index = Score::by_score(CODE, CODE);
+        assert_eq!(ids(index.iter()), vec![3, 1, 7, 5]);
+        assert_eq!(ids(index.rev()), vec![5, 7, 1, 3]);
+        assert_eq!(ids(index.range(0.0..7.0)), vec![1, 7]);
+        assert_eq!(ids(index.range(..=1.5).rev()), vec![7, 1, 3]);
+        assert_eq!(ids(index.range(1.0..)), vec![7, 5]);
+        assert!(ids(index.range(2.0..1.0)).is_empty());
+        assert_eq!(index.first().unwrap().unwrap().id, 3);
+        assert_eq!(index.last().unwrap().unwrap().id, 5);
+    })
+    .unwrap();
+}
+
+#[test]
+fn modify_with_and_upsert() {
+    let host = scores();
+    host.call(|| {
+        let table = Score::table(CODE, CODE);
+        let payer = AccountName::new(CODE);
+        table
+            .find(1_u64)
+            .unwrap()
+            .modify_with(Payer::Same, |row| row.score = 9.0)
+            .unwrap();
+        assert_eq!(ids(Score::by_score(CODE, CODE).range(8.0..)), vec![1]);
+
+        let default = Score { id: 9, score: 0.0 };
+        table
+            .upsert(9_u64, payer, default, |row| row.score += 1.0)
+            .unwrap();
+        table
+            .upsert(9_u64, payer, default, |row| row.score += 1.0)
+            .unwrap();
+        assert_eq!(table.last().unwrap().unwrap().score, 2.0);
+        assert_eq!(table.count(), 5);
+    })
+    .unwrap();
+}
+
+#[test]
+fn upsert_rejects_primary_key_changes() {
+    let host = scores();
+    let err = host
+        .call(|| {
+            let default = Score { id: 1, score: 0.0 };
+            Score::table(CODE, CODE)
+                .upsert(1_u64, AccountName::new(CODE), default, |row| {
+                    row.id = 2
+                })
+                .unwrap();
+        })
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "assertion failure with message: updater cannot change primary key \
+         when modifying an object"
+    );
+
+    let err = host
+        .call(|| {
+            let default = Score { id: 9, score: 0.0 };
+            Score::table(CODE, CODE)
+                .upsert(9_u64, AccountName::new(CODE), default, |row| {
+                    row.id = 10
+                })
+                .unwrap();
+        })
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "assertion failure with message: upserted row has a different \
+         primary key"
+    );
+}
+
+#[test]
+fn erase_ranges() {
+    let host = scores();
+    host.call(|| {
+        let table = Score::table(CODE, CODE);
+        let by_score = Score::by_score(CODE, CODE);
+        assert_eq!(table.len_at_most(2), 2);
+        assert_eq!(table.len_at_most(10), 4);
+        assert_eq!(by_score.len_at_most(3), 3);
+
+        assert_eq!(table.erase_range(2.., 2).unwrap(), 2);
+        assert_eq!(ids(table.iter()), vec![1, 7]);
+        assert!(ids(by_score.range(..0.0)).is_empty());
+
+        assert_eq!(by_score.erase_range(..10.0, 10).unwrap(), 2);
+        assert!(table.is_empty());
+        assert!(by_score.is_empty());
+    })
+    .unwrap();
+}
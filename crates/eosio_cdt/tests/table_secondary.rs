@@ -0,0 +1,72 @@
+//! Secondary index regressions, run against the mock host.
+#![allow(unexpected_cfgs)]
+use eosio::{n, AccountName, Table};
+use eosio_cdt::{mock::MockHost, Payer, TableCursor, TableIndex};
+
+const CODE: u64 = n!("rows");
+
+#[eosio::table("rows")]
+struct Row {
+    #[eosio(primary_key)]
+    id: u64,
+    name: String,
+    #[eosio(secondary_key)]
+    key: u64,
+}
+
+#[eosio::table("others")]
+struct Other {
+    #[eosio(primary_key)]
+    id: u64,
+}
+
+fn row(id: u64, name: &str, key: u64) -> Row {
+    Row {
+        id,
+        name: name.into(),
+        key,
+    }
+}
+
+#[test]
+fn modify_updates_secondary_keys() {
+    let host = MockHost::new();
+    host.set_receiver(CODE);
+    host.call(|| {
+        let code = AccountName::new(CODE);
+        let table = Row::table(code, code);
+        table.emplace(code, row(1, "one", 10)).unwrap();
+        let cursor = table.find(1_u64).unwrap();
+        cursor.modify(Payer::Same, row(1, "one", 20)).unwrap();
+
+        let by_key = Row::by_key(code, code);
+        assert!(by_key.find(10_u64).is_none());
+        let cursor = by_key.find(20_u64).unwrap();
+        assert_eq!(cursor.get().unwrap().id, 1);
+    })
+    .unwrap();
+}
+
+#[test]
+fn secondary_cursors_read_their_rows() {
+    let host = MockHost::new();
+    host.set_receiver(CODE);
+    host.call(|| {
+        let code = AccountName::new(CODE);
+        // a row without secondary keys, so that primary and secondary
+        // iterators no longer line up
+        Other::table(code, code)
+            .emplace(code, Other { id: 1 })
+            .unwrap();
+        let table = Row::table(code, code);
+        table.emplace(code, row(1, "a longer name", 1)).unwrap();
+        table.emplace(code, row(2, "a", 2)).unwrap();
+
+        let by_key = Row::by_key(code, code);
+        let first = by_key.find(1_u64).unwrap().get().unwrap();
+        assert_eq!((first.id, first.name.as_str()), (1, "a longer name"));
+        let second = by_key.find(2_u64).unwrap().get().unwrap();
+        assert_eq!((second.id, second.name.as_str()), (2, "a"));
+    })
+    .unwrap();
+}
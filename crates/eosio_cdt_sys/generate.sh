@@ -82,4 +82,7 @@ bindgen \
     -- \
     -I ./eosio.cdt/libraries \
     --std=c++14
+# long double is quadruple precision on wasm32, but bindgen maps it to the
+# host's long double
+sed -i -E '/fn (db_idx_long_double_|printqf)/,/\)/ s/\*(const|mut) f64/*\1 u128/' src/bindings.rs
 popd
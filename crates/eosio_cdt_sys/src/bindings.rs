@@ -1662,7 +1662,7 @@ extern "C" {
         table: capi_name,
         payer: capi_name,
         id: u64,
-        secondary: *const f64,
+        secondary: *const u128,
     ) -> i32;
 }
 extern "C" {
@@ -1685,7 +1685,7 @@ extern "C" {
     pub fn db_idx_long_double_update(
         iterator: i32,
         payer: capi_name,
-        secondary: *const f64,
+        secondary: *const u128,
     );
 }
 extern "C" {
@@ -1760,7 +1760,7 @@ extern "C" {
         code: capi_name,
         scope: u64,
         table: capi_name,
-        secondary: *mut f64,
+        secondary: *mut u128,
         primary: u64,
     ) -> i32;
 }
@@ -1786,7 +1786,7 @@ extern "C" {
         code: capi_name,
         scope: u64,
         table: capi_name,
-        secondary: *const f64,
+        secondary: *const u128,
         primary: *mut u64,
     ) -> i32;
 }
@@ -1819,7 +1819,7 @@ extern "C" {
         code: capi_name,
         scope: u64,
         table: capi_name,
-        secondary: *mut f64,
+        secondary: *mut u128,
         primary: *mut u64,
     ) -> i32;
 }
@@ -1852,7 +1852,7 @@ extern "C" {
         code: capi_name,
         scope: u64,
         table: capi_name,
-        secondary: *mut f64,
+        secondary: *mut u128,
         primary: *mut u64,
     ) -> i32;
 }
@@ -2070,7 +2070,7 @@ extern "C" {
     #[doc = "  long double value = 5.0 / 10.0;"]
     #[doc = "  printqf(value); // Output: 0.5"]
     #[doc = "  @endcode"]
-    pub fn printqf(value: *const f64);
+    pub fn printqf(value: *const u128);
 }
 extern "C" {
     #[doc = " Prints a 64 bit names as base32 encoded string"]
@@ -0,0 +1,340 @@
+// Intrinsics that are not in the headers `bindgen` runs on, declared by hand.
+
+extern "C" {
+    #[doc = "  Sets the value returned by the current action, which is \
+             included in its trace. Requires the `ACTION_RETURN_VALUE` \
+             protocol feature."]
+    #[doc = ""]
+    #[doc = "  @param return_value - Pointer to the packed value"]
+    #[doc = "  @param size - Size of the packed value"]
+    pub fn set_action_return_value(
+        return_value: *mut crate::c_void,
+        size: usize,
+    );
+}
+
+// Crypto primitives, added by the `CRYPTO_PRIMITIVES` protocol feature
+
+extern "C" {
+    #[doc = "  Adds two alt_bn128 G1 points"]
+    #[doc = ""]
+    #[doc = "  @param op1 - First G1 point, as big-endian `x, y`"]
+    #[doc = "  @param op1_len - Size of the first point, must be 64"]
+    #[doc = "  @param op2 - Second G1 point"]
+    #[doc = "  @param op2_len - Size of the second point, must be 64"]
+    #[doc = "  @param result - Buffer for the resulting G1 point"]
+    #[doc = "  @param result_len - Size of the buffer, must be 64"]
+    #[doc = "  @return 0 on success, -1 if an input is invalid"]
+    pub fn alt_bn128_add(
+        op1: *const crate::c_char,
+        op1_len: u32,
+        op2: *const crate::c_char,
+        op2_len: u32,
+        result: *mut crate::c_char,
+        result_len: u32,
+    ) -> i32;
+}
+extern "C" {
+    #[doc = "  Multiplies an alt_bn128 G1 point by a scalar"]
+    #[doc = ""]
+    #[doc = "  @param g1 - G1 point, as big-endian `x, y`"]
+    #[doc = "  @param g1_len - Size of the point, must be 64"]
+    #[doc = "  @param scalar - Big-endian scalar"]
+    #[doc = "  @param scalar_len - Size of the scalar, must be 32"]
+    #[doc = "  @param result - Buffer for the resulting G1 point"]
+    #[doc = "  @param result_len - Size of the buffer, must be 64"]
+    #[doc = "  @return 0 on success, -1 if an input is invalid"]
+    pub fn alt_bn128_mul(
+        g1: *const crate::c_char,
+        g1_len: u32,
+        scalar: *const crate::c_char,
+        scalar_len: u32,
+        result: *mut crate::c_char,
+        result_len: u32,
+    ) -> i32;
+}
+extern "C" {
+    #[doc = "  Checks that the product of the pairings of alt_bn128 \
+             `(G1, G2)` pairs is one"]
+    #[doc = ""]
+    #[doc = "  @param pairs - Pairs of a 64 byte G1 point and a 128 byte G2 \
+             point"]
+    #[doc = "  @param pairs_len - Size of the pairs, a multiple of 192"]
+    #[doc = "  @return 0 if the check passes, 1 if it fails, -1 if an input \
+             is invalid"]
+    pub fn alt_bn128_pair(pairs: *const crate::c_char, pairs_len: u32) -> i32;
+}
+extern "C" {
+    #[doc = "  Computes `base ^ exp % mod` for big-endian integers"]
+    #[doc = ""]
+    #[doc = "  @param base - Base"]
+    #[doc = "  @param base_len - Size of the base"]
+    #[doc = "  @param exp - Exponent"]
+    #[doc = "  @param exp_len - Size of the exponent"]
+    #[doc = "  @param mod_ - Modulus"]
+    #[doc = "  @param mod_len - Size of the modulus"]
+    #[doc = "  @param result - Buffer for the result"]
+    #[doc = "  @param result_len - Size of the buffer, must equal `mod_len`"]
+    #[doc = "  @return 0 on success, -1 if an input is invalid"]
+    pub fn mod_exp(
+        base: *const crate::c_char,
+        base_len: u32,
+        exp: *const crate::c_char,
+        exp_len: u32,
+        mod_: *const crate::c_char,
+        mod_len: u32,
+        result: *mut crate::c_char,
+        result_len: u32,
+    ) -> i32;
+}
+extern "C" {
+    #[doc = "  BLAKE2b compression function `F`, as specified by EIP-152"]
+    #[doc = ""]
+    #[doc = "  @param rounds - Number of rounds"]
+    #[doc = "  @param state - State vector, 64 bytes"]
+    #[doc = "  @param state_len - Size of the state"]
+    #[doc = "  @param msg - Message block, 128 bytes"]
+    #[doc = "  @param msg_len - Size of the message block"]
+    #[doc = "  @param t0_offset - First offset counter, 8 bytes"]
+    #[doc = "  @param t0_len - Size of the first offset counter"]
+    #[doc = "  @param t1_offset - Second offset counter, 8 bytes"]
+    #[doc = "  @param t1_len - Size of the second offset counter"]
+    #[doc = "  @param final_ - 1 for the final block, 0 otherwise"]
+    #[doc = "  @param result - Buffer for the new state"]
+    #[doc = "  @param result_len - Size of the buffer, must be 64"]
+    #[doc = "  @return 0 on success, -1 if an input is invalid"]
+    pub fn blake2_f(
+        rounds: u32,
+        state: *const crate::c_char,
+        state_len: u32,
+        msg: *const crate::c_char,
+        msg_len: u32,
+        t0_offset: *const crate::c_char,
+        t0_len: u32,
+        t1_offset: *const crate::c_char,
+        t1_len: u32,
+        final_: i32,
+        result: *mut crate::c_char,
+        result_len: u32,
+    ) -> i32;
+}
+extern "C" {
+    #[doc = "  Hashes data with SHA3-256 or Keccak-256"]
+    #[doc = ""]
+    #[doc = "  @param data - Data to hash"]
+    #[doc = "  @param data_len - Size of the data"]
+    #[doc = "  @param hash - Buffer for the hash"]
+    #[doc = "  @param hash_len - Size of the buffer, must be 32"]
+    #[doc = "  @param keccak - 1 for Keccak-256, 0 for SHA3-256"]
+    pub fn sha3(
+        data: *const crate::c_char,
+        data_len: u32,
+        hash: *mut crate::c_char,
+        hash_len: u32,
+        keccak: i32,
+    );
+}
+extern "C" {
+    #[doc = "  Recovers the uncompressed secp256k1 public key that produced \
+             a signature"]
+    #[doc = ""]
+    #[doc = "  @param sig - Signature, as `v, r, s` with `v` from 27 to 34"]
+    #[doc = "  @param sig_len - Size of the signature, must be 65"]
+    #[doc = "  @param dig - Digest that was signed"]
+    #[doc = "  @param dig_len - Size of the digest, must be 32"]
+    #[doc = "  @param pub_ - Buffer for the public key"]
+    #[doc = "  @param pub_len - Size of the buffer, must be 65"]
+    #[doc = "  @return 0 on success, -1 if the key can't be recovered"]
+    pub fn k1_recover(
+        sig: *const crate::c_char,
+        sig_len: u32,
+        dig: *const crate::c_char,
+        dig_len: u32,
+        pub_: *mut crate::c_char,
+        pub_len: u32,
+    ) -> i32;
+}
+
+// Key-value database intrinsics, added in EOSIO 2.1
+
+extern "C" {
+    #[doc = "  Erases a key from the key-value database of a contract"]
+    #[doc = ""]
+    #[doc = "  @param contract - The contract that owns the key"]
+    #[doc = "  @param key - Pointer to the key"]
+    #[doc = "  @param key_size - Size of the key"]
+    #[doc = "  @return the change in RAM usage"]
+    pub fn kv_erase(
+        contract: u64,
+        key: *const crate::c_char,
+        key_size: u32,
+    ) -> i64;
+}
+extern "C" {
+    #[doc = "  Sets the value of a key in the key-value database of a contract"]
+    #[doc = ""]
+    #[doc = "  @param contract - The contract that owns the key"]
+    #[doc = "  @param key - Pointer to the key"]
+    #[doc = "  @param key_size - Size of the key"]
+    #[doc = "  @param value - Pointer to the value"]
+    #[doc = "  @param value_size - Size of the value"]
+    #[doc = "  @param payer - The account that pays for the RAM"]
+    #[doc = "  @return the change in RAM usage"]
+    pub fn kv_set(
+        contract: u64,
+        key: *const crate::c_char,
+        key_size: u32,
+        value: *const crate::c_char,
+        value_size: u32,
+        payer: u64,
+    ) -> i64;
+}
+extern "C" {
+    #[doc = "  Looks up a key and stages its value to be read by `kv_get_data`"]
+    #[doc = ""]
+    #[doc = "  @param contract - The contract that owns the key"]
+    #[doc = "  @param key - Pointer to the key"]
+    #[doc = "  @param key_size - Size of the key"]
+    #[doc = "  @param value_size - Set to the size of the value"]
+    #[doc = "  @return true if the key exists"]
+    pub fn kv_get(
+        contract: u64,
+        key: *const crate::c_char,
+        key_size: u32,
+        value_size: *mut u32,
+    ) -> bool;
+}
+extern "C" {
+    #[doc = "  Copies the value staged by the last call to `kv_get`"]
+    #[doc = ""]
+    #[doc = "  @param offset - Where to start copying from"]
+    #[doc = "  @param data - Pointer to the buffer to copy into"]
+    #[doc = "  @param data_size - Size of the buffer"]
+    #[doc = "  @return the size of the staged value"]
+    pub fn kv_get_data(
+        offset: u32,
+        data: *mut crate::c_char,
+        data_size: u32,
+    ) -> u32;
+}
+extern "C" {
+    #[doc = "  Creates an iterator over keys starting with a prefix. The new \
+             iterator is positioned at the end."]
+    #[doc = ""]
+    #[doc = "  @param contract - The contract that owns the keys"]
+    #[doc = "  @param prefix - Pointer to the prefix"]
+    #[doc = "  @param size - Size of the prefix"]
+    #[doc = "  @return the iterator"]
+    pub fn kv_it_create(
+        contract: u64,
+        prefix: *const crate::c_char,
+        size: u32,
+    ) -> u32;
+}
+extern "C" {
+    #[doc = "  Destroys an iterator"]
+    pub fn kv_it_destroy(itr: u32);
+}
+extern "C" {
+    #[doc = "  Returns the status of an iterator: 0 if it points to a key, -1 \
+             if its key was erased, and -2 if it is at the end"]
+    pub fn kv_it_status(itr: u32) -> i32;
+}
+extern "C" {
+    #[doc = "  Compares the positions of two iterators with the same prefix"]
+    #[doc = ""]
+    #[doc = "  @return -1, 0 or 1 if `itr_a` is before, at, or after `itr_b`"]
+    pub fn kv_it_compare(itr_a: u32, itr_b: u32) -> i32;
+}
+extern "C" {
+    #[doc = "  Compares the key of an iterator to another key"]
+    #[doc = ""]
+    #[doc = "  @return -1, 0 or 1 if the key of `itr` is less than, equal to \
+             or greater than `key`. Iterators at the end are greater than \
+             every key."]
+    pub fn kv_it_key_compare(
+        itr: u32,
+        key: *const crate::c_char,
+        size: u32,
+    ) -> i32;
+}
+extern "C" {
+    #[doc = "  Moves an iterator to the end"]
+    #[doc = ""]
+    #[doc = "  @return the new status of the iterator"]
+    pub fn kv_it_move_to_end(itr: u32) -> i32;
+}
+extern "C" {
+    #[doc = "  Moves an iterator to the next key. Iterators at the end move to \
+             the first key."]
+    #[doc = ""]
+    #[doc = "  @param found_key_size - Set to the size of the new key"]
+    #[doc = "  @param found_value_size - Set to the size of the new value"]
+    #[doc = "  @return the new status of the iterator"]
+    pub fn kv_it_next(
+        itr: u32,
+        found_key_size: *mut u32,
+        found_value_size: *mut u32,
+    ) -> i32;
+}
+extern "C" {
+    #[doc = "  Moves an iterator to the previous key. Iterators at the end \
+             move to the last key, and iterators at the first key move to \
+             the end."]
+    #[doc = ""]
+    #[doc = "  @param found_key_size - Set to the size of the new key"]
+    #[doc = "  @param found_value_size - Set to the size of the new value"]
+    #[doc = "  @return the new status of the iterator"]
+    pub fn kv_it_prev(
+        itr: u32,
+        found_key_size: *mut u32,
+        found_value_size: *mut u32,
+    ) -> i32;
+}
+extern "C" {
+    #[doc = "  Moves an iterator to the first key greater than or equal to \
+             `key`"]
+    #[doc = ""]
+    #[doc = "  @param found_key_size - Set to the size of the new key"]
+    #[doc = "  @param found_value_size - Set to the size of the new value"]
+    #[doc = "  @return the new status of the iterator"]
+    pub fn kv_it_lower_bound(
+        itr: u32,
+        key: *const crate::c_char,
+        size: u32,
+        found_key_size: *mut u32,
+        found_value_size: *mut u32,
+    ) -> i32;
+}
+extern "C" {
+    #[doc = "  Copies the key of an iterator"]
+    #[doc = ""]
+    #[doc = "  @param offset - Where to start copying from"]
+    #[doc = "  @param dest - Pointer to the buffer to copy into"]
+    #[doc = "  @param size - Size of the buffer"]
+    #[doc = "  @param actual_size - Set to the size of the key"]
+    #[doc = "  @return the status of the iterator"]
+    pub fn kv_it_key(
+        itr: u32,
+        offset: u32,
+        dest: *mut crate::c_char,
+        size: u32,
+        actual_size: *mut u32,
+    ) -> i32;
+}
+extern "C" {
+    #[doc = "  Copies the value of an iterator"]
+    #[doc = ""]
+    #[doc = "  @param offset - Where to start copying from"]
+    #[doc = "  @param dest - Pointer to the buffer to copy into"]
+    #[doc = "  @param size - Size of the buffer"]
+    #[doc = "  @param actual_size - Set to the size of the value"]
+    #[doc = "  @return the status of the iterator"]
+    pub fn kv_it_value(
+        itr: u32,
+        offset: u32,
+        dest: *mut crate::c_char,
+        size: u32,
+        actual_size: *mut u32,
+    ) -> i32;
+}
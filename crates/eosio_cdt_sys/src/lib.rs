@@ -9,6 +9,12 @@
 //! [`EOSIO/eosio.cdt`]: https://github.com/EOSIO/eosio.cdt
 //! [`eosio`]: https://crates.io/crates/eosio
 //! [`eosio_cdt`]: https://crates.io/crates/eosio_cdt
+//!
+//! When compiling for targets other than `wasm32`, or with the `mock`
+//! feature enabled, the intrinsics are replaced by the in-memory host in the
+//! [`mock`] module so that contracts can be tested with `cargo test`. The
+//! mock crypto primitives also need the `mock` feature, which pulls in the
+//! crates that implement them.
 #![no_std]
 #![allow(
     non_upper_case_globals,
@@ -24,17 +30,52 @@
     clippy::unseparated_literal_suffix
 )]
 
-// #[cfg(target_arch = "wasm32")]
+#[cfg(any(not(target_arch = "wasm32"), feature = "mock"))]
+#[macro_use]
+extern crate std;
+
+#[cfg(all(target_arch = "wasm32", not(feature = "mock")))]
 mod bindings;
 
-// #[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(feature = "mock")))]
 pub use self::bindings::*;
 
-// #[cfg(any(not(target_arch = "wasm32"), feature = "mock"))]
-// mod mock_bindings;
+#[cfg(all(target_arch = "wasm32", not(feature = "mock")))]
+mod extra_bindings;
+
+#[cfg(all(target_arch = "wasm32", not(feature = "mock")))]
+pub use self::extra_bindings::*;
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "mock"))]
+pub mod mock;
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "mock"))]
+#[allow(clippy::missing_safety_doc)]
+mod mock_bindings;
+
+#[cfg(feature = "mock")]
+mod mock_crypto;
+
+#[cfg(any(not(target_arch = "wasm32"), feature = "mock"))]
+pub use self::mock_bindings::*;
+
+/// Expands to a function pointer type with the ABI of the intrinsics, which
+/// is `C-unwind` for the mock host so that aborts can panic through it.
+#[cfg(all(target_arch = "wasm32", not(feature = "mock")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! intrinsic_fn {
+    ($($sig:tt)*) => { unsafe extern "C" fn $($sig)* };
+}
 
-// #[cfg(any(not(target_arch = "wasm32"), feature = "mock"))]
-// pub use self::mock_bindings::*;
+/// Expands to a function pointer type with the ABI of the intrinsics, which
+/// is `C-unwind` for the mock host so that aborts can panic through it.
+#[cfg(any(not(target_arch = "wasm32"), feature = "mock"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! intrinsic_fn {
+    ($($sig:tt)*) => { unsafe extern "C-unwind" fn $($sig)* };
+}
 
 pub use core::ffi::c_void;
 pub type c_char = u8;
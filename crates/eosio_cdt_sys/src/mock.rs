@@ -0,0 +1,1031 @@
+//! In-memory implementation of the host environment, used in place of the
+//! real intrinsics when compiling for targets other than `wasm32`.
+//!
+//! Every thread has its own [`Host`], so tests that run in parallel don't
+//! share state. Intrinsics that abort the action, like `eosio_assert`, panic
+//! with the error message.
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+    format,
+    ops::Bound,
+    string::String,
+    vec::Vec,
+};
+
+std::thread_local! {
+    static HOST: RefCell<Host> = RefCell::new(Host::default());
+}
+
+/// Calls `f` with the host of the current thread.
+///
+/// # Panics
+///
+/// Panics if called from within `f`.
+#[inline]
+pub fn with_host<R, F: FnOnce(&mut Host) -> R>(f: F) -> R {
+    HOST.with(|host| f(&mut host.borrow_mut()))
+}
+
+/// Resets the host of the current thread to its default state.
+#[inline]
+pub fn reset() {
+    with_host(|host| *host = Host::default());
+}
+
+/// Aborts the current action with a message, like a failed `eosio_assert`.
+#[inline]
+pub fn abort(msg: &str) -> ! {
+    std::panic::panic_any(String::from(msg))
+}
+
+/// The payload of the panic raised by `eosio_exit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exit(pub i32);
+
+/// Mock host state.
+#[derive(Debug, Clone, Default)]
+pub struct Host {
+    /// The account whose code is running
+    pub receiver: u64,
+    /// The packed data of the current action
+    pub action_data: Vec<u8>,
+    /// The packed value returned by the current action
+    pub action_return_value: Vec<u8>,
+    /// The `(actor, permission)` pairs that authorized the current action
+    pub authorizations: Vec<(u64, u64)>,
+    /// Accounts that exist
+    pub accounts: BTreeSet<u64>,
+    /// Privileged accounts
+    pub privileged: BTreeSet<u64>,
+    /// Digests of activated protocol features
+    pub activated_features: BTreeSet<[u8; 32]>,
+    /// Names of the active producers
+    pub active_producers: Vec<u64>,
+    /// Packed blockchain parameters
+    pub blockchain_parameters: Vec<u8>,
+    /// The last proposed producer schedule, packed
+    pub proposed_producers: Option<Vec<u8>>,
+    /// `(ram_bytes, net_weight, cpu_weight)` for each account
+    pub resource_limits: BTreeMap<u64, (i64, i64, i64)>,
+    /// Microseconds since the Unix epoch
+    pub current_time: u64,
+    /// Microseconds since the Unix epoch
+    pub publication_time: u64,
+    /// The account that sent the current inline action, if any
+    pub sender: u64,
+    /// The packed transaction that contains the current action
+    pub transaction: Vec<u8>,
+    /// Packed context free actions of the current transaction
+    pub context_free_actions: Vec<Vec<u8>>,
+    /// Packed actions of the current transaction
+    pub transaction_actions: Vec<Vec<u8>>,
+    /// Context free data of the current transaction
+    pub context_free_data: Vec<Vec<u8>>,
+    /// Seconds since the Unix epoch
+    pub expiration: u32,
+    /// The block number used for TAPoS by the current transaction
+    pub ref_block_num: u16,
+    /// The block prefix used for TAPoS by the current transaction
+    pub ref_block_prefix: u32,
+    /// Everything printed so far
+    pub output: String,
+    /// Accounts notified with `require_recipient`
+    pub recipients: Vec<u64>,
+    /// Packed inline actions
+    pub inline_actions: Vec<Vec<u8>>,
+    /// Packed context free inline actions
+    pub context_free_inline_actions: Vec<Vec<u8>>,
+    /// Packed deferred transactions with their payers, by sender ID
+    pub deferred_transactions: BTreeMap<u128, (u64, Vec<u8>)>,
+    /// Contract tables
+    pub db: Database,
+    /// The key-value database
+    pub kv: KvDatabase,
+}
+
+impl Host {
+    /// Returns true if `account` authorized the current action.
+    #[inline]
+    #[must_use]
+    pub fn has_auth(&self, account: u64) -> bool {
+        self.authorizations
+            .iter()
+            .any(|(actor, _)| *actor == account)
+    }
+}
+
+/// Identifies a table by its `(code, scope, table)` names.
+pub type TableId = (u64, u64, u64);
+
+/// A row in a primary table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Row {
+    /// The account paying for the row
+    pub payer: u64,
+    /// The packed row
+    pub data: Vec<u8>,
+}
+
+/// Iterator handles for one kind of table.
+///
+/// Valid iterators are indexes into `rows`. End iterators are negative:
+/// `-1` is the end of a table that doesn't exist, and `-(i + 2)` is the end
+/// of `ends[i]`.
+#[derive(Debug, Clone, Default)]
+struct Iterators {
+    rows: Vec<(TableId, u64)>,
+    ends: Vec<TableId>,
+}
+
+impl Iterators {
+    fn row(&mut self, table: TableId, primary: u64) -> i32 {
+        let index = match self.rows.iter().position(|r| *r == (table, primary))
+        {
+            Some(index) => index,
+            None => {
+                self.rows.push((table, primary));
+                self.rows.len() - 1
+            }
+        };
+        i32::try_from(index).unwrap_or_else(|_| abort("too many iterators"))
+    }
+
+    fn end(&mut self, table: TableId) -> i32 {
+        let index = match self.ends.iter().position(|t| *t == table) {
+            Some(index) => index,
+            None => {
+                self.ends.push(table);
+                self.ends.len() - 1
+            }
+        };
+        i32::try_from(index)
+            .map(|i| -i - 2)
+            .unwrap_or_else(|_| abort("too many iterators"))
+    }
+
+    fn get(&self, iterator: i32) -> (TableId, u64) {
+        usize::try_from(iterator)
+            .ok()
+            .and_then(|i| self.rows.get(i))
+            .copied()
+            .unwrap_or_else(|| abort("invalid iterator"))
+    }
+
+    fn end_table(&self, iterator: i32) -> Option<TableId> {
+        usize::try_from(-2_i64 - i64::from(iterator))
+            .ok()
+            .and_then(|i| self.ends.get(i))
+            .copied()
+    }
+}
+
+/// The kinds of secondary indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SecondaryKind {
+    /// `idx64`, keyed by `u64`
+    Idx64,
+    /// `idx128`, keyed by `u128`
+    Idx128,
+    /// `idx256`, keyed by `[u128; 2]`
+    Idx256,
+    /// `idx_double`, keyed by `f64`
+    Double,
+    /// `idx_long_double`, keyed by the bits of a quadruple precision float
+    LongDouble,
+}
+
+impl SecondaryKind {
+    /// Maps a key to words that sort in the same order as the key.
+    fn sortable_key(self, key: [u128; 2]) -> [u128; 2] {
+        const F64_SIGN: u128 = 1 << 63;
+        const F128_SIGN: u128 = 1 << 127;
+        match self {
+            Self::Idx64 | Self::Idx128 | Self::Idx256 => key,
+            Self::Double if key[1] & F64_SIGN == 0 => [0, key[1] | F64_SIGN],
+            Self::Double => [0, !key[1] & u128::from(u64::MAX)],
+            Self::LongDouble if key[1] & F128_SIGN == 0 => {
+                [0, key[1] | F128_SIGN]
+            }
+            Self::LongDouble => [0, !key[1]],
+        }
+    }
+
+    fn original_key(self, key: [u128; 2]) -> [u128; 2] {
+        const F64_SIGN: u128 = 1 << 63;
+        const F128_SIGN: u128 = 1 << 127;
+        match self {
+            Self::Idx64 | Self::Idx128 | Self::Idx256 => key,
+            Self::Double if key[1] & F64_SIGN != 0 => [0, key[1] ^ F64_SIGN],
+            Self::Double => [0, !key[1] & u128::from(u64::MAX)],
+            Self::LongDouble if key[1] & F128_SIGN != 0 => {
+                [0, key[1] ^ F128_SIGN]
+            }
+            Self::LongDouble => [0, !key[1]],
+        }
+    }
+}
+
+/// A secondary index table.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SecondaryTable {
+    /// The sortable key and payer of each primary key
+    by_primary: BTreeMap<u64, ([u128; 2], u64)>,
+    /// `(sortable key, primary key)` pairs in index order
+    order: BTreeSet<([u128; 2], u64)>,
+}
+
+/// In-memory contract tables.
+#[derive(Debug, Clone, Default)]
+pub struct Database {
+    primary: BTreeMap<TableId, BTreeMap<u64, Row>>,
+    primary_iterators: Iterators,
+    secondary: BTreeMap<(SecondaryKind, TableId), SecondaryTable>,
+    secondary_iterators: BTreeMap<SecondaryKind, Iterators>,
+}
+
+impl Database {
+    /// Returns the rows of a primary table, ordered by primary key.
+    #[must_use]
+    pub fn rows(&self, table: TableId) -> Vec<(u64, Row)> {
+        self.primary
+            .get(&table)
+            .map(|rows| rows.iter().map(|(k, v)| (*k, v.clone())).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the `(key, primary key)` pairs of a secondary index, in index
+    /// order. `u64`, `u128` and `f64` keys are in the low word.
+    #[must_use]
+    pub fn secondary_rows(
+        &self,
+        kind: SecondaryKind,
+        table: TableId,
+    ) -> Vec<([u128; 2], u64)> {
+        self.secondary
+            .get(&(kind, table))
+            .map(|t| {
+                t.order
+                    .iter()
+                    .map(|(key, pk)| (kind.original_key(*key), *pk))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the tables that have rows, including secondary indexes.
+    #[must_use]
+    pub fn tables(&self) -> Vec<TableId> {
+        let mut tables: Vec<_> = self.primary.keys().copied().collect();
+        for (_, table) in self.secondary.keys() {
+            if !tables.contains(table) {
+                tables.push(*table);
+            }
+        }
+        tables
+    }
+
+    pub(crate) fn store(
+        &mut self,
+        table: TableId,
+        payer: u64,
+        id: u64,
+        data: Vec<u8>,
+    ) -> i32 {
+        let rows = self.primary.entry(table).or_default();
+        if rows.contains_key(&id) {
+            abort("could not insert object, most likely a uniqueness constraint was violated");
+        }
+        rows.insert(id, Row { payer, data });
+        self.primary_iterators.row(table, id)
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        receiver: u64,
+        iterator: i32,
+        payer: u64,
+        data: Vec<u8>,
+    ) {
+        let (table, id) = self.primary_iterators.get(iterator);
+        check_access(receiver, table);
+        let row = self
+            .primary
+            .get_mut(&table)
+            .and_then(|rows| rows.get_mut(&id))
+            .unwrap_or_else(|| abort("dereference of deleted object"));
+        if payer != 0 {
+            row.payer = payer;
+        }
+        row.data = data;
+    }
+
+    pub(crate) fn remove(&mut self, receiver: u64, iterator: i32) {
+        let (table, id) = self.primary_iterators.get(iterator);
+        check_access(receiver, table);
+        let rows = self
+            .primary
+            .get_mut(&table)
+            .unwrap_or_else(|| abort("dereference of deleted object"));
+        if rows.remove(&id).is_none() {
+            abort("dereference of deleted object");
+        }
+        if rows.is_empty() {
+            self.primary.remove(&table);
+        }
+    }
+
+    pub(crate) fn get(&self, iterator: i32) -> &[u8] {
+        let (table, id) = self.primary_iterators.get(iterator);
+        self.primary
+            .get(&table)
+            .and_then(|rows| rows.get(&id))
+            .map(|row| row.data.as_slice())
+            .unwrap_or_else(|| abort("dereference of deleted object"))
+    }
+
+    pub(crate) fn next(&mut self, iterator: i32) -> (i32, u64) {
+        if iterator < 0 {
+            return (-1, 0);
+        }
+        let (table, id) = self.primary_iterators.get(iterator);
+        let next = self.primary.get(&table).and_then(|rows| {
+            rows.range((Bound::Excluded(id), Bound::Unbounded))
+                .next()
+                .map(|(k, _)| *k)
+        });
+        match next {
+            Some(next) => (self.primary_iterators.row(table, next), next),
+            None => (self.primary_iterators.end(table), 0),
+        }
+    }
+
+    pub(crate) fn previous(&mut self, iterator: i32) -> (i32, u64) {
+        let (table, prev) = if iterator < -1 {
+            let table = match self.primary_iterators.end_table(iterator) {
+                Some(table) => table,
+                None => abort("invalid iterator"),
+            };
+            let prev = self
+                .primary
+                .get(&table)
+                .and_then(|rows| rows.keys().next_back().copied());
+            (table, prev)
+        } else if iterator == -1 {
+            return (-1, 0);
+        } else {
+            let (table, id) = self.primary_iterators.get(iterator);
+            let prev = self
+                .primary
+                .get(&table)
+                .and_then(|rows| rows.range(..id).next_back().map(|(k, _)| *k));
+            (table, prev)
+        };
+        match prev {
+            Some(prev) => (self.primary_iterators.row(table, prev), prev),
+            None => (-1, 0),
+        }
+    }
+
+    /// Returns an iterator to the first row matching `find`, or the end
+    /// iterator of the table.
+    pub(crate) fn find(
+        &mut self,
+        table: TableId,
+        find: impl FnOnce(&BTreeMap<u64, Row>) -> Option<u64>,
+    ) -> i32 {
+        match self.primary.get(&table) {
+            None => -1,
+            Some(rows) => match find(rows) {
+                Some(id) => self.primary_iterators.row(table, id),
+                None => self.primary_iterators.end(table),
+            },
+        }
+    }
+
+    pub(crate) fn end(&mut self, table: TableId) -> i32 {
+        if self.primary.contains_key(&table) {
+            self.primary_iterators.end(table)
+        } else {
+            -1
+        }
+    }
+
+    fn secondary_iterators(&mut self, kind: SecondaryKind) -> &mut Iterators {
+        self.secondary_iterators.entry(kind).or_default()
+    }
+
+    pub(crate) fn idx_store(
+        &mut self,
+        kind: SecondaryKind,
+        table: TableId,
+        payer: u64,
+        id: u64,
+        key: [u128; 2],
+    ) -> i32 {
+        let key = kind.sortable_key(key);
+        let index = self.secondary.entry((kind, table)).or_default();
+        if index.by_primary.contains_key(&id) {
+            abort("could not insert object, most likely a uniqueness constraint was violated");
+        }
+        index.by_primary.insert(id, (key, payer));
+        index.order.insert((key, id));
+        self.secondary_iterators(kind).row(table, id)
+    }
+
+    pub(crate) fn idx_update(
+        &mut self,
+        kind: SecondaryKind,
+        receiver: u64,
+        iterator: i32,
+        payer: u64,
+        key: [u128; 2],
+    ) {
+        let (table, id) = self.secondary_iterators(kind).get(iterator);
+        check_access(receiver, table);
+        let key = kind.sortable_key(key);
+        let index = self
+            .secondary
+            .get_mut(&(kind, table))
+            .unwrap_or_else(|| abort("dereference of deleted object"));
+        let (old_key, old_payer) = index
+            .by_primary
+            .get(&id)
+            .copied()
+            .unwrap_or_else(|| abort("dereference of deleted object"));
+        let payer = if payer == 0 { old_payer } else { payer };
+        index.order.remove(&(old_key, id));
+        index.order.insert((key, id));
+        index.by_primary.insert(id, (key, payer));
+    }
+
+    pub(crate) fn idx_remove(
+        &mut self,
+        kind: SecondaryKind,
+        receiver: u64,
+        iterator: i32,
+    ) {
+        let (table, id) = self.secondary_iterators(kind).get(iterator);
+        check_access(receiver, table);
+        let index = self
+            .secondary
+            .get_mut(&(kind, table))
+            .unwrap_or_else(|| abort("dereference of deleted object"));
+        let (key, _) = index
+            .by_primary
+            .remove(&id)
+            .unwrap_or_else(|| abort("dereference of deleted object"));
+        index.order.remove(&(key, id));
+        if index.by_primary.is_empty() {
+            self.secondary.remove(&(kind, table));
+        }
+    }
+
+    pub(crate) fn idx_next(
+        &mut self,
+        kind: SecondaryKind,
+        iterator: i32,
+    ) -> (i32, u64) {
+        if iterator < 0 {
+            return (-1, 0);
+        }
+        let (table, id) = self.secondary_iterators(kind).get(iterator);
+        let next = self.secondary.get(&(kind, table)).and_then(|index| {
+            let (key, _) = index.by_primary.get(&id)?;
+            index
+                .order
+                .range((Bound::Excluded((*key, id)), Bound::Unbounded))
+                .next()
+                .map(|(_, pk)| *pk)
+        });
+        match next {
+            Some(next) => {
+                (self.secondary_iterators(kind).row(table, next), next)
+            }
+            None => (self.secondary_iterators(kind).end(table), 0),
+        }
+    }
+
+    pub(crate) fn idx_previous(
+        &mut self,
+        kind: SecondaryKind,
+        iterator: i32,
+    ) -> (i32, u64) {
+        let (table, prev) = if iterator < -1 {
+            let table = match self.secondary_iterators(kind).end_table(iterator)
+            {
+                Some(table) => table,
+                None => abort("invalid iterator"),
+            };
+            let prev = self
+                .secondary
+                .get(&(kind, table))
+                .and_then(|index| index.order.iter().next_back())
+                .map(|(_, pk)| *pk);
+            (table, prev)
+        } else if iterator == -1 {
+            return (-1, 0);
+        } else {
+            let (table, id) = self.secondary_iterators(kind).get(iterator);
+            let prev = self.secondary.get(&(kind, table)).and_then(|index| {
+                let (key, _) = index.by_primary.get(&id)?;
+                index
+                    .order
+                    .range(..(*key, id))
+                    .next_back()
+                    .map(|(_, pk)| *pk)
+            });
+            (table, prev)
+        };
+        match prev {
+            Some(prev) => {
+                (self.secondary_iterators(kind).row(table, prev), prev)
+            }
+            None => (-1, 0),
+        }
+    }
+
+    /// Returns an iterator to the first entry matching `find` along with its
+    /// key and primary key, or the end iterator of the index.
+    pub(crate) fn idx_find(
+        &mut self,
+        kind: SecondaryKind,
+        table: TableId,
+        find: impl FnOnce(&SecondaryTable) -> Option<([u128; 2], u64)>,
+    ) -> (i32, Option<([u128; 2], u64)>) {
+        match self.secondary.get(&(kind, table)) {
+            None => (-1, None),
+            Some(index) => match find(index) {
+                Some((key, pk)) => {
+                    let iterator =
+                        self.secondary_iterators(kind).row(table, pk);
+                    (iterator, Some((kind.original_key(key), pk)))
+                }
+                None => (self.secondary_iterators(kind).end(table), None),
+            },
+        }
+    }
+
+    pub(crate) fn idx_find_primary(
+        &mut self,
+        kind: SecondaryKind,
+        table: TableId,
+        primary: u64,
+    ) -> (i32, Option<([u128; 2], u64)>) {
+        self.idx_find(kind, table, |index| {
+            index
+                .by_primary
+                .get(&primary)
+                .map(|(key, _)| (*key, primary))
+        })
+    }
+
+    pub(crate) fn idx_find_secondary(
+        &mut self,
+        kind: SecondaryKind,
+        table: TableId,
+        key: [u128; 2],
+    ) -> (i32, Option<([u128; 2], u64)>) {
+        let key = kind.sortable_key(key);
+        self.idx_find(kind, table, |index| {
+            index
+                .order
+                .range((key, 0)..)
+                .next()
+                .filter(|(k, _)| *k == key)
+                .copied()
+        })
+    }
+
+    pub(crate) fn idx_lowerbound(
+        &mut self,
+        kind: SecondaryKind,
+        table: TableId,
+        key: [u128; 2],
+    ) -> (i32, Option<([u128; 2], u64)>) {
+        let key = kind.sortable_key(key);
+        self.idx_find(kind, table, |index| {
+            index.order.range((key, 0)..).next().copied()
+        })
+    }
+
+    pub(crate) fn idx_upperbound(
+        &mut self,
+        kind: SecondaryKind,
+        table: TableId,
+        key: [u128; 2],
+    ) -> (i32, Option<([u128; 2], u64)>) {
+        let key = kind.sortable_key(key);
+        self.idx_find(kind, table, |index| {
+            index
+                .order
+                .range((Bound::Excluded((key, u64::MAX)), Bound::Unbounded))
+                .next()
+                .copied()
+        })
+    }
+
+    pub(crate) fn idx_end(
+        &mut self,
+        kind: SecondaryKind,
+        table: TableId,
+    ) -> i32 {
+        if self.secondary.contains_key(&(kind, table)) {
+            self.secondary_iterators(kind).end(table)
+        } else {
+            -1
+        }
+    }
+}
+
+/// A value in the key-value database.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KvRow {
+    /// The account paying for the key and value
+    pub payer: u64,
+    /// The value
+    pub value: Vec<u8>,
+}
+
+/// An iterator over the keys of a contract that start with a prefix.
+#[derive(Debug, Clone)]
+struct KvIterator {
+    contract: u64,
+    prefix: Vec<u8>,
+    /// The current key, or `None` at the end
+    key: Option<Vec<u8>>,
+}
+
+/// The status of an iterator that points to a key.
+const KV_OK: i32 = 0;
+/// The status of an iterator whose key was erased.
+const KV_ERASED: i32 = -1;
+/// The status of an iterator at the end.
+const KV_END: i32 = -2;
+
+/// The in-memory key-value database.
+#[derive(Debug, Clone, Default)]
+pub struct KvDatabase {
+    rows: BTreeMap<u64, BTreeMap<Vec<u8>, KvRow>>,
+    iterators: Vec<Option<KvIterator>>,
+    /// The value found by the last `kv_get`
+    staged: Vec<u8>,
+}
+
+impl KvDatabase {
+    /// Returns the keys and values of a contract, ordered by key.
+    #[must_use]
+    pub fn rows(&self, contract: u64) -> Vec<(Vec<u8>, KvRow)> {
+        self.rows
+            .get(&contract)
+            .map(|rows| {
+                rows.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Sets a value, returning the change in RAM usage.
+    pub(crate) fn set(
+        &mut self,
+        receiver: u64,
+        contract: u64,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        payer: u64,
+    ) -> i64 {
+        check_kv_access(receiver, contract);
+        let new_size = to_i64(value.len());
+        let key_size = to_i64(key.len());
+        let rows = self.rows.entry(contract).or_default();
+        match rows.insert(key, KvRow { payer, value }) {
+            Some(old) => new_size - to_i64(old.value.len()),
+            None => key_size + new_size,
+        }
+    }
+
+    /// Erases a key, returning the change in RAM usage.
+    pub(crate) fn erase(
+        &mut self,
+        receiver: u64,
+        contract: u64,
+        key: &[u8],
+    ) -> i64 {
+        check_kv_access(receiver, contract);
+        let rows = match self.rows.get_mut(&contract) {
+            Some(rows) => rows,
+            None => return 0,
+        };
+        let delta = rows
+            .remove(key)
+            .map_or(0, |row| -to_i64(key.len()) - to_i64(row.value.len()));
+        if rows.is_empty() {
+            self.rows.remove(&contract);
+        }
+        delta
+    }
+
+    /// Stages the value of a key, returning its size.
+    pub(crate) fn get(&mut self, contract: u64, key: &[u8]) -> Option<usize> {
+        match self.rows.get(&contract).and_then(|rows| rows.get(key)) {
+            Some(row) => {
+                self.staged = row.value.clone();
+                Some(row.value.len())
+            }
+            None => {
+                self.staged.clear();
+                None
+            }
+        }
+    }
+
+    pub(crate) fn staged(&self) -> &[u8] {
+        &self.staged
+    }
+
+    pub(crate) fn it_create(&mut self, contract: u64, prefix: Vec<u8>) -> u32 {
+        let iterator = Some(KvIterator {
+            contract,
+            prefix,
+            key: None,
+        });
+        let index = match self.iterators.iter().position(Option::is_none) {
+            Some(index) => {
+                if let Some(slot) = self.iterators.get_mut(index) {
+                    *slot = iterator;
+                }
+                index
+            }
+            None => {
+                self.iterators.push(iterator);
+                self.iterators.len() - 1
+            }
+        };
+        u32::try_from(index).unwrap_or_else(|_| abort("too many iterators"))
+    }
+
+    pub(crate) fn it_destroy(&mut self, iterator: u32) {
+        *self.slot(iterator) = None;
+    }
+
+    fn slot(&mut self, iterator: u32) -> &mut Option<KvIterator> {
+        usize::try_from(iterator)
+            .ok()
+            .and_then(move |i| self.iterators.get_mut(i))
+            .filter(|slot| slot.is_some())
+            .unwrap_or_else(|| abort("invalid kv iterator"))
+    }
+
+    fn iterator(&self, iterator: u32) -> &KvIterator {
+        usize::try_from(iterator)
+            .ok()
+            .and_then(|i| self.iterators.get(i))
+            .and_then(Option::as_ref)
+            .unwrap_or_else(|| abort("invalid kv iterator"))
+    }
+
+    fn row(&self, iterator: &KvIterator) -> Option<(&Vec<u8>, &KvRow)> {
+        let key = iterator.key.as_ref()?;
+        self.rows.get(&iterator.contract)?.get_key_value(key)
+    }
+
+    pub(crate) fn it_status(&self, iterator: u32) -> i32 {
+        let iterator = self.iterator(iterator);
+        if iterator.key.is_none() {
+            KV_END
+        } else if self.row(iterator).is_some() {
+            KV_OK
+        } else {
+            KV_ERASED
+        }
+    }
+
+    pub(crate) fn it_compare(&self, a: u32, b: u32) -> i32 {
+        let b = self.iterator(b).key.clone();
+        self.it_key_compare(a, b.as_deref())
+    }
+
+    /// Compares the key of an iterator to `key`, where `None` is the end.
+    pub(crate) fn it_key_compare(
+        &self,
+        iterator: u32,
+        key: Option<&[u8]>,
+    ) -> i32 {
+        let ordering = match (&self.iterator(iterator).key, key) {
+            (None, None) => std::cmp::Ordering::Equal,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (Some(a), Some(b)) => a.as_slice().cmp(b),
+        };
+        ordering as i32
+    }
+
+    /// Moves an iterator to `find(prefix, current key, keys)`, returning its
+    /// status and the sizes of the new key and value.
+    fn move_to(
+        &mut self,
+        iterator: u32,
+        find: impl FnOnce(
+            &[u8],
+            Option<&Vec<u8>>,
+            &BTreeMap<Vec<u8>, KvRow>,
+        ) -> Option<Vec<u8>>,
+    ) -> (i32, u32, u32) {
+        let current = self.iterator(iterator);
+        if current.key.is_some() && self.row(current).is_none() {
+            abort("kv iterator was erased");
+        }
+        let empty = BTreeMap::new();
+        let rows = self.rows.get(&current.contract).unwrap_or(&empty);
+        let key = find(&current.prefix, current.key.as_ref(), rows)
+            .filter(|key| key.starts_with(&current.prefix));
+        let sizes = key
+            .as_ref()
+            .and_then(|key| rows.get(key).map(|row| (key, row)))
+            .map_or((0, 0), |(key, row)| {
+                (to_u32(key.len()), to_u32(row.value.len()))
+            });
+        let status = if key.is_some() { KV_OK } else { KV_END };
+        if let Some(slot) = self.slot(iterator) {
+            slot.key = key;
+        }
+        (status, sizes.0, sizes.1)
+    }
+
+    pub(crate) fn it_move_to_end(&mut self, iterator: u32) -> i32 {
+        if let Some(slot) = self.slot(iterator) {
+            slot.key = None;
+        }
+        KV_END
+    }
+
+    pub(crate) fn it_next(&mut self, iterator: u32) -> (i32, u32, u32) {
+        self.move_to(iterator, |prefix, key, rows| {
+            let start = match key {
+                Some(key) => Bound::Excluded(key.clone()),
+                None => Bound::Included(prefix.to_vec()),
+            };
+            rows.range((start, Bound::Unbounded))
+                .next()
+                .map(|(k, _)| k.clone())
+        })
+    }
+
+    pub(crate) fn it_prev(&mut self, iterator: u32) -> (i32, u32, u32) {
+        self.move_to(iterator, |prefix, key, rows| {
+            match key {
+                Some(key) => rows.range(..key.clone()).next_back(),
+                None => rows
+                    .range(prefix.to_vec()..)
+                    .take_while(|(k, _)| k.starts_with(prefix))
+                    .last(),
+            }
+            .map(|(k, _)| k.clone())
+        })
+    }
+
+    pub(crate) fn it_lower_bound(
+        &mut self,
+        iterator: u32,
+        key: &[u8],
+    ) -> (i32, u32, u32) {
+        self.move_to(iterator, |prefix, _, rows| {
+            let start = if key < prefix { prefix } else { key };
+            rows.range(start.to_vec()..).next().map(|(k, _)| k.clone())
+        })
+    }
+
+    /// Returns the status of an iterator and its key, or its value if `value`
+    /// is true. The bytes are empty if the iterator doesn't point to a key.
+    pub(crate) fn it_bytes(&self, iterator: u32, value: bool) -> (i32, &[u8]) {
+        let status = self.it_status(iterator);
+        let bytes = self
+            .row(self.iterator(iterator))
+            .map(|(k, row)| if value { &row.value } else { k })
+            .map_or(&[][..], Vec::as_slice);
+        (status, bytes)
+    }
+}
+
+fn to_i64(len: usize) -> i64 {
+    i64::try_from(len).unwrap_or(i64::MAX)
+}
+
+fn to_u32(len: usize) -> u32 {
+    u32::try_from(len).unwrap_or(u32::MAX)
+}
+
+fn check_kv_access(receiver: u64, contract: u64) {
+    if contract != receiver {
+        abort(&format!(
+            "kv access violation: {} cannot modify the key-value database \
+             of {}",
+            name_to_string(receiver),
+            name_to_string(contract)
+        ));
+    }
+}
+
+fn check_access(receiver: u64, (code, ..): TableId) {
+    if code != receiver {
+        abort(&format!(
+            "db access violation: {} cannot modify tables of {}",
+            name_to_string(receiver),
+            name_to_string(code)
+        ));
+    }
+}
+
+/// Converts a name to its string representation.
+#[must_use]
+pub fn name_to_string(name: u64) -> String {
+    const CHARS: &[u8; 32] = b".12345abcdefghijklmnopqrstuvwxyz";
+    let mut chars = [b'.'; 13];
+    let mut value = name;
+    for (i, c) in chars.iter_mut().enumerate().rev() {
+        let (mask, shift) = if i == 12 { (0x0f, 4) } else { (0x1f, 5) };
+        *c = CHARS
+            .get(usize::try_from(value & mask).unwrap_or_default())
+            .copied()
+            .unwrap_or(b'.');
+        value >>= shift;
+    }
+    let len = chars.iter().rposition(|c| *c != b'.').map_or(0, |i| i + 1);
+    chars
+        .get(..len)
+        .map(|c| c.iter().map(|c| char::from(*c)).collect())
+        .unwrap_or_default()
+}
+
+/// Reads values from packed bytes.
+struct Unpacker<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Unpacker<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.bytes.get(..len)?;
+        self.bytes = self.bytes.get(len..)?;
+        Some(bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let mut buf = [0_u8; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Some(u64::from_le_bytes(buf))
+    }
+
+    fn varuint32(&mut self) -> Option<usize> {
+        let mut value = 0_u64;
+        for shift in (0..35).step_by(7) {
+            let byte = *self.take(1)?.first()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return usize::try_from(value).ok();
+            }
+        }
+        None
+    }
+
+    fn permission_levels(&mut self) -> Option<Vec<(u64, u64)>> {
+        let len = self.varuint32()?;
+        (0..len).map(|_| Some((self.u64()?, self.u64()?))).collect()
+    }
+
+    /// Skips an action, returning its authorizations.
+    fn action(&mut self) -> Option<Vec<(u64, u64)>> {
+        // account and name
+        self.take(16)?;
+        let authorization = self.permission_levels()?;
+        let len = self.varuint32()?;
+        self.take(len)?;
+        Some(authorization)
+    }
+}
+
+/// Reads packed permission levels. Returns `None` if they are malformed.
+#[must_use]
+pub fn unpack_permission_levels(bytes: &[u8]) -> Option<Vec<(u64, u64)>> {
+    Unpacker { bytes }.permission_levels()
+}
+
+/// Reads the `(actor, permission)` pairs that authorize the actions of a
+/// packed transaction. Returns `None` if the transaction is malformed.
+#[must_use]
+pub fn transaction_authorizations(trx: &[u8]) -> Option<Vec<(u64, u64)>> {
+    let mut trx = Unpacker { bytes: trx };
+    // expiration, ref_block_num and ref_block_prefix
+    trx.take(10)?;
+    // max_net_usage_words, max_cpu_usage_ms and delay_sec
+    trx.varuint32()?;
+    trx.take(1)?;
+    trx.varuint32()?;
+    let mut authorizations = Vec::new();
+    // context free actions, then actions
+    for _ in 0..2 {
+        for _ in 0..trx.varuint32()? {
+            authorizations.extend(trx.action()?);
+        }
+    }
+    Some(authorizations)
+}
@@ -1,4 +1,13 @@
-use std::default::Default;
+use crate::mock::{
+    abort, name_to_string, transaction_authorizations,
+    unpack_permission_levels, with_host, Exit, SecondaryKind,
+};
+#[cfg(feature = "mock")]
+use crate::mock_crypto;
+use std::{
+    convert::TryFrom, default::Default, format, ops::Bound, string::String,
+    string::ToString, vec::Vec,
+};
 
 pub type uint128_t = u128;
 pub type int128_t = i128;
@@ -114,604 +123,1189 @@ impl ::std::cmp::PartialEq for capi_checksum512 {
     }
 }
 
-pub unsafe fn read_action_data(_msg: *mut crate::c_void, _len: u32) -> u32 {
-    Default::default()
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(ptr, len)
+    }
 }
 
-pub unsafe fn action_data_size() -> u32 {
-    Default::default()
+unsafe fn copy_out(src: &[u8], dst: *mut u8, len: usize) -> usize {
+    let len = len.min(src.len());
+    if len > 0 && !dst.is_null() {
+        std::ptr::copy_nonoverlapping(src.as_ptr(), dst, len);
+    }
+    len
 }
 
-pub unsafe fn require_recipient(_name: capi_name) {}
+fn print(s: &str) {
+    with_host(|host| host.output.push_str(s));
+}
 
-pub unsafe fn require_auth(_name: capi_name) {}
+fn receiver() -> u64 {
+    with_host(|host| host.receiver)
+}
 
-pub unsafe fn has_auth(_name: capi_name) -> bool {
-    Default::default()
+fn unsupported(name: &str) -> ! {
+    abort(&format!("{} is not supported by the mock host", name))
 }
 
-pub unsafe fn require_auth2(_name: capi_name, _permission: capi_name) {}
+/// Without the `mock` feature the crates behind the crypto primitives are not
+/// built, so the primitives abort instead.
+#[cfg(not(feature = "mock"))]
+mod mock_crypto {
+    use super::abort;
+    use std::{format, vec::Vec};
 
-pub unsafe fn is_account(_name: capi_name) -> bool {
-    Default::default()
-}
+    fn requires_mock(name: &str) -> ! {
+        abort(&format!("{} requires the mock feature", name))
+    }
 
-pub unsafe fn send_inline(
-    _serialized_action: *mut crate::c_char,
-    _size: usize,
-) {
-}
+    pub fn sha256(_: &[u8]) -> [u8; 32] {
+        requires_mock("sha256")
+    }
 
-pub unsafe fn send_context_free_inline(
-    _serialized_action: *mut crate::c_char,
-    _size: usize,
-) {
-}
+    pub fn sha1(_: &[u8]) -> [u8; 20] {
+        requires_mock("sha1")
+    }
 
-pub unsafe fn publication_time() -> u64 {
-    Default::default()
-}
+    pub fn sha512(_: &[u8]) -> [u8; 64] {
+        requires_mock("sha512")
+    }
 
-pub unsafe fn current_receiver() -> capi_name {
-    Default::default()
-}
+    pub fn ripemd160(_: &[u8]) -> [u8; 20] {
+        requires_mock("ripemd160")
+    }
 
-pub unsafe fn get_active_producers(
-    _producers: *mut capi_name,
-    _datalen: u32,
-) -> u32 {
-    Default::default()
-}
+    pub fn sha3(_: &[u8], _: bool) -> [u8; 32] {
+        requires_mock("sha3")
+    }
 
-pub unsafe fn assert_sha256(
-    _data: *const crate::c_char,
-    _length: u32,
-    _hash: *const capi_checksum256,
-) {
+    pub fn blake2_f(
+        _: u32,
+        _: &[u8],
+        _: &[u8],
+        _: &[u8],
+        _: &[u8],
+        _: bool,
+    ) -> Option<[u8; 64]> {
+        requires_mock("blake2_f")
+    }
+
+    pub fn k1_recover(_: &[u8], _: &[u8]) -> Option<[u8; 65]> {
+        requires_mock("k1_recover")
+    }
+
+    pub fn recover_key(_: &[u8], _: &[u8]) -> Option<[u8; 34]> {
+        requires_mock("recover_key")
+    }
+
+    pub fn mod_exp(_: &[u8], _: &[u8], _: &[u8]) -> Vec<u8> {
+        requires_mock("mod_exp")
+    }
+
+    pub fn alt_bn128_add(_: &[u8], _: &[u8]) -> Option<[u8; 64]> {
+        requires_mock("alt_bn128_add")
+    }
+
+    pub fn alt_bn128_mul(_: &[u8], _: &[u8]) -> Option<[u8; 64]> {
+        requires_mock("alt_bn128_mul")
+    }
+
+    pub fn alt_bn128_pair(_: &[u8]) -> Option<bool> {
+        requires_mock("alt_bn128_pair")
+    }
 }
 
-pub unsafe fn assert_sha1(
-    _data: *const crate::c_char,
-    _length: u32,
-    _hash: *const capi_checksum160,
-) {
+fn to_u32(len: usize) -> u32 {
+    u32::try_from(len).unwrap_or(u32::MAX)
 }
 
-pub unsafe fn assert_sha512(
-    _data: *const crate::c_char,
-    _length: u32,
-    _hash: *const capi_checksum512,
-) {
+pub unsafe extern "C-unwind" fn read_action_data(
+    msg: *mut crate::c_void,
+    len: u32,
+) -> u32 {
+    let data = with_host(|host| host.action_data.clone());
+    if len == 0 {
+        return to_u32(data.len());
+    }
+    to_u32(copy_out(&data, msg.cast(), len as usize))
 }
 
-pub unsafe fn assert_ripemd160(
-    _data: *const crate::c_char,
-    _length: u32,
-    _hash: *const capi_checksum160,
-) {
+pub unsafe extern "C-unwind" fn action_data_size() -> u32 {
+    with_host(|host| to_u32(host.action_data.len()))
 }
 
-pub unsafe fn sha256(
-    _data: *const crate::c_char,
-    _length: u32,
-    _hash: *mut capi_checksum256,
-) {
+pub unsafe extern "C-unwind" fn require_recipient(name: capi_name) {
+    with_host(|host| {
+        if name != host.receiver && !host.recipients.contains(&name) {
+            host.recipients.push(name);
+        }
+    });
 }
 
-pub unsafe fn sha1(
-    _data: *const crate::c_char,
-    _length: u32,
-    _hash: *mut capi_checksum160,
-) {
+pub unsafe extern "C-unwind" fn require_auth(name: capi_name) {
+    if !has_auth(name) {
+        abort(&format!("missing authority of {}", name_to_string(name)));
+    }
 }
 
-pub unsafe fn sha512(
-    _data: *const crate::c_char,
-    _length: u32,
-    _hash: *mut capi_checksum512,
-) {
+pub unsafe extern "C-unwind" fn has_auth(name: capi_name) -> bool {
+    with_host(|host| host.has_auth(name))
 }
 
-pub unsafe fn ripemd160(
-    _data: *const crate::c_char,
-    _length: u32,
-    _hash: *mut capi_checksum160,
+pub unsafe extern "C-unwind" fn require_auth2(
+    name: capi_name,
+    permission: capi_name,
 ) {
+    let authorized =
+        with_host(|host| host.authorizations.contains(&(name, permission)));
+    if !authorized {
+        abort(&format!(
+            "missing authority of {}/{}",
+            name_to_string(name),
+            name_to_string(permission)
+        ));
+    }
 }
 
-pub unsafe fn recover_key(
-    _digest: *const capi_checksum256,
-    _sig: *const crate::c_char,
-    _siglen: usize,
-    _pub_: *mut crate::c_char,
-    _publen: usize,
-) -> crate::c_int {
-    Default::default()
+pub unsafe extern "C-unwind" fn is_account(name: capi_name) -> bool {
+    with_host(|host| host.accounts.contains(&name))
 }
 
-pub unsafe fn assert_recover_key(
-    _digest: *const capi_checksum256,
-    _sig: *const crate::c_char,
-    _siglen: usize,
-    _pub_: *const crate::c_char,
-    _publen: usize,
+pub unsafe extern "C-unwind" fn send_inline(
+    serialized_action: *mut crate::c_char,
+    size: usize,
 ) {
+    let action = bytes(serialized_action, size).to_vec();
+    with_host(|host| host.inline_actions.push(action));
 }
 
-pub unsafe fn db_store_i64(
-    _scope: u64,
-    _table: capi_name,
-    _payer: capi_name,
-    _id: u64,
-    _data: *const crate::c_void,
-    _len: u32,
-) -> i32 {
-    Default::default()
-}
-
-pub unsafe fn db_update_i64(
-    _iterator: i32,
-    _payer: capi_name,
-    _data: *const crate::c_void,
-    _len: u32,
+pub unsafe extern "C-unwind" fn send_context_free_inline(
+    serialized_action: *mut crate::c_char,
+    size: usize,
 ) {
+    let action = bytes(serialized_action, size).to_vec();
+    with_host(|host| host.context_free_inline_actions.push(action));
 }
 
-pub unsafe fn db_remove_i64(_iterator: i32) {}
+/// The default `max_action_return_value_size` in nodeos.
+const MAX_ACTION_RETURN_VALUE_SIZE: usize = 256;
 
-pub unsafe fn db_get_i64(
-    _iterator: i32,
-    _data: *const crate::c_void,
-    _len: u32,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn set_action_return_value(
+    return_value: *mut crate::c_void,
+    size: usize,
+) {
+    if size > MAX_ACTION_RETURN_VALUE_SIZE {
+        abort(&format!(
+            "action return value size must be less or equal to {} bytes",
+            MAX_ACTION_RETURN_VALUE_SIZE
+        ));
+    }
+    let data = bytes(return_value.cast(), size).to_vec();
+    with_host(|host| host.action_return_value = data);
 }
 
-pub unsafe fn db_next_i64(_iterator: i32, _primary: *mut u64) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn publication_time() -> u64 {
+    with_host(|host| host.publication_time)
 }
 
-pub unsafe fn db_previous_i64(_iterator: i32, _primary: *mut u64) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn current_receiver() -> capi_name {
+    receiver()
 }
 
-pub unsafe fn db_find_i64(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _id: u64,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn get_active_producers(
+    producers: *mut capi_name,
+    datalen: u32,
+) -> u32 {
+    let data: Vec<u8> = with_host(|host| {
+        host.active_producers
+            .iter()
+            .flat_map(|p| p.to_le_bytes().to_vec())
+            .collect()
+    });
+    if datalen == 0 {
+        return to_u32(data.len());
+    }
+    to_u32(copy_out(&data, producers.cast(), datalen as usize))
 }
 
-pub unsafe fn db_lowerbound_i64(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _id: u64,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn assert_sha256(
+    data: *const crate::c_char,
+    length: u32,
+    hash: *const capi_checksum256,
+) {
+    if mock_crypto::sha256(bytes(data, length as usize)) != (*hash).hash {
+        abort("hash mismatch");
+    }
 }
 
-pub unsafe fn db_upperbound_i64(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _id: u64,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn assert_sha1(
+    data: *const crate::c_char,
+    length: u32,
+    hash: *const capi_checksum160,
+) {
+    if mock_crypto::sha1(bytes(data, length as usize)) != (*hash).hash {
+        abort("hash mismatch");
+    }
 }
 
-pub unsafe fn db_end_i64(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn assert_sha512(
+    data: *const crate::c_char,
+    length: u32,
+    hash: *const capi_checksum512,
+) {
+    if mock_crypto::sha512(bytes(data, length as usize)) != (*hash).hash {
+        abort("hash mismatch");
+    }
 }
 
-pub unsafe fn db_idx64_store(
-    _scope: u64,
-    _table: capi_name,
-    _payer: capi_name,
-    _id: u64,
-    _secondary: *const u64,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn assert_ripemd160(
+    data: *const crate::c_char,
+    length: u32,
+    hash: *const capi_checksum160,
+) {
+    if mock_crypto::ripemd160(bytes(data, length as usize)) != (*hash).hash {
+        abort("hash mismatch");
+    }
 }
 
-pub unsafe fn db_idx64_update(
-    _iterator: i32,
-    _payer: capi_name,
-    _secondary: *const u64,
+pub unsafe extern "C-unwind" fn sha256(
+    data: *const crate::c_char,
+    length: u32,
+    hash: *mut capi_checksum256,
 ) {
+    (*hash).hash = mock_crypto::sha256(bytes(data, length as usize));
 }
 
-pub unsafe fn db_idx64_remove(_iterator: i32) {}
-
-pub unsafe fn db_idx64_next(_iterator: i32, _primary: *mut u64) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn sha1(
+    data: *const crate::c_char,
+    length: u32,
+    hash: *mut capi_checksum160,
+) {
+    (*hash).hash = mock_crypto::sha1(bytes(data, length as usize));
 }
 
-pub unsafe fn db_idx64_previous(_iterator: i32, _primary: *mut u64) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn sha512(
+    data: *const crate::c_char,
+    length: u32,
+    hash: *mut capi_checksum512,
+) {
+    (*hash).hash = mock_crypto::sha512(bytes(data, length as usize));
 }
 
-pub unsafe fn db_idx64_find_primary(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut u64,
-    _primary: u64,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn ripemd160(
+    data: *const crate::c_char,
+    length: u32,
+    hash: *mut capi_checksum160,
+) {
+    (*hash).hash = mock_crypto::ripemd160(bytes(data, length as usize));
 }
 
-pub unsafe fn db_idx64_find_secondary(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *const u64,
-    _primary: *mut u64,
+/// Writes the result of a crypto primitive, returning 0 on success or -1 if
+/// the inputs were invalid or the buffer has the wrong size.
+unsafe fn crypto_result(
+    result: Option<&[u8]>,
+    dst: *mut crate::c_char,
+    len: u32,
 ) -> i32 {
-    Default::default()
+    match result {
+        Some(result) if result.len() == len as usize => {
+            copy_out(result, dst, result.len());
+            0
+        }
+        _ => -1,
+    }
 }
 
-pub unsafe fn db_idx64_lowerbound(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut u64,
-    _primary: *mut u64,
+pub unsafe extern "C-unwind" fn alt_bn128_add(
+    op1: *const crate::c_char,
+    op1_len: u32,
+    op2: *const crate::c_char,
+    op2_len: u32,
+    result: *mut crate::c_char,
+    result_len: u32,
 ) -> i32 {
-    Default::default()
+    let sum = mock_crypto::alt_bn128_add(
+        bytes(op1, op1_len as usize),
+        bytes(op2, op2_len as usize),
+    );
+    crypto_result(sum.as_ref().map(|s| &s[..]), result, result_len)
+}
+
+pub unsafe extern "C-unwind" fn alt_bn128_mul(
+    g1: *const crate::c_char,
+    g1_len: u32,
+    scalar: *const crate::c_char,
+    scalar_len: u32,
+    result: *mut crate::c_char,
+    result_len: u32,
+) -> i32 {
+    let product = mock_crypto::alt_bn128_mul(
+        bytes(g1, g1_len as usize),
+        bytes(scalar, scalar_len as usize),
+    );
+    crypto_result(product.as_ref().map(|p| &p[..]), result, result_len)
 }
 
-pub unsafe fn db_idx64_upperbound(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut u64,
-    _primary: *mut u64,
+pub unsafe extern "C-unwind" fn alt_bn128_pair(
+    pairs: *const crate::c_char,
+    pairs_len: u32,
 ) -> i32 {
-    Default::default()
+    match mock_crypto::alt_bn128_pair(bytes(pairs, pairs_len as usize)) {
+        Some(true) => 0,
+        Some(false) => 1,
+        None => -1,
+    }
 }
 
-pub unsafe fn db_idx64_end(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
+pub unsafe extern "C-unwind" fn mod_exp(
+    base: *const crate::c_char,
+    base_len: u32,
+    exp: *const crate::c_char,
+    exp_len: u32,
+    mod_: *const crate::c_char,
+    mod_len: u32,
+    result: *mut crate::c_char,
+    result_len: u32,
 ) -> i32 {
-    Default::default()
+    if mod_len == 0 {
+        return -1;
+    }
+    let value = mock_crypto::mod_exp(
+        bytes(base, base_len as usize),
+        bytes(exp, exp_len as usize),
+        bytes(mod_, mod_len as usize),
+    );
+    crypto_result(Some(&value), result, result_len)
+}
+
+pub unsafe extern "C-unwind" fn blake2_f(
+    rounds: u32,
+    state: *const crate::c_char,
+    state_len: u32,
+    msg: *const crate::c_char,
+    msg_len: u32,
+    t0_offset: *const crate::c_char,
+    t0_len: u32,
+    t1_offset: *const crate::c_char,
+    t1_len: u32,
+    final_: i32,
+    result: *mut crate::c_char,
+    result_len: u32,
+) -> i32 {
+    if final_ != 0 && final_ != 1 {
+        return -1;
+    }
+    let state = mock_crypto::blake2_f(
+        rounds,
+        bytes(state, state_len as usize),
+        bytes(msg, msg_len as usize),
+        bytes(t0_offset, t0_len as usize),
+        bytes(t1_offset, t1_len as usize),
+        final_ == 1,
+    );
+    crypto_result(state.as_ref().map(|s| &s[..]), result, result_len)
+}
+
+pub unsafe extern "C-unwind" fn sha3(
+    data: *const crate::c_char,
+    data_len: u32,
+    hash: *mut crate::c_char,
+    hash_len: u32,
+    keccak: i32,
+) {
+    let digest = mock_crypto::sha3(bytes(data, data_len as usize), keccak != 0);
+    copy_out(&digest, hash, hash_len as usize);
 }
 
-pub unsafe fn db_idx128_store(
-    _scope: u64,
-    _table: capi_name,
-    _payer: capi_name,
-    _id: u64,
-    _secondary: *const uint128_t,
+pub unsafe extern "C-unwind" fn k1_recover(
+    sig: *const crate::c_char,
+    sig_len: u32,
+    dig: *const crate::c_char,
+    dig_len: u32,
+    pub_: *mut crate::c_char,
+    pub_len: u32,
 ) -> i32 {
-    Default::default()
+    let key = mock_crypto::k1_recover(
+        bytes(sig, sig_len as usize),
+        bytes(dig, dig_len as usize),
+    );
+    crypto_result(key.as_ref().map(|k| &k[..]), pub_, pub_len)
+}
+
+/// Recovers the packed key of a packed signature, aborting if it isn't a
+/// valid K1 signature.
+unsafe fn recover_packed_key(
+    digest: *const capi_checksum256,
+    sig: *const crate::c_char,
+    siglen: usize,
+) -> [u8; 34] {
+    mock_crypto::recover_key(&(*digest).hash, bytes(sig, siglen))
+        .unwrap_or_else(|| abort("unable to recover key from signature"))
+}
+
+pub unsafe extern "C-unwind" fn recover_key(
+    digest: *const capi_checksum256,
+    sig: *const crate::c_char,
+    siglen: usize,
+    pub_: *mut crate::c_char,
+    publen: usize,
+) -> crate::c_int {
+    let key = recover_packed_key(digest, sig, siglen);
+    copy_out(&key, pub_, publen);
+    i32::try_from(key.len()).unwrap_or(i32::MAX)
 }
 
-pub unsafe fn db_idx128_update(
-    _iterator: i32,
-    _payer: capi_name,
-    _secondary: *const uint128_t,
+pub unsafe extern "C-unwind" fn assert_recover_key(
+    digest: *const capi_checksum256,
+    sig: *const crate::c_char,
+    siglen: usize,
+    pub_: *const crate::c_char,
+    publen: usize,
 ) {
+    let key = recover_packed_key(digest, sig, siglen);
+    if &key[..] != bytes(pub_, publen) {
+        abort("Error expected key different than recovered key");
+    }
 }
 
-pub unsafe fn db_idx128_remove(_iterator: i32) {}
-
-pub unsafe fn db_idx128_next(_iterator: i32, _primary: *mut u64) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn db_store_i64(
+    scope: u64,
+    table: capi_name,
+    payer: capi_name,
+    id: u64,
+    data: *const crate::c_void,
+    len: u32,
+) -> i32 {
+    let data = bytes(data.cast(), len as usize).to_vec();
+    with_host(|host| {
+        let table = (host.receiver, scope, table);
+        host.db.store(table, payer, id, data)
+    })
+}
+
+pub unsafe extern "C-unwind" fn db_update_i64(
+    iterator: i32,
+    payer: capi_name,
+    data: *const crate::c_void,
+    len: u32,
+) {
+    let data = bytes(data.cast(), len as usize).to_vec();
+    with_host(|host| host.db.update(host.receiver, iterator, payer, data));
 }
 
-pub unsafe fn db_idx128_previous(_iterator: i32, _primary: *mut u64) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn db_remove_i64(iterator: i32) {
+    with_host(|host| host.db.remove(host.receiver, iterator));
 }
 
-pub unsafe fn db_idx128_find_primary(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut uint128_t,
-    _primary: u64,
+pub unsafe extern "C-unwind" fn db_get_i64(
+    iterator: i32,
+    data: *const crate::c_void,
+    len: u32,
 ) -> i32 {
-    Default::default()
-}
-
-pub unsafe fn db_idx128_find_secondary(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *const uint128_t,
-    _primary: *mut u64,
+    with_host(|host| {
+        let row = host.db.get(iterator);
+        let size = i32::try_from(row.len()).unwrap_or(i32::MAX);
+        if len != 0 {
+            copy_out(row, data as *mut u8, len as usize);
+        }
+        size
+    })
+}
+
+pub unsafe extern "C-unwind" fn db_next_i64(
+    iterator: i32,
+    primary: *mut u64,
 ) -> i32 {
-    Default::default()
+    let (iterator, pk) = with_host(|host| host.db.next(iterator));
+    if iterator >= 0 && !primary.is_null() {
+        *primary = pk;
+    }
+    iterator
 }
 
-pub unsafe fn db_idx128_lowerbound(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut uint128_t,
-    _primary: *mut u64,
+pub unsafe extern "C-unwind" fn db_previous_i64(
+    iterator: i32,
+    primary: *mut u64,
 ) -> i32 {
-    Default::default()
+    let (iterator, pk) = with_host(|host| host.db.previous(iterator));
+    if iterator >= 0 && !primary.is_null() {
+        *primary = pk;
+    }
+    iterator
 }
 
-pub unsafe fn db_idx128_upperbound(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut uint128_t,
-    _primary: *mut u64,
+pub unsafe extern "C-unwind" fn db_find_i64(
+    code: capi_name,
+    scope: u64,
+    table: capi_name,
+    id: u64,
 ) -> i32 {
-    Default::default()
+    with_host(|host| {
+        host.db
+            .find((code, scope, table), |rows| rows.get(&id).map(|_| id))
+    })
 }
 
-pub unsafe fn db_idx128_end(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
+pub unsafe extern "C-unwind" fn db_lowerbound_i64(
+    code: capi_name,
+    scope: u64,
+    table: capi_name,
+    id: u64,
 ) -> i32 {
-    Default::default()
-}
-
-pub unsafe fn db_idx256_store(
-    _scope: u64,
-    _table: capi_name,
-    _payer: capi_name,
-    _id: u64,
-    _data: *const uint128_t,
-    _data_len: u32,
+    with_host(|host| {
+        host.db.find((code, scope, table), |rows| {
+            rows.range(id..).next().map(|(k, _)| *k)
+        })
+    })
+}
+
+pub unsafe extern "C-unwind" fn db_upperbound_i64(
+    code: capi_name,
+    scope: u64,
+    table: capi_name,
+    id: u64,
+) -> i32 {
+    with_host(|host| {
+        host.db.find((code, scope, table), |rows| {
+            rows.range((Bound::Excluded(id), Bound::Unbounded))
+                .next()
+                .map(|(k, _)| *k)
+        })
+    })
+}
+
+pub unsafe extern "C-unwind" fn db_end_i64(
+    code: capi_name,
+    scope: u64,
+    table: capi_name,
 ) -> i32 {
-    Default::default()
+    with_host(|host| host.db.end((code, scope, table)))
+}
+
+/// Secondary index intrinsics for keys that are passed as a single value.
+macro_rules! secondary_index {
+    (
+        $kind:ident, $t:ty, $to_key:expr, $from_key:expr,
+        $store:ident, $update:ident, $remove:ident, $next:ident,
+        $previous:ident, $find_primary:ident, $find_secondary:ident,
+        $lowerbound:ident, $upperbound:ident, $end:ident
+    ) => {
+        pub unsafe extern "C-unwind" fn $store(
+            scope: u64,
+            table: capi_name,
+            payer: capi_name,
+            id: u64,
+            secondary: *const $t,
+        ) -> i32 {
+            let key = $to_key(*secondary);
+            with_host(|host| {
+                let table = (host.receiver, scope, table);
+                host.db
+                    .idx_store(SecondaryKind::$kind, table, payer, id, key)
+            })
+        }
+
+        pub unsafe extern "C-unwind" fn $update(
+            iterator: i32,
+            payer: capi_name,
+            secondary: *const $t,
+        ) {
+            let key = $to_key(*secondary);
+            with_host(|host| {
+                host.db.idx_update(
+                    SecondaryKind::$kind,
+                    host.receiver,
+                    iterator,
+                    payer,
+                    key,
+                )
+            });
+        }
+
+        pub unsafe extern "C-unwind" fn $remove(iterator: i32) {
+            with_host(|host| {
+                host.db.idx_remove(
+                    SecondaryKind::$kind,
+                    host.receiver,
+                    iterator,
+                )
+            });
+        }
+
+        pub unsafe extern "C-unwind" fn $next(
+            iterator: i32,
+            primary: *mut u64,
+        ) -> i32 {
+            let (iterator, pk) = with_host(|host| {
+                host.db.idx_next(SecondaryKind::$kind, iterator)
+            });
+            if iterator >= 0 && !primary.is_null() {
+                *primary = pk;
+            }
+            iterator
+        }
+
+        pub unsafe extern "C-unwind" fn $previous(
+            iterator: i32,
+            primary: *mut u64,
+        ) -> i32 {
+            let (iterator, pk) = with_host(|host| {
+                host.db.idx_previous(SecondaryKind::$kind, iterator)
+            });
+            if iterator >= 0 && !primary.is_null() {
+                *primary = pk;
+            }
+            iterator
+        }
+
+        pub unsafe extern "C-unwind" fn $find_primary(
+            code: capi_name,
+            scope: u64,
+            table: capi_name,
+            secondary: *mut $t,
+            primary: u64,
+        ) -> i32 {
+            let (iterator, found) = with_host(|host| {
+                host.db.idx_find_primary(
+                    SecondaryKind::$kind,
+                    (code, scope, table),
+                    primary,
+                )
+            });
+            if let Some((key, _)) = found {
+                *secondary = $from_key(key);
+            }
+            iterator
+        }
+
+        pub unsafe extern "C-unwind" fn $find_secondary(
+            code: capi_name,
+            scope: u64,
+            table: capi_name,
+            secondary: *const $t,
+            primary: *mut u64,
+        ) -> i32 {
+            let key = $to_key(*secondary);
+            let (iterator, found) = with_host(|host| {
+                host.db.idx_find_secondary(
+                    SecondaryKind::$kind,
+                    (code, scope, table),
+                    key,
+                )
+            });
+            if let Some((_, pk)) = found {
+                *primary = pk;
+            }
+            iterator
+        }
+
+        pub unsafe extern "C-unwind" fn $lowerbound(
+            code: capi_name,
+            scope: u64,
+            table: capi_name,
+            secondary: *mut $t,
+            primary: *mut u64,
+        ) -> i32 {
+            let key = $to_key(*secondary);
+            let (iterator, found) = with_host(|host| {
+                host.db.idx_lowerbound(
+                    SecondaryKind::$kind,
+                    (code, scope, table),
+                    key,
+                )
+            });
+            if let Some((key, pk)) = found {
+                *secondary = $from_key(key);
+                *primary = pk;
+            }
+            iterator
+        }
+
+        pub unsafe extern "C-unwind" fn $upperbound(
+            code: capi_name,
+            scope: u64,
+            table: capi_name,
+            secondary: *mut $t,
+            primary: *mut u64,
+        ) -> i32 {
+            let key = $to_key(*secondary);
+            let (iterator, found) = with_host(|host| {
+                host.db.idx_upperbound(
+                    SecondaryKind::$kind,
+                    (code, scope, table),
+                    key,
+                )
+            });
+            if let Some((key, pk)) = found {
+                *secondary = $from_key(key);
+                *primary = pk;
+            }
+            iterator
+        }
+
+        pub unsafe extern "C-unwind" fn $end(
+            code: capi_name,
+            scope: u64,
+            table: capi_name,
+        ) -> i32 {
+            with_host(|host| {
+                host.db.idx_end(SecondaryKind::$kind, (code, scope, table))
+            })
+        }
+    };
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn low_u64(key: [u128; 2]) -> u64 {
+    key[1] as u64
+}
+
+const fn low_u128(key: [u128; 2]) -> u128 {
+    key[1]
+}
+
+const fn from_u128(value: u128) -> [u128; 2] {
+    [0, value]
+}
+
+secondary_index!(
+    Idx64,
+    u64,
+    |v| from_u128(u128::from(v)),
+    low_u64,
+    db_idx64_store,
+    db_idx64_update,
+    db_idx64_remove,
+    db_idx64_next,
+    db_idx64_previous,
+    db_idx64_find_primary,
+    db_idx64_find_secondary,
+    db_idx64_lowerbound,
+    db_idx64_upperbound,
+    db_idx64_end
+);
+
+secondary_index!(
+    Idx128,
+    uint128_t,
+    from_u128,
+    low_u128,
+    db_idx128_store,
+    db_idx128_update,
+    db_idx128_remove,
+    db_idx128_next,
+    db_idx128_previous,
+    db_idx128_find_primary,
+    db_idx128_find_secondary,
+    db_idx128_lowerbound,
+    db_idx128_upperbound,
+    db_idx128_end
+);
+
+secondary_index!(
+    Double,
+    f64,
+    |v: f64| from_u128(u128::from(v.to_bits())),
+    |k| f64::from_bits(low_u64(k)),
+    db_idx_double_store,
+    db_idx_double_update,
+    db_idx_double_remove,
+    db_idx_double_next,
+    db_idx_double_previous,
+    db_idx_double_find_primary,
+    db_idx_double_find_secondary,
+    db_idx_double_lowerbound,
+    db_idx_double_upperbound,
+    db_idx_double_end
+);
+
+secondary_index!(
+    LongDouble,
+    u128,
+    from_u128,
+    low_u128,
+    db_idx_long_double_store,
+    db_idx_long_double_update,
+    db_idx_long_double_remove,
+    db_idx_long_double_next,
+    db_idx_long_double_previous,
+    db_idx_long_double_find_primary,
+    db_idx_long_double_find_secondary,
+    db_idx_long_double_lowerbound,
+    db_idx_long_double_upperbound,
+    db_idx_long_double_end
+);
+
+unsafe fn read_idx256(data: *const uint128_t, data_len: u32) -> [u128; 2] {
+    if data_len != 2 {
+        abort("invalid size of secondary key array for idx256");
+    }
+    [*data, *data.add(1)]
 }
 
-pub unsafe fn db_idx256_update(
-    _iterator: i32,
-    _payer: capi_name,
-    _data: *const uint128_t,
-    _data_len: u32,
-) {
+unsafe fn write_idx256(data: *mut uint128_t, key: [u128; 2]) {
+    *data = key[0];
+    *data.add(1) = key[1];
 }
 
-pub unsafe fn db_idx256_remove(_iterator: i32) {}
-
-pub unsafe fn db_idx256_next(_iterator: i32, _primary: *mut u64) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn db_idx256_store(
+    scope: u64,
+    table: capi_name,
+    payer: capi_name,
+    id: u64,
+    data: *const uint128_t,
+    data_len: u32,
+) -> i32 {
+    let key = read_idx256(data, data_len);
+    with_host(|host| {
+        let table = (host.receiver, scope, table);
+        host.db
+            .idx_store(SecondaryKind::Idx256, table, payer, id, key)
+    })
+}
+
+pub unsafe extern "C-unwind" fn db_idx256_update(
+    iterator: i32,
+    payer: capi_name,
+    data: *const uint128_t,
+    data_len: u32,
+) {
+    let key = read_idx256(data, data_len);
+    with_host(|host| {
+        host.db.idx_update(
+            SecondaryKind::Idx256,
+            host.receiver,
+            iterator,
+            payer,
+            key,
+        )
+    });
 }
 
-pub unsafe fn db_idx256_previous(_iterator: i32, _primary: *mut u64) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn db_idx256_remove(iterator: i32) {
+    with_host(|host| {
+        host.db
+            .idx_remove(SecondaryKind::Idx256, host.receiver, iterator)
+    });
 }
 
-pub unsafe fn db_idx256_find_primary(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _data: *mut uint128_t,
-    _data_len: u32,
-    _primary: u64,
+pub unsafe extern "C-unwind" fn db_idx256_next(
+    iterator: i32,
+    primary: *mut u64,
 ) -> i32 {
-    Default::default()
+    let (iterator, pk) =
+        with_host(|host| host.db.idx_next(SecondaryKind::Idx256, iterator));
+    if iterator >= 0 && !primary.is_null() {
+        *primary = pk;
+    }
+    iterator
 }
 
-pub unsafe fn db_idx256_find_secondary(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _data: *const uint128_t,
-    _data_len: u32,
-    _primary: *mut u64,
+pub unsafe extern "C-unwind" fn db_idx256_previous(
+    iterator: i32,
+    primary: *mut u64,
 ) -> i32 {
-    Default::default()
+    let (iterator, pk) =
+        with_host(|host| host.db.idx_previous(SecondaryKind::Idx256, iterator));
+    if iterator >= 0 && !primary.is_null() {
+        *primary = pk;
+    }
+    iterator
 }
 
-pub unsafe fn db_idx256_lowerbound(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _data: *mut uint128_t,
-    _data_len: u32,
-    _primary: *mut u64,
+pub unsafe extern "C-unwind" fn db_idx256_find_primary(
+    code: capi_name,
+    scope: u64,
+    table: capi_name,
+    data: *mut uint128_t,
+    data_len: u32,
+    primary: u64,
 ) -> i32 {
-    Default::default()
+    if data_len != 2 {
+        abort("invalid size of secondary key array for idx256");
+    }
+    let (iterator, found) = with_host(|host| {
+        host.db.idx_find_primary(
+            SecondaryKind::Idx256,
+            (code, scope, table),
+            primary,
+        )
+    });
+    if let Some((key, _)) = found {
+        write_idx256(data, key);
+    }
+    iterator
 }
 
-pub unsafe fn db_idx256_upperbound(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _data: *mut uint128_t,
-    _data_len: u32,
-    _primary: *mut u64,
+pub unsafe extern "C-unwind" fn db_idx256_find_secondary(
+    code: capi_name,
+    scope: u64,
+    table: capi_name,
+    data: *const uint128_t,
+    data_len: u32,
+    primary: *mut u64,
 ) -> i32 {
-    Default::default()
+    let key = read_idx256(data, data_len);
+    let (iterator, found) = with_host(|host| {
+        host.db.idx_find_secondary(
+            SecondaryKind::Idx256,
+            (code, scope, table),
+            key,
+        )
+    });
+    if let Some((_, pk)) = found {
+        *primary = pk;
+    }
+    iterator
 }
 
-pub unsafe fn db_idx256_end(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
+pub unsafe extern "C-unwind" fn db_idx256_lowerbound(
+    code: capi_name,
+    scope: u64,
+    table: capi_name,
+    data: *mut uint128_t,
+    data_len: u32,
+    primary: *mut u64,
 ) -> i32 {
-    Default::default()
+    let key = read_idx256(data, data_len);
+    let (iterator, found) = with_host(|host| {
+        host.db
+            .idx_lowerbound(SecondaryKind::Idx256, (code, scope, table), key)
+    });
+    if let Some((key, pk)) = found {
+        write_idx256(data, key);
+        *primary = pk;
+    }
+    iterator
 }
 
-pub unsafe fn db_idx_double_store(
-    _scope: u64,
-    _table: capi_name,
-    _payer: capi_name,
-    _id: u64,
-    _secondary: *const f64,
+pub unsafe extern "C-unwind" fn db_idx256_upperbound(
+    code: capi_name,
+    scope: u64,
+    table: capi_name,
+    data: *mut uint128_t,
+    data_len: u32,
+    primary: *mut u64,
 ) -> i32 {
-    Default::default()
-}
-
-pub unsafe fn db_idx_double_update(
-    _iterator: i32,
-    _payer: capi_name,
-    _secondary: *const f64,
-) {
+    let key = read_idx256(data, data_len);
+    let (iterator, found) = with_host(|host| {
+        host.db
+            .idx_upperbound(SecondaryKind::Idx256, (code, scope, table), key)
+    });
+    if let Some((key, pk)) = found {
+        write_idx256(data, key);
+        *primary = pk;
+    }
+    iterator
 }
 
-pub unsafe fn db_idx_double_remove(_iterator: i32) {}
-
-pub unsafe fn db_idx_double_next(_iterator: i32, _primary: *mut u64) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn db_idx256_end(
+    code: capi_name,
+    scope: u64,
+    table: capi_name,
+) -> i32 {
+    with_host(|host| {
+        host.db.idx_end(SecondaryKind::Idx256, (code, scope, table))
+    })
 }
 
-pub unsafe fn db_idx_double_previous(
-    _iterator: i32,
-    _primary: *mut u64,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn kv_erase(
+    contract: u64,
+    key: *const crate::c_char,
+    key_size: u32,
+) -> i64 {
+    let key = bytes(key, key_size as usize);
+    with_host(|host| host.kv.erase(host.receiver, contract, key))
 }
 
-pub unsafe fn db_idx_double_find_primary(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut f64,
-    _primary: u64,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn kv_set(
+    contract: u64,
+    key: *const crate::c_char,
+    key_size: u32,
+    value: *const crate::c_char,
+    value_size: u32,
+    payer: u64,
+) -> i64 {
+    let key = bytes(key, key_size as usize).to_vec();
+    let value = bytes(value, value_size as usize).to_vec();
+    with_host(|host| host.kv.set(host.receiver, contract, key, value, payer))
+}
+
+pub unsafe extern "C-unwind" fn kv_get(
+    contract: u64,
+    key: *const crate::c_char,
+    key_size: u32,
+    value_size: *mut u32,
+) -> bool {
+    let key = bytes(key, key_size as usize);
+    let size = with_host(|host| host.kv.get(contract, key));
+    if !value_size.is_null() {
+        *value_size = to_u32(size.unwrap_or_default());
+    }
+    size.is_some()
 }
 
-pub unsafe fn db_idx_double_find_secondary(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *const f64,
-    _primary: *mut u64,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn kv_get_data(
+    offset: u32,
+    data: *mut crate::c_char,
+    data_size: u32,
+) -> u32 {
+    with_host(|host| {
+        let staged = host.kv.staged();
+        let rest = staged.get(offset as usize..).unwrap_or_default();
+        copy_out(rest, data, data_size as usize);
+        to_u32(staged.len())
+    })
+}
+
+pub unsafe extern "C-unwind" fn kv_it_create(
+    contract: u64,
+    prefix: *const crate::c_char,
+    size: u32,
+) -> u32 {
+    let prefix = bytes(prefix, size as usize).to_vec();
+    with_host(|host| host.kv.it_create(contract, prefix))
 }
 
-pub unsafe fn db_idx_double_lowerbound(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut f64,
-    _primary: *mut u64,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn kv_it_destroy(itr: u32) {
+    with_host(|host| host.kv.it_destroy(itr));
 }
 
-pub unsafe fn db_idx_double_upperbound(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut f64,
-    _primary: *mut u64,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn kv_it_status(itr: u32) -> i32 {
+    with_host(|host| host.kv.it_status(itr))
 }
 
-pub unsafe fn db_idx_double_end(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-) -> i32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn kv_it_compare(itr_a: u32, itr_b: u32) -> i32 {
+    with_host(|host| host.kv.it_compare(itr_a, itr_b))
 }
 
-pub unsafe fn db_idx_long_double_store(
-    _scope: u64,
-    _table: capi_name,
-    _payer: capi_name,
-    _id: u64,
-    _secondary: *const f64,
+pub unsafe extern "C-unwind" fn kv_it_key_compare(
+    itr: u32,
+    key: *const crate::c_char,
+    size: u32,
 ) -> i32 {
-    Default::default()
+    let key = bytes(key, size as usize);
+    with_host(|host| host.kv.it_key_compare(itr, Some(key)))
 }
 
-pub unsafe fn db_idx_long_double_update(
-    _iterator: i32,
-    _payer: capi_name,
-    _secondary: *const f64,
-) {
+pub unsafe extern "C-unwind" fn kv_it_move_to_end(itr: u32) -> i32 {
+    with_host(|host| host.kv.it_move_to_end(itr))
 }
 
-pub unsafe fn db_idx_long_double_remove(_iterator: i32) {}
-
-pub unsafe fn db_idx_long_double_next(
-    _iterator: i32,
-    _primary: *mut u64,
+unsafe fn kv_found(
+    (status, key_size, value_size): (i32, u32, u32),
+    found_key_size: *mut u32,
+    found_value_size: *mut u32,
 ) -> i32 {
-    Default::default()
+    // nodeos writes the sizes unconditionally
+    if found_key_size.is_null() || found_value_size.is_null() {
+        abort("kv iterator sizes must be written to valid pointers");
+    }
+    *found_key_size = key_size;
+    *found_value_size = value_size;
+    status
 }
 
-pub unsafe fn db_idx_long_double_previous(
-    _iterator: i32,
-    _primary: *mut u64,
+pub unsafe extern "C-unwind" fn kv_it_next(
+    itr: u32,
+    found_key_size: *mut u32,
+    found_value_size: *mut u32,
 ) -> i32 {
-    Default::default()
+    let found = with_host(|host| host.kv.it_next(itr));
+    kv_found(found, found_key_size, found_value_size)
 }
 
-pub unsafe fn db_idx_long_double_find_primary(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut f64,
-    _primary: u64,
+pub unsafe extern "C-unwind" fn kv_it_prev(
+    itr: u32,
+    found_key_size: *mut u32,
+    found_value_size: *mut u32,
 ) -> i32 {
-    Default::default()
+    let found = with_host(|host| host.kv.it_prev(itr));
+    kv_found(found, found_key_size, found_value_size)
 }
 
-pub unsafe fn db_idx_long_double_find_secondary(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *const f64,
-    _primary: *mut u64,
+pub unsafe extern "C-unwind" fn kv_it_lower_bound(
+    itr: u32,
+    key: *const crate::c_char,
+    size: u32,
+    found_key_size: *mut u32,
+    found_value_size: *mut u32,
 ) -> i32 {
-    Default::default()
-}
-
-pub unsafe fn db_idx_long_double_lowerbound(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut f64,
-    _primary: *mut u64,
+    let key = bytes(key, size as usize);
+    let found = with_host(|host| host.kv.it_lower_bound(itr, key));
+    kv_found(found, found_key_size, found_value_size)
+}
+
+/// Copies part of the key or value of an iterator.
+unsafe fn kv_it_copy(
+    itr: u32,
+    offset: u32,
+    dest: *mut crate::c_char,
+    size: u32,
+    actual_size: *mut u32,
+    value: bool,
 ) -> i32 {
-    Default::default()
-}
-
-pub unsafe fn db_idx_long_double_upperbound(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
-    _secondary: *mut f64,
-    _primary: *mut u64,
+    with_host(|host| {
+        let (status, src) = host.kv.it_bytes(itr, value);
+        let rest = src.get(offset as usize..).unwrap_or_default();
+        copy_out(rest, dest, size as usize);
+        if !actual_size.is_null() {
+            *actual_size = to_u32(src.len());
+        }
+        status
+    })
+}
+
+pub unsafe extern "C-unwind" fn kv_it_key(
+    itr: u32,
+    offset: u32,
+    dest: *mut crate::c_char,
+    size: u32,
+    actual_size: *mut u32,
 ) -> i32 {
-    Default::default()
+    kv_it_copy(itr, offset, dest, size, actual_size, false)
 }
 
-pub unsafe fn db_idx_long_double_end(
-    _code: capi_name,
-    _scope: u64,
-    _table: capi_name,
+pub unsafe extern "C-unwind" fn kv_it_value(
+    itr: u32,
+    offset: u32,
+    dest: *mut crate::c_char,
+    size: u32,
+    actual_size: *mut u32,
 ) -> i32 {
-    Default::default()
+    kv_it_copy(itr, offset, dest, size, actual_size, true)
 }
 
-pub unsafe fn check_transaction_authorization(
-    _trx_data: *const crate::c_char,
-    _trx_size: u32,
+/// Checks transaction authorizations against permission levels only, since
+/// the mock host has no keys. A level is satisfied by the same level, or by
+/// the `owner` permission of its actor.
+pub unsafe extern "C-unwind" fn check_transaction_authorization(
+    trx_data: *const crate::c_char,
+    trx_size: u32,
     _pubkeys_data: *const crate::c_char,
     _pubkeys_size: u32,
-    _perms_data: *const crate::c_char,
-    _perms_size: u32,
+    perms_data: *const crate::c_char,
+    perms_size: u32,
 ) -> i32 {
-    Default::default()
-}
-
-pub unsafe fn check_permission_authorization(
+    const OWNER: u64 = 0xa726_ab80_0000_0000;
+    let required =
+        transaction_authorizations(bytes(trx_data, trx_size as usize))
+            .unwrap_or_else(|| abort("failed to unpack transaction"));
+    let provided =
+        unpack_permission_levels(bytes(perms_data, perms_size as usize))
+            .unwrap_or_else(|| abort("failed to unpack permission levels"));
+    let satisfied = required.iter().all(|(actor, permission)| {
+        provided
+            .iter()
+            .any(|(a, p)| a == actor && (p == permission || *p == OWNER))
+    });
+    satisfied.into()
+}
+
+pub unsafe extern "C-unwind" fn check_permission_authorization(
     _account: capi_name,
     _permission: capi_name,
     _pubkeys_data: *const crate::c_char,
@@ -720,150 +1314,335 @@ pub unsafe fn check_permission_authorization(
     _perms_size: u32,
     _delay_us: u64,
 ) -> i32 {
-    Default::default()
+    unsupported("check_permission_authorization")
 }
 
-pub unsafe fn get_permission_last_used(
+pub unsafe extern "C-unwind" fn get_permission_last_used(
     _account: capi_name,
     _permission: capi_name,
 ) -> i64 {
-    Default::default()
+    unsupported("get_permission_last_used")
 }
 
-pub unsafe fn get_account_creation_time(_account: capi_name) -> i64 {
-    Default::default()
+pub unsafe extern "C-unwind" fn get_account_creation_time(
+    _account: capi_name,
+) -> i64 {
+    unsupported("get_account_creation_time")
 }
 
-pub unsafe fn prints(_cstr: *const crate::c_char) {}
-
-pub unsafe fn prints_l(_cstr: *const crate::c_char, _len: u32) {}
+pub unsafe extern "C-unwind" fn prints(cstr: *const crate::c_char) {
+    let s = std::ffi::CStr::from_ptr(cstr.cast());
+    print(&String::from_utf8_lossy(s.to_bytes()));
+}
 
-pub unsafe fn printi(_value: i64) {}
+pub unsafe extern "C-unwind" fn prints_l(cstr: *const crate::c_char, len: u32) {
+    print(&String::from_utf8_lossy(bytes(cstr, len as usize)));
+}
 
-pub unsafe fn printui(_value: u64) {}
+pub unsafe extern "C-unwind" fn printi(value: i64) {
+    print(&value.to_string());
+}
 
-pub unsafe fn printi128(_value: *const int128_t) {}
+pub unsafe extern "C-unwind" fn printui(value: u64) {
+    print(&value.to_string());
+}
 
-pub unsafe fn printui128(_value: *const uint128_t) {}
+pub unsafe extern "C-unwind" fn printi128(value: *const int128_t) {
+    print(&(*value).to_string());
+}
 
-pub unsafe fn printsf(_value: f32) {}
+pub unsafe extern "C-unwind" fn printui128(value: *const uint128_t) {
+    print(&(*value).to_string());
+}
 
-pub unsafe fn printdf(_value: f64) {}
+/// Formats like `std::scientific` in C++, which nodeos uses for floats.
+fn scientific(value: f64, precision: usize) -> String {
+    let s = format!("{:.*e}", precision, value);
+    match s.split_once('e') {
+        Some((mantissa, exp)) => {
+            let (sign, digits) = match exp.strip_prefix('-') {
+                Some(digits) => ('-', digits),
+                None => ('+', exp),
+            };
+            format!("{}e{}{:0>2}", mantissa, sign, digits)
+        }
+        None => s,
+    }
+}
 
-pub unsafe fn printqf(_value: *const f64) {}
+pub unsafe extern "C-unwind" fn printsf(value: f32) {
+    print(&scientific(f64::from(value), 6));
+}
 
-pub unsafe fn printn(_name: u64) {}
+pub unsafe extern "C-unwind" fn printdf(value: f64) {
+    print(&scientific(value, 15));
+}
 
-pub unsafe fn printhex(_data: *const crate::c_void, _datalen: u32) {}
+pub unsafe extern "C-unwind" fn printqf(value: *const u128) {
+    // only the 52 most significant bits of the fraction are printed
+    let bits = *value;
+    let sign = if bits >> 127 == 0 { 1.0 } else { -1.0 };
+    let exponent = i32::try_from((bits >> 112) & 0x7fff).unwrap_or_default();
+    let fraction = (bits >> 60) & ((1 << 52) - 1);
+    #[allow(clippy::cast_precision_loss)]
+    let fraction = fraction as f64 / (1_u64 << 52) as f64;
+    let value = match exponent {
+        0 => sign * fraction * 2_f64.powi(-16382),
+        0x7fff if fraction == 0.0 => sign * f64::INFINITY,
+        0x7fff => f64::NAN,
+        _ => sign * (1.0 + fraction) * 2_f64.powi(exponent - 16383),
+    };
+    print(&scientific(value, 33));
+}
 
-pub unsafe fn get_resource_limits(
-    _account: capi_name,
-    _ram_bytes: *mut i64,
-    _net_weight: *mut i64,
-    _cpu_weight: *mut i64,
-) {
+pub unsafe extern "C-unwind" fn printn(name: u64) {
+    print(&name_to_string(name));
 }
 
-pub unsafe fn set_resource_limits(
-    _account: capi_name,
-    _ram_bytes: i64,
-    _net_weight: i64,
-    _cpu_weight: i64,
+pub unsafe extern "C-unwind" fn printhex(
+    data: *const crate::c_void,
+    datalen: u32,
+) {
+    let hex: String = bytes(data.cast(), datalen as usize)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    print(&hex);
+}
+
+pub unsafe extern "C-unwind" fn get_resource_limits(
+    account: capi_name,
+    ram_bytes: *mut i64,
+    net_weight: *mut i64,
+    cpu_weight: *mut i64,
 ) {
+    let (ram, net, cpu) = with_host(|host| {
+        host.resource_limits
+            .get(&account)
+            .copied()
+            .unwrap_or((-1, -1, -1))
+    });
+    *ram_bytes = ram;
+    *net_weight = net;
+    *cpu_weight = cpu;
+}
+
+pub unsafe extern "C-unwind" fn set_resource_limits(
+    account: capi_name,
+    ram_bytes: i64,
+    net_weight: i64,
+    cpu_weight: i64,
+) {
+    with_host(|host| {
+        host.resource_limits
+            .insert(account, (ram_bytes, net_weight, cpu_weight))
+    });
 }
 
-pub unsafe fn set_proposed_producers(
-    _producer_data: *mut crate::c_char,
-    _producer_data_size: u32,
+pub unsafe extern "C-unwind" fn set_proposed_producers(
+    producer_data: *mut crate::c_char,
+    producer_data_size: u32,
 ) -> i64 {
-    Default::default()
+    let data = bytes(producer_data, producer_data_size as usize).to_vec();
+    with_host(|host| host.proposed_producers = Some(data));
+    0
 }
 
-pub unsafe fn is_privileged(_account: capi_name) -> bool {
-    Default::default()
+pub unsafe extern "C-unwind" fn is_privileged(account: capi_name) -> bool {
+    with_host(|host| host.privileged.contains(&account))
 }
 
-pub unsafe fn set_privileged(_account: capi_name, _is_priv: bool) {}
-
-pub unsafe fn set_blockchain_parameters_packed(
-    _data: *mut crate::c_char,
-    _datalen: u32,
+pub unsafe extern "C-unwind" fn set_privileged(
+    account: capi_name,
+    is_priv: bool,
 ) {
+    with_host(|host| {
+        if is_priv {
+            host.privileged.insert(account);
+        } else {
+            host.privileged.remove(&account);
+        }
+    });
+}
+
+pub unsafe extern "C-unwind" fn set_blockchain_parameters_packed(
+    data: *mut crate::c_char,
+    datalen: u32,
+) {
+    let data = bytes(data, datalen as usize).to_vec();
+    with_host(|host| host.blockchain_parameters = data);
 }
 
-pub unsafe fn get_blockchain_parameters_packed(
-    _data: *mut crate::c_char,
-    _datalen: u32,
+pub unsafe extern "C-unwind" fn get_blockchain_parameters_packed(
+    data: *mut crate::c_char,
+    datalen: u32,
 ) -> u32 {
-    Default::default()
+    let params = with_host(|host| host.blockchain_parameters.clone());
+    if datalen == 0 {
+        return to_u32(params.len());
+    }
+    to_u32(copy_out(&params, data, datalen as usize))
 }
 
-pub unsafe fn eosio_assert(_test: u32, _msg: *const crate::c_char) {}
+pub unsafe extern "C-unwind" fn preactivate_feature(
+    feature_digest: *const capi_checksum256,
+) {
+    let digest = (*feature_digest).hash;
+    let activated = with_host(|host| !host.activated_features.insert(digest));
+    if activated {
+        abort("protocol feature has already been activated");
+    }
+}
 
-pub unsafe fn eosio_assert_message(
-    _test: u32,
-    _msg: *const crate::c_char,
-    _msg_len: u32,
+pub unsafe extern "C-unwind" fn eosio_assert(
+    test: u32,
+    msg: *const crate::c_char,
 ) {
+    if test == 0 {
+        let msg = std::ffi::CStr::from_ptr(msg.cast());
+        abort(&format!(
+            "assertion failure with message: {}",
+            String::from_utf8_lossy(msg.to_bytes())
+        ));
+    }
 }
 
-pub unsafe fn eosio_assert_code(_test: u32, _code: u64) {}
+pub unsafe extern "C-unwind" fn eosio_assert_message(
+    test: u32,
+    msg: *const crate::c_char,
+    msg_len: u32,
+) {
+    if test == 0 {
+        abort(&format!(
+            "assertion failure with message: {}",
+            String::from_utf8_lossy(bytes(msg, msg_len as usize))
+        ));
+    }
+}
 
-pub unsafe fn eosio_exit(_code: i32) {}
+pub unsafe extern "C-unwind" fn eosio_assert_code(test: u32, code: u64) {
+    if test == 0 {
+        abort(&format!("assertion failure with error code: {}", code));
+    }
+}
 
-pub unsafe fn current_time() -> u64 {
-    Default::default()
+pub unsafe extern "C-unwind" fn eosio_exit(code: i32) {
+    std::panic::panic_any(Exit(code))
 }
 
-pub unsafe fn send_deferred(
-    _sender_id: *const uint128_t,
-    _payer: capi_name,
-    _serialized_transaction: *const crate::c_char,
-    _size: usize,
-    _replace_existing: u32,
-) {
+pub unsafe extern "C-unwind" fn current_time() -> u64 {
+    with_host(|host| host.current_time)
+}
+
+pub unsafe extern "C-unwind" fn is_feature_activated(
+    feature_digest: *const capi_checksum256,
+) -> bool {
+    let digest = (*feature_digest).hash;
+    with_host(|host| host.activated_features.contains(&digest))
+}
+
+pub unsafe extern "C-unwind" fn get_sender() -> capi_name {
+    with_host(|host| host.sender)
 }
 
-pub unsafe fn cancel_deferred(_sender_id: *const uint128_t) -> crate::c_int {
-    Default::default()
+pub unsafe extern "C-unwind" fn send_deferred(
+    sender_id: *const uint128_t,
+    payer: capi_name,
+    serialized_transaction: *const crate::c_char,
+    size: usize,
+    replace_existing: u32,
+) {
+    let sender_id = *sender_id;
+    let transaction = bytes(serialized_transaction, size).to_vec();
+    let replaced = with_host(|host| {
+        host.deferred_transactions
+            .insert(sender_id, (payer, transaction))
+            .is_some()
+    });
+    if replaced && replace_existing == 0 {
+        abort("deferred transaction with the same sender_id and payer already exists");
+    }
 }
 
-pub unsafe fn read_transaction(
-    _buffer: *mut crate::c_char,
-    _size: usize,
+pub unsafe extern "C-unwind" fn cancel_deferred(
+    sender_id: *const uint128_t,
+) -> crate::c_int {
+    let sender_id = *sender_id;
+    with_host(|host| {
+        host.deferred_transactions
+            .remove(&sender_id)
+            .is_some()
+            .into()
+    })
+}
+
+pub unsafe extern "C-unwind" fn read_transaction(
+    buffer: *mut crate::c_char,
+    size: usize,
 ) -> usize {
-    Default::default()
+    let transaction = with_host(|host| host.transaction.clone());
+    if size == 0 {
+        return transaction.len();
+    }
+    copy_out(&transaction, buffer.cast(), size)
 }
 
-pub unsafe fn transaction_size() -> usize {
-    Default::default()
+pub unsafe extern "C-unwind" fn transaction_size() -> usize {
+    with_host(|host| host.transaction.len())
 }
 
-pub unsafe fn tapos_block_num() -> crate::c_int {
-    Default::default()
+pub unsafe extern "C-unwind" fn tapos_block_num() -> crate::c_int {
+    with_host(|host| i32::from(host.ref_block_num))
 }
 
-pub unsafe fn tapos_block_prefix() -> crate::c_int {
-    Default::default()
+#[allow(clippy::cast_possible_wrap)]
+pub unsafe extern "C-unwind" fn tapos_block_prefix() -> crate::c_int {
+    with_host(|host| host.ref_block_prefix as i32)
 }
 
-pub unsafe fn expiration() -> u32 {
-    Default::default()
+pub unsafe extern "C-unwind" fn expiration() -> u32 {
+    with_host(|host| host.expiration)
 }
 
-pub unsafe fn get_action(
-    _type_: u32,
-    _index: u32,
-    _buff: *mut crate::c_char,
-    _size: usize,
+pub unsafe extern "C-unwind" fn get_action(
+    type_: u32,
+    index: u32,
+    buff: *mut crate::c_char,
+    size: usize,
 ) -> crate::c_int {
-    Default::default()
+    let action = with_host(|host| {
+        let actions = match type_ {
+            0 => &host.context_free_actions,
+            1 => &host.transaction_actions,
+            _ => abort("action is not found"),
+        };
+        actions.get(index as usize).cloned()
+    });
+    let action = match action {
+        Some(action) => action,
+        None => return -1,
+    };
+    if action.len() <= size {
+        copy_out(&action, buff.cast(), size);
+    }
+    i32::try_from(action.len()).unwrap_or(i32::MAX)
 }
 
-pub unsafe fn get_context_free_data(
-    _index: u32,
-    _buff: *mut crate::c_char,
-    _size: usize,
+pub unsafe extern "C-unwind" fn get_context_free_data(
+    index: u32,
+    buff: *mut crate::c_char,
+    size: usize,
 ) -> crate::c_int {
-    Default::default()
+    let data =
+        with_host(|host| host.context_free_data.get(index as usize).cloned());
+    let data = match data {
+        Some(data) => data,
+        None => return -1,
+    };
+    let len = if size == 0 {
+        data.len()
+    } else {
+        copy_out(&data, buff.cast(), size)
+    };
+    i32::try_from(len).unwrap_or(i32::MAX)
 }
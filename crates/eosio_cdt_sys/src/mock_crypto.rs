@@ -0,0 +1,204 @@
+//! Implementations of the crypto primitives intrinsics for the mock host.
+//!
+//! The crates these are built on are only available with the `mock`
+//! feature.
+use bn::{AffineG1, AffineG2, Fq, Fq2, Fr, Group, Gt, G1, G2};
+use num_bigint::BigUint;
+use sha2::Digest;
+use std::vec::Vec;
+
+/// Hashes `data` with SHA-256.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    sha2::Sha256::digest(data).into()
+}
+
+/// Hashes `data` with SHA-1.
+pub fn sha1(data: &[u8]) -> [u8; 20] {
+    sha1::Sha1::digest(data).into()
+}
+
+/// Hashes `data` with SHA-512.
+pub fn sha512(data: &[u8]) -> [u8; 64] {
+    sha2::Sha512::digest(data).into()
+}
+
+/// Hashes `data` with RIPEMD-160.
+pub fn ripemd160(data: &[u8]) -> [u8; 20] {
+    ripemd::Ripemd160::digest(data).into()
+}
+
+/// Hashes `data` with SHA3-256, or with Keccak-256 if `keccak` is set.
+pub fn sha3(data: &[u8], keccak: bool) -> [u8; 32] {
+    if keccak {
+        sha3::Keccak256::digest(data).into()
+    } else {
+        sha3::Sha3_256::digest(data).into()
+    }
+}
+
+/// Reads little-endian 64-bit words.
+fn le_words<const N: usize>(bytes: &[u8]) -> [u64; N] {
+    let mut words = [0_u64; N];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks(8)) {
+        let mut buf = [0_u8; 8];
+        buf.copy_from_slice(chunk);
+        *word = u64::from_le_bytes(buf);
+    }
+    words
+}
+
+/// The BLAKE2b compression function `F`, as specified by EIP-152. Words are
+/// little-endian. Returns `None` if an input has the wrong length.
+pub fn blake2_f(
+    rounds: u32,
+    state: &[u8],
+    message: &[u8],
+    t0: &[u8],
+    t1: &[u8],
+    last: bool,
+) -> Option<[u8; 64]> {
+    if state.len() != 64
+        || message.len() != 128
+        || t0.len() != 8
+        || t1.len() != 8
+    {
+        return None;
+    }
+    let mut h: [u64; 8] = le_words(state);
+    let [t0]: [u64; 1] = le_words(t0);
+    let [t1]: [u64; 1] = le_words(t1);
+    eip_152::compress(
+        &mut h,
+        le_words(message),
+        [t0, t1],
+        last,
+        rounds as usize,
+    );
+    let mut out = [0_u8; 64];
+    for (word, bytes) in h.iter().zip(out.chunks_mut(8)) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    Some(out)
+}
+
+/// Recovers the uncompressed secp256k1 public key that produced a signature,
+/// which is a recovery id of 27 to 34 followed by `r` and `s`.
+pub fn k1_recover(signature: &[u8], digest: &[u8]) -> Option<[u8; 65]> {
+    if signature.len() != 65 {
+        return None;
+    }
+    let (v, rs) = signature.split_first()?;
+    let recovery_id = v.checked_sub(27).filter(|id| *id < 8)? & 3;
+    let recovery_id = libsecp256k1::RecoveryId::parse(recovery_id).ok()?;
+    let signature = libsecp256k1::Signature::parse_standard_slice(rs).ok()?;
+    let message = libsecp256k1::Message::parse_slice(digest).ok()?;
+    let key = libsecp256k1::recover(&message, &signature, &recovery_id).ok()?;
+    Some(key.serialize())
+}
+
+/// Recovers the packed public key that produced a packed signature. Only K1
+/// signatures are supported.
+pub fn recover_key(digest: &[u8], signature: &[u8]) -> Option<[u8; 34]> {
+    // variant tag, recovery id, r and s
+    let (tag, data) = signature.split_first()?;
+    let (header, rs) = data.split_first()?;
+    if *tag != 0 || rs.len() != 64 {
+        return None;
+    }
+    let recovery_id = header.checked_sub(4)?;
+    let recovery_id = libsecp256k1::RecoveryId::parse_rpc(recovery_id).ok()?;
+    let signature = libsecp256k1::Signature::parse_standard_slice(rs).ok()?;
+    let message = libsecp256k1::Message::parse_slice(digest).ok()?;
+    let key = libsecp256k1::recover(&message, &signature, &recovery_id).ok()?;
+    let mut packed = [0_u8; 34];
+    packed[1..].copy_from_slice(&key.serialize_compressed());
+    Some(packed)
+}
+
+/// Computes `base ^ exponent mod modulus` for big-endian numbers. The result
+/// has the same length as the modulus, and is zero if the modulus is zero.
+pub fn mod_exp(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+    let m = BigUint::from_bytes_be(modulus);
+    let mut result = vec![0; modulus.len()];
+    if m == BigUint::default() {
+        return result;
+    }
+    // the value is less than the modulus, so it always fits
+    let value = BigUint::from_bytes_be(base)
+        .modpow(&BigUint::from_bytes_be(exponent), &m)
+        .to_bytes_be();
+    let start = result.len() - value.len();
+    result[start..].copy_from_slice(&value);
+    result
+}
+
+/// Reads a G1 point, where all zeros is the point at infinity.
+fn read_g1(bytes: &[u8]) -> Option<G1> {
+    let x = Fq::from_slice(bytes.get(..32)?).ok()?;
+    let y = Fq::from_slice(bytes.get(32..64)?).ok()?;
+    if x.is_zero() && y.is_zero() {
+        Some(G1::zero())
+    } else {
+        AffineG1::new(x, y).ok().map(Into::into)
+    }
+}
+
+fn write_g1(point: G1) -> [u8; 64] {
+    let mut bytes = [0_u8; 64];
+    if let Some(point) = AffineG1::from_jacobian(point) {
+        // both halves are exactly 32 bytes, so these can't fail
+        let _ = point.x().to_big_endian(&mut bytes[..32]);
+        let _ = point.y().to_big_endian(&mut bytes[32..]);
+    }
+    bytes
+}
+
+/// Reads a G2 point, encoded as `x.1, x.0, y.1, y.0`.
+fn read_g2(bytes: &[u8]) -> Option<G2> {
+    let mut parts = bytes.chunks(32).map(|part| Fq::from_slice(part).ok());
+    let x1 = parts.next()??;
+    let x0 = parts.next()??;
+    let y1 = parts.next()??;
+    let y0 = parts.next()??;
+    let x = Fq2::new(x0, x1);
+    let y = Fq2::new(y0, y1);
+    if x.is_zero() && y.is_zero() {
+        Some(G2::zero())
+    } else {
+        AffineG2::new(x, y).ok().map(Into::into)
+    }
+}
+
+/// Adds two alt_bn128 G1 points. Returns `None` if a point is invalid.
+pub fn alt_bn128_add(a: &[u8], b: &[u8]) -> Option<[u8; 64]> {
+    if a.len() != 64 || b.len() != 64 {
+        return None;
+    }
+    Some(write_g1(read_g1(a)? + read_g1(b)?))
+}
+
+/// Multiplies an alt_bn128 G1 point by a big-endian scalar. Returns `None`
+/// if the point is invalid.
+pub fn alt_bn128_mul(point: &[u8], scalar: &[u8]) -> Option<[u8; 64]> {
+    if point.len() != 64 || scalar.len() != 32 {
+        return None;
+    }
+    let scalar = Fr::from_slice(scalar).ok()?;
+    Some(write_g1(read_g1(point)? * scalar))
+}
+
+/// Checks that the product of the pairings of `(G1, G2)` pairs is one.
+/// Returns `None` if a point is invalid.
+pub fn alt_bn128_pair(pairs: &[u8]) -> Option<bool> {
+    if !pairs.len().is_multiple_of(192) {
+        return None;
+    }
+    let points = pairs
+        .chunks(192)
+        .map(|pair| {
+            let (g1, g2) = pair.split_at(64);
+            Some((read_g1(g1)?, read_g2(g2)?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(bn::pairing_batch(&points) == Gt::one())
+}
@@ -1,4 +1,4 @@
-//! Macros for creating compile-time EOSIO names and symbols.
+//! Macros for creating compile-time EOSIO names, symbols and assets.
 //!
 //! Creating EOSIO names:
 //!
@@ -16,6 +16,13 @@
 //! use eosio_macros::s;
 //! assert_eq!(s!(4, "EOS"), 1397703940);
 //! ```
+//!
+//! Creating EOSIO assets:
+//!
+//! ```
+//! use eosio_macros::asset;
+//! assert_eq!(asset!("1.0000 EOS").amount, 1_0000);
+//! ```
 #![no_std]
 #![allow(clippy::missing_docs_in_private_items)]
 
@@ -48,6 +55,43 @@ pub use eosio_macros_internal::n;
 #[proc_macro_hack]
 pub use eosio_macros_internal::s;
 
+/// Macro for creating EOSIO assets at compile time. The precision is the
+/// number of decimal places, which must be 18 or less, and the amount must be
+/// `2^62 - 1` or less.
+///
+/// # Examples
+///
+/// ```
+/// use eosio::{Asset, Symbol};
+/// use eosio_macros::{asset, s};
+/// assert_eq!(
+///     asset!("1.0000 EOS"),
+///     Asset {
+///         amount: 1_0000,
+///         symbol: Symbol::new(s!(4, "EOS")),
+///     }
+/// );
+/// assert_eq!(asset!("-25 TGFT").amount, -25);
+/// ```
+#[proc_macro_hack]
+pub use eosio_macros_internal::asset;
+
+/// Macro for creating EOSIO extended assets at compile time, from an asset
+/// and the contract that issued it.
+///
+/// # Examples
+///
+/// ```
+/// use eosio::AccountName;
+/// use eosio_macros::{asset, ext_asset, n};
+/// let quantity = ext_asset!("1.0000 EOS@eosio.token");
+/// assert_eq!(quantity.quantity, asset!("1.0000 EOS"));
+/// assert_eq!(quantity.contract, AccountName::new(n!("eosio.token")));
+/// ```
+#[proc_macro_hack]
+pub use eosio_macros_internal::ext_asset;
+
 pub use eosio_macros_internal::{
-    abi, action, table, NumBytes, Read, Table, Write,
+    abi, action, contract, on_notify, ricardian_clauses, table, ContractError,
+    KvTable, NumBytes, Read, Table, Write,
 };
@@ -0,0 +1,7 @@
+extern crate eosio_macros;
+
+use eosio_macros::asset;
+
+fn main() {
+    let _ = asset!("1.0000000000000000000 EOS");
+}
@@ -0,0 +1,7 @@
+extern crate eosio_macros;
+
+use eosio_macros::asset;
+
+fn main() {
+    let _ = asset!("4611686018427387904 EOS");
+}
@@ -0,0 +1,7 @@
+extern crate eosio_macros;
+
+use eosio_macros::ext_asset;
+
+fn main() {
+    let _ = ext_asset!("1.0000 EOS");
+}
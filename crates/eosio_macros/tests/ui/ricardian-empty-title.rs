@@ -0,0 +1,8 @@
+// paths are relative to the crate that trybuild generates in
+// target/tests/trybuild/eosio_macros
+#[eosio::action(
+    ricardian = "../../../../crates/eosio_macros/tests/ui/ricardian/empty-title.md"
+)]
+fn transfer(to: eosio::AccountName, quantity: eosio::Asset) {}
+
+fn main() {}
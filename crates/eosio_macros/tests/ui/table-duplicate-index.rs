@@ -0,0 +1,14 @@
+use eosio::{NumBytes, Read, Table, Write};
+
+#[derive(Table, Read, Write, NumBytes)]
+#[eosio(table_name = "scores")]
+struct Score {
+    #[eosio(primary_key)]
+    id: u64,
+    #[eosio(secondary_key(index = 0))]
+    points: u64,
+    #[eosio(secondary_key(index = 0))]
+    bonus: u64,
+}
+
+fn main() {}
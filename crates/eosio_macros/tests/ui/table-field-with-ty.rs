@@ -0,0 +1,12 @@
+use eosio::{NumBytes, Read, Table, Write};
+
+#[derive(Table, Read, Write, NumBytes)]
+#[eosio(table_name = "scores")]
+struct Score {
+    #[eosio(primary_key)]
+    id: u64,
+    #[eosio(secondary_key(with = "Score::points_key", ty = "u128"))]
+    points: u64,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use eosio::{NumBytes, Read, Table, Write};
+
+#[derive(Table, Read, Write, NumBytes)]
+#[eosio(table_name = "scores")]
+struct Score {
+    #[eosio(primary_key)]
+    id: u64,
+    #[eosio(secondary_key(index = 16))]
+    points: u64,
+}
+
+fn main() {}
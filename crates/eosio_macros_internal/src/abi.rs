@@ -28,20 +28,91 @@ impl Parse for AbiPair {
     }
 }
 
-struct AbiPairs(Vec<AbiPair>);
+mod kw {
+    syn::custom_keyword!(notify);
+}
+
+/// Either an action, or a notification handler written as `notify handler`.
+enum AbiEntry {
+    Action(Box<AbiPair>),
+    Notify(Path),
+}
+
+impl Parse for AbiEntry {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        // `notify` on its own is an action named `notify`
+        let fork = input.fork();
+        let is_notify = fork.parse::<kw::notify>().is_ok()
+            && !fork.is_empty()
+            && !fork.peek(Token![,])
+            && !fork.peek(Token![@]);
+        if is_notify {
+            input.parse::<kw::notify>()?;
+            Ok(AbiEntry::Notify(input.parse()?))
+        } else {
+            Ok(AbiEntry::Action(input.parse()?))
+        }
+    }
+}
+
+struct AbiPairs(Vec<AbiEntry>);
 
 impl Parse for AbiPairs {
     fn parse(input: ParseStream) -> ParseResult<Self> {
         let parsed =
-            Punctuated::<AbiPair, Token![,]>::parse_separated_nonempty(input)?;
-        let pairs: Vec<AbiPair> = parsed.into_iter().collect();
+            Punctuated::<AbiEntry, Token![,]>::parse_separated_nonempty(input)?;
+        let pairs: Vec<AbiEntry> = parsed.into_iter().collect();
         Ok(AbiPairs(pairs))
     }
 }
 
 pub fn expand(input: TokenStream) -> TokenStream {
-    let pairs = parse_macro_input!(input as AbiPairs);
-    let actions = pairs.0.into_iter().map(|pair| {
+    let entries = parse_macro_input!(input as AbiPairs);
+    let mut pairs = Vec::new();
+    let mut handlers = Vec::new();
+    for entry in entries.0 {
+        match entry {
+            AbiEntry::Action(pair) => pairs.push(*pair),
+            AbiEntry::Notify(handler) => handlers.push(handler),
+        }
+    }
+    // handlers for a specific contract take precedence over wildcards
+    let notify = |wildcard: bool| {
+        let code_matches = if wildcard {
+            quote!(CODE.is_none())
+        } else {
+            quote!(CODE == Some(eosio::AccountName::new(code)))
+        };
+        handlers.iter().map(move |handler| quote! {
+            else if code != receiver
+                && action == <#handler as eosio::NotifyFn>::NAME.as_u64()
+                && <#handler as eosio::NotifyFn>::#code_matches
+            {
+                let data = eosio_cdt::read_action_data::<#handler>().expect("failed to read action data");
+                <#handler as eosio::NotifyFn>::call(data, eosio::AccountName::new(code));
+            }
+        })
+    };
+    let notify_specific = notify(false);
+    let notify_any = notify(true);
+    let reject_notifications = if handlers.is_empty() {
+        quote!()
+    } else {
+        let names = handlers.iter().map(|handler| {
+            quote!(action == <#handler as eosio::NotifyFn>::NAME.as_u64())
+        });
+        quote! {
+            // never trust notifications from contracts that weren't asked for
+            else if code != receiver && (#(#names)||*) {
+                panic!(
+                    "unexpected notification of '{}' from '{}'",
+                    eosio::Name::new(action),
+                    eosio::Name::new(code)
+                );
+            }
+        }
+    };
+    let actions = pairs.into_iter().map(|pair| {
         let code = pair
             .code
             .map(|code| quote!(eosio::n!(#code)))
@@ -50,11 +121,35 @@ pub fn expand(input: TokenStream) -> TokenStream {
         quote! {
             else if code == #code && action == <#action as eosio::ActionFn>::NAME.as_u64() {
                 let data = eosio_cdt::read_action_data::<#action>().expect("failed to read action data");
-                <#action as eosio::ActionFn>::call(data)
+                let output = <#action as eosio::ActionFn>::call(data);
+                // actions returning `()` must not require the ACTION_RETURN_VALUE feature
+                if ::core::mem::size_of::<<#action as eosio::ActionFn>::Output>() > 0 {
+                    eosio_cdt::set_action_return_value(&output)
+                        .expect("failed to write action return value");
+                }
             }
         }
     });
     let expanded = quote! {
+        /// Runs the handler of an action sent to `code`, or of a
+        /// notification of it, as `receiver`. Called by `apply`, and by
+        /// `MockHost::push_notification` in tests.
+        pub fn dispatch(receiver: u64, code: u64, action: u64) {
+            if action == eosio::n!("onerror") {
+                assert!(
+                    code == eosio::n!("eosio"),
+                    "onerror action's are only valid from the \"eosio\" system account"
+                );
+            }
+            #(#actions)*
+            #(#notify_specific)*
+            #(#notify_any)*
+            #reject_notifications
+            else if code == receiver {
+                panic!("unknown action '{}'", eosio::Name::new(action));
+            }
+        }
+
         #[cfg(target_arch = "wasm32")]
         #[no_mangle]
         pub extern "C" fn apply(receiver: u64, code: u64, action: u64) {
@@ -67,16 +162,7 @@ pub fn expand(input: TokenStream) -> TokenStream {
                     .unwrap_or_else(|| panic_info.to_string());
                 eosio_cdt::check(false, &message);
             }));
-            if action == eosio::n!("onerror") {
-                assert!(
-                    code == eosio::n!("eosio"),
-                    "onerror action's are only valid from the \"eosio\" system account"
-                );
-            }
-            #(#actions)*
-            else if code == receiver {
-                panic!("unknown action '{}'", eosio::Name::new(action));
-            }
+            dispatch(receiver, code, action);
         }
     };
     expanded.into()
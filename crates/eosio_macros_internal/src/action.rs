@@ -1,35 +1,269 @@
+use crate::{internal::abi_type_name, ricardian::ricardian_contract};
 use heck::CamelCase;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream, Result as ParseResult},
-    Block, FnArg, Ident, ItemFn, LitStr, Signature,
+    token::Comma,
+    Block, Error as ParseError, FnArg, GenericArgument, Ident, ItemFn, Lit,
+    LitInt, LitStr, Meta, NestedMeta, PathArguments, ReturnType, Signature,
+    Type,
 };
 
+mod kw {
+    syn::custom_keyword!(auth);
+    syn::custom_keyword!(ricardian);
+}
+
 pub struct ActionArgs {
-    name: Option<LitStr>,
+    pub name: Option<LitStr>,
+    /// Arguments whose authority is required, from `auth = "arg"`
+    pub auth: Vec<LitStr>,
+    /// Path to the Ricardian contract, from `ricardian = "path.md"`
+    pub ricardian: Option<LitStr>,
 }
 
 impl Parse for ActionArgs {
     fn parse(input: ParseStream) -> ParseResult<Self> {
         let name = input.parse::<Option<LitStr>>()?;
-        Ok(Self { name })
+        let mut auth = Vec::new();
+        let mut ricardian = None;
+        let mut first = name.is_none();
+        while !input.is_empty() {
+            if !first {
+                input.parse::<Comma>()?;
+            }
+            first = false;
+            if input.peek(kw::ricardian) {
+                input.parse::<kw::ricardian>()?;
+                input.parse::<syn::Token![=]>()?;
+                ricardian = Some(input.parse::<LitStr>()?);
+            } else {
+                input.parse::<kw::auth>()?;
+                input.parse::<syn::Token![=]>()?;
+                auth.push(input.parse::<LitStr>()?);
+            }
+        }
+        Ok(Self {
+            name,
+            auth,
+            ricardian,
+        })
+    }
+}
+
+/// Checks declared on an argument with `#[eosio(...)]`. `valid` and
+/// `positive` hold the custom message of the check, if one was set.
+#[derive(Default)]
+struct ArgChecks {
+    max_len: Option<LitInt>,
+    valid: Option<Option<LitStr>>,
+    positive: Option<Option<LitStr>>,
+}
+
+/// Returns the message of `#[eosio(check = "message")]`.
+fn check_message(lit: Lit) -> ParseResult<Option<LitStr>> {
+    match lit {
+        Lit::Str(lit) => Ok(Some(lit)),
+        lit => Err(ParseError::new_spanned(lit, "expected a string literal")),
+    }
+}
+
+impl ArgChecks {
+    fn parse_meta(meta: Meta) -> ParseResult<Self> {
+        let list = match meta {
+            Meta::List(list) => list,
+            meta => {
+                return Err(ParseError::new_spanned(
+                    meta,
+                    "expected #[eosio(...)]",
+                ))
+            }
+        };
+        let mut checks = Self::default();
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::Path(path))
+                    if path.is_ident("valid") =>
+                {
+                    checks.valid = Some(None);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv))
+                    if nv.path.is_ident("valid") =>
+                {
+                    checks.valid = Some(check_message(nv.lit)?);
+                }
+                NestedMeta::Meta(Meta::Path(path))
+                    if path.is_ident("positive") =>
+                {
+                    checks.positive = Some(None);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv))
+                    if nv.path.is_ident("positive") =>
+                {
+                    checks.positive = Some(check_message(nv.lit)?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv))
+                    if nv.path.is_ident("max_len") =>
+                {
+                    match nv.lit {
+                        Lit::Int(lit) => checks.max_len = Some(lit),
+                        lit => {
+                            return Err(ParseError::new_spanned(
+                                lit,
+                                "expected an integer",
+                            ))
+                        }
+                    }
+                }
+                nested => {
+                    return Err(ParseError::new_spanned(
+                        nested,
+                        "expected `max_len = N`, `valid` or `positive`, \
+                         optionally with a message: `valid = \"...\"`",
+                    ))
+                }
+            }
+        }
+        Ok(checks)
     }
 }
 
+/// Removes the `#[eosio(...)]` attributes from the arguments of an action and
+/// returns the statements that require the authority of the `auth` arguments
+/// and run the argument checks. The statements expect the arguments to be
+/// bound to their names.
+pub fn arg_checks<'a, I>(
+    inputs: I,
+    auth: &[LitStr],
+) -> ParseResult<TokenStream2>
+where
+    I: IntoIterator<Item = &'a mut FnArg>,
+{
+    let mut names = Vec::new();
+    let mut checks = quote!();
+    for input in inputs {
+        let input = match input {
+            FnArg::Typed(input) => input,
+            FnArg::Receiver(_) => continue,
+        };
+        let pat = &input.pat;
+        let name = quote!(#pat).to_string();
+        let mut attrs = Vec::new();
+        for attr in input.attrs.drain(..) {
+            if attr.path.is_ident("eosio") {
+                let arg = ArgChecks::parse_meta(attr.parse_meta()?)?;
+                if let Some(max_len) = arg.max_len {
+                    let msg = format!(
+                        "{} has more than {} bytes",
+                        name,
+                        max_len.base10_digits()
+                    );
+                    checks = quote! {
+                        #checks
+                        assert!(#pat.len() <= #max_len, #msg);
+                    };
+                }
+                if let Some(msg) = arg.valid {
+                    let msg = msg.map_or_else(
+                        || format!("invalid {}", name),
+                        |msg| msg.value(),
+                    );
+                    checks = quote! {
+                        #checks
+                        assert!(#pat.is_valid(), #msg);
+                    };
+                }
+                if let Some(msg) = arg.positive {
+                    let msg = msg.map_or_else(
+                        || format!("{} must be positive", name),
+                        |msg| msg.value(),
+                    );
+                    checks = quote! {
+                        #checks
+                        assert!(#pat.amount > 0, #msg);
+                    };
+                }
+            } else {
+                attrs.push(attr);
+            }
+        }
+        input.attrs = attrs;
+        names.push(name);
+    }
+    let mut require_auth = quote!();
+    for lit in auth {
+        if !names.contains(&lit.value()) {
+            return Err(ParseError::new_spanned(
+                lit,
+                "`auth` must name an argument of the action",
+            ));
+        }
+        let ident = Ident::new(&lit.value(), lit.span());
+        require_auth = quote! {
+            #require_auth
+            eosio_cdt::require_auth(#ident);
+        };
+    }
+    Ok(quote! {
+        #require_auth
+        #checks
+    })
+}
+
+/// Returns the value of `ActionFn::REQUIRED_AUTH`.
+pub fn required_auth(auth: &[LitStr]) -> TokenStream2 {
+    quote!(&[#(#auth),*])
+}
+
+/// Returns the value of `ActionFn::RICARDIAN_CONTRACT`, checking the
+/// Ricardian contract against the name and arguments of the action.
+pub fn ricardian<'a, I>(
+    args: &ActionArgs,
+    action: &LitStr,
+    inputs: I,
+) -> ParseResult<TokenStream2>
+where
+    I: IntoIterator<Item = &'a FnArg>,
+{
+    let lit = match &args.ricardian {
+        Some(lit) => lit,
+        None => return Ok(quote!("")),
+    };
+    let names: Vec<String> = inputs
+        .into_iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(input) => {
+                let pat = &input.pat;
+                Some(quote!(#pat).to_string())
+            }
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    ricardian_contract(lit, &action.value(), &names)
+}
+
 pub struct ActionFn {
     sig: Signature,
     block: Box<Block>,
     args: ActionArgs,
+    checks: TokenStream2,
+    ricardian: TokenStream2,
 }
 
 impl ActionFn {
-    pub fn new(args: ActionArgs, item: ItemFn) -> Self {
-        Self {
+    pub fn new(args: ActionArgs, mut item: ItemFn) -> ParseResult<Self> {
+        let checks = arg_checks(&mut item.sig.inputs, &args.auth)?;
+        let mut action = Self {
             sig: item.sig,
             block: item.block,
             args,
-        }
+            checks,
+            ricardian: quote!(),
+        };
+        action.ricardian =
+            ricardian(&action.args, &action.action_name(), &action.sig.inputs)?;
+        Ok(action)
     }
 
     pub fn struct_ident(&self) -> Ident {
@@ -49,48 +283,101 @@ impl ActionFn {
     }
 }
 
-impl ToTokens for ActionFn {
-    fn to_tokens(&self, tokens: &mut TokenStream2) {
-        let inputs = &self.sig.inputs;
-        let mut struct_fields = quote!();
-        let mut assign_args = quote!();
-        for input in inputs.iter() {
-            match input {
-                FnArg::Typed(input) => {
-                    let pat = &input.pat;
-                    let ty = &input.ty;
-                    let ty_str = quote!(#ty).to_string();
-                    let serde_attr = if ty_str == "bool" {
-                        quote!(
-                            #[cfg_attr(
-                                feature = "serde",
-                                serde(
-                                    deserialize_with = "::eosio::bool_from_u8",
-                                    serialize_with = "::eosio::bool_to_u8"
-                                )
-                            )]
-                        )
-                    } else {
-                        quote!()
-                    };
-                    struct_fields = quote! {
-                        #struct_fields
-                        #serde_attr
-                        pub #pat: #ty,
-                    };
-                    assign_args = quote! {
-                        #assign_args
-                        let #pat = self.#pat;
-                    };
+/// Returns the fields of the struct generated for the data of an action-like
+/// function, and the statements that bind them to the argument names.
+pub fn data_fields<'a, I>(inputs: I) -> (TokenStream2, TokenStream2)
+where
+    I: IntoIterator<Item = &'a FnArg>,
+{
+    let mut struct_fields = quote!();
+    let mut assign_args = quote!();
+    for input in inputs {
+        match input {
+            FnArg::Typed(input) => {
+                let pat = &input.pat;
+                let ty = &input.ty;
+                let ty_str = quote!(#ty).to_string();
+                let serde_attr = if ty_str == "bool" {
+                    quote!(
+                        #[cfg_attr(
+                            feature = "serde",
+                            serde(
+                                deserialize_with = "::eosio::bool_from_u8",
+                                serialize_with = "::eosio::bool_to_u8"
+                            )
+                        )]
+                    )
+                } else {
+                    quote!()
+                };
+                struct_fields = quote! {
+                    #struct_fields
+                    #serde_attr
+                    pub #pat: #ty,
+                };
+                assign_args = quote! {
+                    #assign_args
+                    let #pat = self.#pat;
+                };
+            }
+            _ => unimplemented!(),
+        }
+    }
+    (struct_fields, assign_args)
+}
+
+/// Returns the `ActionFn::Output` of an action, which is `T` for actions
+/// returning `Result<T, E>`, its ABI type name, which is empty for `()`, and
+/// whether the action returns a `Result`.
+pub fn action_output(output: &ReturnType) -> (TokenStream2, String, bool) {
+    let ty = match output {
+        ReturnType::Default => return (quote!(()), String::new(), false),
+        ReturnType::Type(_, ty) => ty,
+    };
+    if let Type::Path(path) = &**ty {
+        let segment = path.path.segments.last();
+        if let Some(segment) = segment.filter(|s| s.ident == "Result") {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(ok)) = args.args.first() {
+                    return (quote!(#ok), abi_type_name(ok), true);
                 }
-                _ => unimplemented!(),
             }
         }
+    }
+    (quote!(#ty), abi_type_name(ty), false)
+}
+
+/// Unwraps the result of an action, aborting with the error with
+/// `eosio_cdt::abort_with_error`.
+pub fn unwrap_output(result: TokenStream2) -> TokenStream2 {
+    quote! {
+        match #result {
+            Ok(output) => output,
+            Err(error) => eosio_cdt::abort_with_error(&error),
+        }
+    }
+}
+
+impl ToTokens for ActionFn {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let (struct_fields, assign_args) = data_fields(&self.sig.inputs);
         let block = &self.block;
+        let checks = &self.checks;
+        let required_auth = required_auth(&self.args.auth);
+        let ricardian = &self.ricardian;
 
         let struct_ident = self.struct_ident();
         let type_ident = &self.sig.ident;
         let action_name = self.action_name();
+        let (output, output_abi_type, is_result) =
+            action_output(&self.sig.output);
+        let body = match &self.sig.output {
+            ReturnType::Type(_, ty) if is_result => {
+                // the block may return early with `return` or `?`
+                unwrap_output(quote!((move || -> #ty #block)()))
+            }
+            _ => quote!(#block),
+        };
 
         let expanded = quote! {
             #[derive(Clone, eosio::Read, eosio::Write, eosio::NumBytes)]
@@ -106,9 +393,14 @@ impl ToTokens for ActionFn {
             #[automatically_derived]
             impl eosio::ActionFn for #struct_ident {
                 const NAME: eosio::ActionName = eosio::ActionName::new(eosio::n!(#action_name));
-                fn call(self) {
+                const REQUIRED_AUTH: &'static [&'static str] = #required_auth;
+                const RICARDIAN_CONTRACT: &'static str = #ricardian;
+                const OUTPUT_ABI_TYPE: &'static str = #output_abi_type;
+                type Output = #output;
+                fn call(self) -> Self::Output {
                     #assign_args
-                    #block
+                    #checks
+                    #body
                 }
             }
 
@@ -0,0 +1,156 @@
+use eosio_numstr::{name_from_bytes, symbol_from_bytes};
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream, Result},
+    Error as ParseError, LitStr,
+};
+
+/// The largest precision a symbol can have.
+const MAX_PRECISION: usize = 18;
+
+/// The largest amount an asset can have, `2^62 - 1`.
+const MAX_AMOUNT: i128 = (1 << 62) - 1;
+
+/// Parses an asset like `"1.0000 EOS"` into its amount and symbol.
+fn parse_asset(lit: &LitStr, value: &str) -> Result<(i64, u64)> {
+    let error = |msg: String| ParseError::new_spanned(lit, msg);
+    let mut parts = value.splitn(2, ' ');
+    let (number, code) = match (parts.next(), parts.next()) {
+        (Some(number), Some(code))
+            if !number.is_empty() && !code.is_empty() =>
+        {
+            (number, code)
+        }
+        _ => {
+            return Err(error(
+                "expected an asset like \"1.0000 EOS\"".to_string(),
+            ))
+        }
+    };
+    let (negative, digits) = match number.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, number),
+    };
+    let (whole, fraction) = match digits.find('.') {
+        Some(index) => (&digits[..index], &digits[index + 1..]),
+        None => (digits, ""),
+    };
+    if whole.is_empty() || (digits.contains('.') && fraction.is_empty()) {
+        return Err(error(format!("invalid amount \"{}\"", number)));
+    }
+    if let Some(c) = whole
+        .chars()
+        .chain(fraction.chars())
+        .find(|c| !c.is_ascii_digit())
+    {
+        return Err(error(format!(
+            "amount contains invalid character '{}'",
+            c
+        )));
+    }
+    if fraction.len() > MAX_PRECISION {
+        return Err(error(format!(
+            "precision is {}, must be {} or less",
+            fraction.len(),
+            MAX_PRECISION
+        )));
+    }
+    let amount = whole
+        .bytes()
+        .chain(fraction.bytes())
+        .try_fold(0_i128, |amount, c| {
+            amount
+                .checked_mul(10)?
+                .checked_add(i128::from(c - b'0'))
+                .filter(|amount| *amount <= MAX_AMOUNT)
+        })
+        .ok_or_else(|| {
+            error(format!(
+                "amount is too large, must be {} or less",
+                MAX_AMOUNT
+            ))
+        })?;
+    #[allow(clippy::cast_possible_truncation)]
+    let amount = if negative { -amount } else { amount } as i64;
+    #[allow(clippy::cast_possible_truncation)]
+    let precision = fraction.len() as u8;
+    let symbol = symbol_from_bytes(precision, code.bytes())
+        .map_err(|e| error(e.to_string()))?;
+    Ok((amount, symbol))
+}
+
+fn asset_tokens(amount: i64, symbol: u64) -> TokenStream {
+    let amount = Literal::i64_suffixed(amount);
+    let symbol = Literal::u64_suffixed(symbol);
+    quote! {
+        eosio::Asset {
+            amount: #amount,
+            symbol: eosio::Symbol::new(#symbol),
+        }
+    }
+}
+
+pub struct EosioAsset {
+    amount: i64,
+    symbol: u64,
+}
+
+impl Parse for EosioAsset {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lit = input.parse::<LitStr>()?;
+        let (amount, symbol) = parse_asset(&lit, &lit.value())?;
+        Ok(Self { amount, symbol })
+    }
+}
+
+impl ToTokens for EosioAsset {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        asset_tokens(self.amount, self.symbol).to_tokens(tokens)
+    }
+}
+
+pub struct EosioExtendedAsset {
+    amount: i64,
+    symbol: u64,
+    contract: u64,
+}
+
+impl Parse for EosioExtendedAsset {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lit = input.parse::<LitStr>()?;
+        let value = lit.value();
+        let index = value.rfind('@').ok_or_else(|| {
+            ParseError::new_spanned(
+                &lit,
+                "expected an extended asset like \"1.0000 EOS@eosio.token\"",
+            )
+        })?;
+        let (amount, symbol) = parse_asset(&lit, &value[..index])?;
+        let contract = &value[index + 1..];
+        if contract.is_empty() {
+            return Err(ParseError::new_spanned(&lit, "missing contract name"));
+        }
+        let contract = name_from_bytes(contract.bytes())
+            .map_err(|e| ParseError::new_spanned(&lit, e))?;
+        Ok(Self {
+            amount,
+            symbol,
+            contract,
+        })
+    }
+}
+
+impl ToTokens for EosioExtendedAsset {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let quantity = asset_tokens(self.amount, self.symbol);
+        let contract = Literal::u64_suffixed(self.contract);
+        let expanded = quote! {
+            eosio::ExtendedAsset {
+                quantity: #quantity,
+                contract: eosio::AccountName::new(#contract),
+            }
+        };
+        expanded.to_tokens(tokens)
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{
+    action::{
+        action_output, arg_checks, data_fields, required_auth, ricardian,
+        unwrap_output, ActionArgs,
+    },
+    on_notify::OnNotifyArgs,
+};
+use heck::CamelCase;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::Result as ParseResult, Attribute, Error as ParseError, FnArg,
+    ImplItem, ImplItemMethod, ItemImpl, LitStr, Pat, ReturnType, Type,
+};
+
+/// A method marked with `#[action]` or `#[on_notify]`.
+enum ContractMethod {
+    Action {
+        method: ImplItemMethod,
+        name: LitStr,
+        auth: Vec<LitStr>,
+        checks: TokenStream2,
+        ricardian: TokenStream2,
+    },
+    Notify {
+        method: ImplItemMethod,
+        args: OnNotifyArgs,
+        checks: TokenStream2,
+    },
+}
+
+impl ContractMethod {
+    fn method(&self) -> &ImplItemMethod {
+        match self {
+            Self::Action { method, .. } | Self::Notify { method, .. } => method,
+        }
+    }
+
+    fn checks(&self) -> &TokenStream2 {
+        match self {
+            Self::Action { checks, .. } | Self::Notify { checks, .. } => checks,
+        }
+    }
+
+    fn struct_ident(&self) -> Ident {
+        let ident = &self.method().sig.ident;
+        let name = ident.to_string().as_str().to_camel_case();
+        Ident::new(&name, ident.span())
+    }
+
+    /// The arguments after `self` that are read from the action data.
+    fn data_args(&self) -> impl Iterator<Item = &FnArg> {
+        let skip = match self {
+            Self::Action { .. } => 1,
+            // the first receiver comes from `apply`
+            Self::Notify { .. } => 2,
+        };
+        self.method().sig.inputs.iter().skip(skip)
+    }
+}
+
+pub struct Contract {
+    item: ItemImpl,
+    methods: Vec<ContractMethod>,
+}
+
+/// Removes the attribute named `name`, returning it.
+fn take_attr(attrs: &mut Vec<Attribute>, name: &str) -> Option<Attribute> {
+    let index = attrs.iter().position(|attr| attr.path.is_ident(name))?;
+    Some(attrs.remove(index))
+}
+
+impl Contract {
+    pub fn new(mut item: ItemImpl) -> ParseResult<Self> {
+        if item.trait_.is_some() || !item.generics.params.is_empty() {
+            return Err(ParseError::new_spanned(
+                &item.self_ty,
+                "#[eosio::contract] must be used on an inherent impl of a \
+                 type without generics",
+            ));
+        }
+        let mut methods = Vec::new();
+        for impl_item in &mut item.items {
+            let method = match impl_item {
+                ImplItem::Method(method) => method,
+                _ => continue,
+            };
+            if let Some(attr) = take_attr(&mut method.attrs, "action") {
+                let args: ActionArgs = if attr.tokens.is_empty() {
+                    syn::parse2(TokenStream2::new())?
+                } else {
+                    attr.parse_args()?
+                };
+                let name = args.name.clone().unwrap_or_else(|| {
+                    let ident = &method.sig.ident;
+                    LitStr::new(&ident.to_string(), ident.span())
+                });
+                check_receiver(method, 0)?;
+                let checks =
+                    arg_checks(method.sig.inputs.iter_mut(), &args.auth)?;
+                let ricardian = ricardian(&args, &name, &method.sig.inputs)?;
+                methods.push(ContractMethod::Action {
+                    method: method.clone(),
+                    name,
+                    auth: args.auth,
+                    checks,
+                    ricardian,
+                });
+            } else if let Some(attr) = take_attr(&mut method.attrs, "on_notify")
+            {
+                let args: OnNotifyArgs = attr.parse_args()?;
+                check_receiver(method, 1)?;
+                if let ReturnType::Type(_, ty) = &method.sig.output {
+                    return Err(ParseError::new_spanned(
+                        ty,
+                        "notification handlers can't return values",
+                    ));
+                }
+                let checks = arg_checks(method.sig.inputs.iter_mut(), &[])?;
+                methods.push(ContractMethod::Notify {
+                    method: method.clone(),
+                    args,
+                    checks,
+                });
+            }
+        }
+        if methods.is_empty() {
+            return Err(ParseError::new_spanned(
+                &item.self_ty,
+                "contracts must have at least one #[action] or #[on_notify] \
+                 method",
+            ));
+        }
+        Ok(Self { item, methods })
+    }
+}
+
+/// Checks that a method takes `self`, followed by at least `min_args` other
+/// arguments.
+fn check_receiver(method: &ImplItemMethod, min_args: usize) -> ParseResult<()> {
+    let inputs = &method.sig.inputs;
+    if let Some(FnArg::Receiver(_)) = inputs.first() {
+        if inputs.len() > min_args {
+            return Ok(());
+        }
+    }
+    let msg = if min_args == 0 {
+        "actions must take `self`"
+    } else {
+        "notification handlers must take `self`, then the first receiver"
+    };
+    Err(ParseError::new_spanned(&method.sig, msg))
+}
+
+/// Returns the names of the arguments, which are bound by the statements from
+/// `data_fields`.
+fn arg_names<'a, I>(args: I) -> Vec<&'a Pat>
+where
+    I: IntoIterator<Item = &'a FnArg>,
+{
+    args.into_iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(arg) => Some(&*arg.pat),
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+fn expand_method(self_ty: &Type, method: &ContractMethod) -> TokenStream2 {
+    let struct_ident = method.struct_ident();
+    let (struct_fields, assign_args) = data_fields(method.data_args());
+    let args = arg_names(method.data_args());
+    let checks = method.checks();
+    let ident = &method.method().sig.ident;
+    // hygienic names, so they can't clash with the arguments
+    let contract = Ident::new("contract", Span::mixed_site());
+    let first_receiver = Ident::new("first_receiver", Span::mixed_site());
+    let output = Ident::new("output", Span::mixed_site());
+    let new_contract = |first_receiver: TokenStream2| {
+        quote! {
+            #[allow(unused_mut)]
+            let mut #contract = <#self_ty as eosio::Contract>::new(
+                eosio::ContractContext {
+                    receiver: eosio_cdt::current_receiver(),
+                    first_receiver: #first_receiver,
+                    action: Self::NAME,
+                },
+            );
+        }
+    };
+    let trait_impl = match method {
+        ContractMethod::Action {
+            name,
+            auth,
+            ricardian,
+            ..
+        } => {
+            let required_auth = required_auth(auth);
+            let (output_ty, output_abi_type, is_result) =
+                action_output(&method.method().sig.output);
+            let call = quote!(#contract.#ident(#(#args),*));
+            // errors abort before `finalize`
+            let call = if is_result { unwrap_output(call) } else { call };
+            // actions only run when they were sent to the receiver
+            let new_contract =
+                new_contract(quote!(eosio_cdt::current_receiver()));
+            quote! {
+                #[automatically_derived]
+                impl eosio::ActionFn for #struct_ident {
+                    const NAME: eosio::ActionName = eosio::ActionName::new(eosio::n!(#name));
+                    const REQUIRED_AUTH: &'static [&'static str] = #required_auth;
+                    const RICARDIAN_CONTRACT: &'static str = #ricardian;
+                    const OUTPUT_ABI_TYPE: &'static str = #output_abi_type;
+                    type Output = #output_ty;
+                    fn call(self) -> Self::Output {
+                        #assign_args
+                        #checks
+                        #new_contract
+                        let #output = #call;
+                        eosio::Contract::finalize(#contract);
+                        #output
+                    }
+                }
+            }
+        }
+        ContractMethod::Notify { args: notify, .. } => {
+            let (code, action) = notify.consts();
+            let new_contract = new_contract(quote!(#first_receiver));
+            quote! {
+                #[automatically_derived]
+                impl eosio::NotifyFn for #struct_ident {
+                    const CODE: Option<eosio::AccountName> = #code;
+                    const NAME: eosio::ActionName = #action;
+                    fn call(self, #first_receiver: eosio::AccountName) {
+                        #assign_args
+                        #checks
+                        #new_contract
+                        #contract.#ident(#first_receiver, #(#args),*);
+                        eosio::Contract::finalize(#contract);
+                    }
+                }
+            }
+        }
+    };
+    quote! {
+        #[derive(Clone, eosio::Read, eosio::Write, eosio::NumBytes)]
+        #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+        pub struct #struct_ident {
+            #struct_fields
+        }
+
+        #trait_impl
+    }
+}
+
+impl ToTokens for Contract {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let item = &self.item;
+        let methods = self
+            .methods
+            .iter()
+            .map(|method| expand_method(&item.self_ty, method));
+        let entries = self.methods.iter().map(|method| {
+            let struct_ident = method.struct_ident();
+            match method {
+                ContractMethod::Action { .. } => quote!(#struct_ident),
+                ContractMethod::Notify { .. } => quote!(notify #struct_ident),
+            }
+        });
+        let expanded = quote! {
+            #item
+            #(#methods)*
+            eosio::abi!(#(#entries),*);
+        };
+        expanded.to_tokens(tokens);
+    }
+}
@@ -0,0 +1,187 @@
+//! Derive `ContractError`.
+use crate::internal::{
+    get_eosio_meta_items, get_root_path, Attr, CODE, MESSAGE,
+};
+use heck::SnakeCase;
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Error as ParseError, Parse, ParseStream, Result as ParseResult},
+    Data, DeriveInput, Expr, ExprLit, Generics, Ident, Lit, LitStr, Meta,
+    NestedMeta, Path,
+};
+
+/// Codes from here on are reserved for the system.
+const RESERVED_CODES: u64 = 5_000_000_000_000_000_000;
+
+struct ErrorVariant {
+    ident: Ident,
+    code: u64,
+    message: String,
+}
+
+pub struct DeriveContractError {
+    ident: Ident,
+    generics: Generics,
+    variants: Vec<ErrorVariant>,
+    root_path: Path,
+}
+
+impl Parse for DeriveContractError {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let input = input.parse::<DeriveInput>()?;
+        let root_path = get_root_path(&input.attrs);
+        let data = match input.data {
+            Data::Enum(data) => data,
+            _ => {
+                return Err(ParseError::new(
+                    input.ident.span(),
+                    "`ContractError` can only be derived for enums",
+                ))
+            }
+        };
+
+        let mut variants = Vec::new();
+        let mut next_code = 0_u64;
+        for variant in data.variants {
+            let mut code: Attr<u64> = Attr::none(CODE);
+            let mut message: Attr<String> = Attr::none(MESSAGE);
+            match &variant.discriminant {
+                Some((_, Expr::Lit(ExprLit { lit, .. }))) => {
+                    code.set(lit, int_value(lit)?)?;
+                }
+                Some((_, expr)) => {
+                    return Err(ParseError::new_spanned(
+                        expr,
+                        "error codes must be integers",
+                    ))
+                }
+                None => {}
+            }
+            for meta_item in variant
+                .attrs
+                .iter()
+                .flat_map(get_eosio_meta_items)
+                .flatten()
+            {
+                match meta_item {
+                    // Parse `#[eosio(code = 10)]`
+                    NestedMeta::Meta(Meta::NameValue(m)) if m.path == CODE => {
+                        code.set(&m.lit, int_value(&m.lit)?)?;
+                    }
+                    // Parse `#[eosio(message = "...")]`
+                    NestedMeta::Meta(Meta::NameValue(m))
+                        if m.path == MESSAGE =>
+                    {
+                        if let Lit::Str(lit) = &m.lit {
+                            message.set(lit, lit.value())?;
+                        } else {
+                            return Err(ParseError::new_spanned(
+                                m,
+                                "`#[eosio(message = \"...\")]` must use a \
+                                 string literal",
+                            ));
+                        }
+                    }
+                    // Error
+                    meta_item => {
+                        return Err(ParseError::new_spanned(
+                            meta_item,
+                            "expected `code = N` or `message = \"...\"`",
+                        ));
+                    }
+                }
+            }
+
+            let code = code.get().unwrap_or(next_code);
+            if code >= RESERVED_CODES {
+                return Err(ParseError::new(
+                    variant.ident.span(),
+                    format!(
+                        "error codes from {} are reserved for the system",
+                        RESERVED_CODES
+                    ),
+                ));
+            }
+            if let Some(other) = variants
+                .iter()
+                .find(|other: &&ErrorVariant| other.code == code)
+            {
+                return Err(ParseError::new(
+                    variant.ident.span(),
+                    format!(
+                        "error code {} is already used by `{}`",
+                        code, other.ident
+                    ),
+                ));
+            }
+            next_code = code + 1;
+            let message = message.get().unwrap_or_else(|| {
+                variant.ident.to_string().to_snake_case().replace('_', " ")
+            });
+            variants.push(ErrorVariant {
+                ident: variant.ident,
+                code,
+                message,
+            });
+        }
+
+        Ok(Self {
+            ident: input.ident,
+            generics: input.generics,
+            variants,
+            root_path,
+        })
+    }
+}
+
+/// Parses the value of an error code.
+fn int_value(lit: &Lit) -> ParseResult<u64> {
+    match lit {
+        Lit::Int(lit) => lit.base10_parse(),
+        _ => Err(ParseError::new_spanned(lit, "error codes must be integers")),
+    }
+}
+
+impl ToTokens for DeriveContractError {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let name = &self.ident;
+        let root = &self.root_path;
+        let (impl_generics, ty_generics, where_clause) =
+            self.generics.split_for_impl();
+        let idents: Vec<_> = self.variants.iter().map(|v| &v.ident).collect();
+        let codes: Vec<_> = self
+            .variants
+            .iter()
+            .map(|v| Literal::u64_suffixed(v.code))
+            .collect();
+        let messages: Vec<_> = self
+            .variants
+            .iter()
+            .map(|v| LitStr::new(&v.message, v.ident.span()))
+            .collect();
+        let expanded = quote! {
+            #[automatically_derived]
+            impl #impl_generics #root::ContractError for #name #ty_generics #where_clause {
+                const ERRORS: &'static [(u64, &'static str)] = &[
+                    #((#codes, #messages)),*
+                ];
+
+                #[inline]
+                fn error_code(&self) -> u64 {
+                    match self {
+                        #(Self::#idents { .. } => #codes,)*
+                    }
+                }
+
+                #[inline]
+                fn error_message(&self) -> &'static str {
+                    match self {
+                        #(Self::#idents { .. } => #messages,)*
+                    }
+                }
+            }
+        };
+        expanded.to_tokens(tokens);
+    }
+}
@@ -0,0 +1,350 @@
+use crate::internal::{
+    abi_type_name, get_eosio_meta_items, Attr, CRATE_PATH, PRIMARY_KEY,
+    SECONDARY_KEY, TABLE_NAME,
+};
+use heck::SnakeCase;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Error as ParseError, Parse, ParseStream, Result as ParseResult},
+    Data, DeriveInput, Fields, Generics, Ident, Lit, LitStr, Meta, NestedMeta,
+    Path, Type,
+};
+
+pub struct DeriveKvTable {
+    name: LitStr,
+    ident: Ident,
+    generics: Generics,
+    primary_index: KvIndex,
+    secondary_indexes: Vec<KvIndex>,
+    crate_path: Option<Path>,
+}
+
+pub struct KvIndex {
+    /// The name of the index
+    name: LitStr,
+    ident: Ident,
+    ty: Type,
+}
+
+impl KvIndex {
+    fn by_ident(&self) -> Ident {
+        Ident::new(format!("by_{}", self.ident).as_str(), self.ident.span())
+    }
+}
+
+/// Parses `#[eosio(primary_key)]` or `#[eosio(primary_key = "name")]`,
+/// returning the index name if the attribute matches.
+fn index_name(
+    meta: &Meta,
+    word: crate::internal::Symbol,
+    ident: &Ident,
+) -> ParseResult<Option<LitStr>> {
+    match meta {
+        Meta::Path(path) if path == word => {
+            Ok(Some(LitStr::new(&ident.to_string(), ident.span())))
+        }
+        Meta::NameValue(m) if m.path == word => match &m.lit {
+            Lit::Str(lit) => Ok(Some(lit.clone())),
+            _ => Err(ParseError::new_spanned(
+                m,
+                format!(
+                    "`#[eosio({} = \"...\")]` must use a string literal",
+                    word
+                ),
+            )),
+        },
+        _ => Ok(None),
+    }
+}
+
+impl Parse for DeriveKvTable {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let input = input.parse::<DeriveInput>()?;
+
+        // Container attributes
+        let mut table_name: Attr<LitStr> = Attr::none(TABLE_NAME);
+        let mut crate_path: Attr<Path> = Attr::none(CRATE_PATH);
+
+        for meta_item in
+            input.attrs.iter().flat_map(get_eosio_meta_items).flatten()
+        {
+            match meta_item {
+                // Parse `#[eosio(table_name = "test")]`
+                NestedMeta::Meta(Meta::NameValue(m))
+                    if m.path == TABLE_NAME =>
+                {
+                    if let Lit::Str(lit) = m.lit {
+                        table_name.set(lit.clone(), lit)?;
+                    } else {
+                        return Err(ParseError::new_spanned(
+                            m,
+                            "`#[eosio(table_name = \"...\")]` must use a \
+                             string literal",
+                        ));
+                    }
+                }
+                // Parse `#[eosio(crate_path = "crate")]`
+                NestedMeta::Meta(Meta::NameValue(m))
+                    if m.path == CRATE_PATH =>
+                {
+                    let path = match &m.lit {
+                        Lit::Str(string) => {
+                            string.parse_with(Path::parse_mod_style).ok()
+                        }
+                        _ => None,
+                    };
+                    match path {
+                        Some(path) => crate_path.set(m.path, path)?,
+                        None => {
+                            return Err(ParseError::new_spanned(
+                                m.path,
+                                "`#[eosio(crate_path = \"...\")]` received an \
+                                 invalid path",
+                            ))
+                        }
+                    }
+                }
+                // Error
+                NestedMeta::Meta(meta_item) => {
+                    let path = meta_item
+                        .path()
+                        .into_token_stream()
+                        .to_string()
+                        .replace(' ', "");
+                    return Err(ParseError::new_spanned(
+                        meta_item,
+                        format!("unknown eosio container attribute `{}`", path),
+                    ));
+                }
+                // Error
+                NestedMeta::Lit(lit) => {
+                    return Err(ParseError::new_spanned(
+                        lit,
+                        "unexpected literal in eosio container attribute",
+                    ));
+                }
+            }
+        }
+
+        let table_name = match table_name.get() {
+            Some(t) => t,
+            None => {
+                return Err(ParseError::new(
+                    input.ident.span(),
+                    "`#[eosio(table_name = \"...\")]` must be set when \
+                     deriving from `eosio::KvTable`",
+                ))
+            }
+        };
+
+        // Field attributes
+        let mut primary_index: Attr<KvIndex> = Attr::none(PRIMARY_KEY);
+        let mut secondary_indexes: Vec<KvIndex> = Vec::new();
+        let fields = match input.data {
+            Data::Struct(data) => match data.fields {
+                Fields::Named(fields) => fields.named,
+                // Error
+                fields => {
+                    return Err(ParseError::new_spanned(
+                        fields,
+                        "deriving `eosio::KvTable` is only supported for \
+                         structs with named fields",
+                    ));
+                }
+            },
+            // Error
+            _ => {
+                return Err(ParseError::new(
+                    input.ident.span(),
+                    "deriving `eosio::KvTable` is only supported for structs",
+                ));
+            }
+        };
+        for field in fields {
+            let ident = field.ident.clone().unwrap();
+            let mut primary = None;
+            let mut secondary = None;
+            for field_attr in
+                field.attrs.iter().flat_map(get_eosio_meta_items).flatten()
+            {
+                match field_attr {
+                    NestedMeta::Meta(meta) => {
+                        if let Some(name) =
+                            index_name(&meta, PRIMARY_KEY, &ident)?
+                        {
+                            primary = Some(name);
+                        } else if let Some(name) =
+                            index_name(&meta, SECONDARY_KEY, &ident)?
+                        {
+                            secondary = Some(name);
+                        } else {
+                            let path = meta
+                                .path()
+                                .into_token_stream()
+                                .to_string()
+                                .replace(' ', "");
+                            return Err(ParseError::new_spanned(
+                                meta,
+                                format!(
+                                    "unknown eosio field attribute `{}`",
+                                    path
+                                ),
+                            ));
+                        }
+                    }
+                    // Error
+                    NestedMeta::Lit(lit) => {
+                        return Err(ParseError::new_spanned(
+                            lit,
+                            "unexpected literal in eosio field attribute",
+                        ));
+                    }
+                }
+            }
+            match (primary, secondary) {
+                (Some(name), None) => {
+                    let index = KvIndex {
+                        name,
+                        ident,
+                        ty: field.ty.clone(),
+                    };
+                    primary_index.set(field, index)?;
+                }
+                (None, Some(name)) => secondary_indexes.push(KvIndex {
+                    name,
+                    ident,
+                    ty: field.ty,
+                }),
+                (Some(_), Some(_)) => {
+                    return Err(ParseError::new_spanned(
+                        field,
+                        "cannot use both `#[eosio(primary_key)]` and \
+                         `#[eosio(secondary_key)]` on the same field",
+                    ));
+                }
+                (None, None) => (),
+            }
+        }
+
+        let primary_index = match primary_index.get() {
+            Some(index) => index,
+            None => {
+                return Err(ParseError::new(
+                    input.ident.span(),
+                    "`#[eosio(primary_key)]` must be set for a field when \
+                     deriving from `eosio::KvTable`",
+                ))
+            }
+        };
+
+        Ok(Self {
+            name: table_name,
+            ident: input.ident,
+            generics: input.generics,
+            primary_index,
+            secondary_indexes,
+            crate_path: crate_path.get(),
+        })
+    }
+}
+
+impl ToTokens for DeriveKvTable {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let (impl_generics, ty_generics, where_clause) =
+            &self.generics.split_for_impl();
+        let default_path = LitStr::new("::eosio", Span::call_site())
+            .parse_with(Path::parse_mod_style)
+            .unwrap();
+        let eosio = &self.crate_path.as_ref().unwrap_or(&default_path);
+
+        let mut secondary_names = quote!();
+        let mut secondary_abi_types = quote!();
+        let mut secondary_keys = quote!();
+        let mut secondary_constructors = quote!();
+        for index in &self.secondary_indexes {
+            let index_name = &index.name;
+            let ident = &index.ident;
+            let ty = &index.ty;
+            let by_ident = index.by_ident();
+            let abi_type = abi_type_name(ty);
+            secondary_names = quote! {
+                #secondary_names
+                #eosio::Name::new(#eosio::n!(#index_name)),
+            };
+            secondary_abi_types = quote! {
+                #secondary_abi_types
+                #abi_type,
+            };
+            secondary_keys = quote! {
+                #secondary_keys
+                #eosio::KvKey::to_kv_key(&row.#ident),
+            };
+            secondary_constructors = quote! {
+                #secondary_constructors
+
+                #[inline]
+                pub fn #by_ident<C>(contract: C) -> #eosio::KvSecondaryIndex<#ty, Self>
+                where
+                    C: Into<#eosio::AccountName>,
+                {
+                    #eosio::KvSecondaryIndex::new(
+                        contract,
+                        #eosio::Name::new(#eosio::n!(#index_name)),
+                    )
+                }
+            };
+        }
+
+        let table_name = &self.name;
+        let name = &self.ident;
+        let primary_name = &self.primary_index.name;
+        let primary_key = &self.primary_index.ident;
+        let primary_ty = &self.primary_index.ty;
+        let row_abi_type = name.to_string().to_snake_case();
+        let primary_abi_type = abi_type_name(primary_ty);
+
+        let expanded = quote! {
+            #[automatically_derived]
+            impl #impl_generics #eosio::KvTable for #name #ty_generics #where_clause {
+                const NAME: #eosio::TableName = #eosio::TableName::new(#eosio::n!(#table_name));
+
+                const PRIMARY_INDEX: #eosio::Name = #eosio::Name::new(#eosio::n!(#primary_name));
+
+                const SECONDARY_INDEXES: &'static [#eosio::Name] = &[
+                    #secondary_names
+                ];
+
+                const ROW_ABI_TYPE: &'static str = #row_abi_type;
+
+                const PRIMARY_ABI_TYPE: &'static str = #primary_abi_type;
+
+                const SECONDARY_ABI_TYPES: &'static [&'static str] = &[
+                    #secondary_abi_types
+                ];
+
+                type Row = Self;
+
+                type PrimaryKey = #primary_ty;
+
+                #[inline]
+                fn primary_key(row: &Self::Row) -> Self::PrimaryKey {
+                    ::core::clone::Clone::clone(&row.#primary_key)
+                }
+
+                #[inline]
+                fn secondary_keys(row: &Self::Row) -> #eosio::KvKeys {
+                    #eosio::KvKeys::from([
+                        #secondary_keys
+                    ])
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_generics #name #ty_generics #where_clause {
+                #secondary_constructors
+            }
+        };
+        expanded.to_tokens(tokens);
+    }
+}
@@ -1,15 +1,18 @@
 use crate::internal::{
-    get_eosio_meta_items, Attr, BoolAttr, CRATE_PATH, PRIMARY_KEY,
-    SECONDARY_KEY, SINGLETON, TABLE_NAME,
+    get_eosio_meta_items, Attr, BoolAttr, CRATE_PATH, INDEX, NAME, PRIMARY_KEY,
+    SCOPE, SECONDARY_KEY, SINGLETON, TABLE_NAME, TY, WITH,
 };
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{
     parse::{Error as ParseError, Parse, ParseStream, Result as ParseResult},
-    Data, DeriveInput, Fields, Generics, Ident, Lit, LitStr, Meta, NestedMeta,
-    Path, Type,
+    Data, DeriveInput, Fields, Generics, Ident, Lit, LitStr, Meta, MetaList,
+    NestedMeta, Path, Type,
 };
 
+/// Secondary indexes are stored in the low 4 bits of the table name.
+const MAX_SECONDARY_KEYS: usize = 16;
+
 pub enum DeriveTable {
     Table(Table),
     Singleton(Singleton),
@@ -21,6 +24,7 @@ pub struct Table {
     generics: Generics,
     primary_key: PrimaryKey,
     secondary_keys: Vec<SecondaryKey>,
+    scope: Option<Type>,
     crate_path: Option<Path>,
 }
 
@@ -28,6 +32,7 @@ pub struct Singleton {
     name: LitStr,
     ident: Ident,
     generics: Generics,
+    scope: Option<Type>,
     crate_path: Option<Path>,
 }
 
@@ -36,14 +41,99 @@ pub struct PrimaryKey {
 }
 
 pub struct SecondaryKey {
-    ident: Ident,
+    /// The name of the index, used for the `by_{name}` accessor
+    name: Ident,
+    /// Computes the key from `row`
+    key: TokenStream,
     ty: Type,
-    // index: Option<usize>
+    index: Option<usize>,
+    /// The attribute, for errors
+    tokens: TokenStream,
 }
 
 impl SecondaryKey {
     fn by_ident(&self) -> Ident {
-        Ident::new(format!("by_{}", self.ident).as_str(), self.ident.span())
+        Ident::new(format!("by_{}", self.name).as_str(), self.name.span())
+    }
+}
+
+/// Options from `#[eosio(secondary_key(...))]`
+#[derive(Default)]
+struct SecondaryKeyArgs {
+    name: Option<Ident>,
+    with: Option<Path>,
+    ty: Option<Type>,
+    index: Option<usize>,
+}
+
+impl SecondaryKeyArgs {
+    fn parse_list(list: &MetaList) -> ParseResult<Self> {
+        let mut name: Attr<Ident> = Attr::none(NAME);
+        let mut with: Attr<Path> = Attr::none(WITH);
+        let mut ty: Attr<Type> = Attr::none(TY);
+        let mut index: Attr<usize> = Attr::none(INDEX);
+        for nested in &list.nested {
+            match nested {
+                // Parse `name = "byprice"`
+                NestedMeta::Meta(Meta::NameValue(m)) if m.path == NAME => {
+                    name.set(m, parse_lit_str(m.path.clone(), &m.lit)?)?;
+                }
+                // Parse `with = "Self::price_key"`
+                NestedMeta::Meta(Meta::NameValue(m)) if m.path == WITH => {
+                    with.set(m, parse_lit_str(m.path.clone(), &m.lit)?)?;
+                }
+                // Parse `ty = "u128"`
+                NestedMeta::Meta(Meta::NameValue(m)) if m.path == TY => {
+                    ty.set(m, parse_lit_str(m.path.clone(), &m.lit)?)?;
+                }
+                // Parse `index = 0`
+                NestedMeta::Meta(Meta::NameValue(m)) if m.path == INDEX => {
+                    let value = match &m.lit {
+                        Lit::Int(lit) => lit.base10_parse::<usize>()?,
+                        _ => {
+                            return Err(ParseError::new_spanned(
+                                m,
+                                "`index` must be an integer",
+                            ))
+                        }
+                    };
+                    if value >= MAX_SECONDARY_KEYS {
+                        return Err(ParseError::new_spanned(
+                            m,
+                            format!(
+                                "`index` must be less than {}",
+                                MAX_SECONDARY_KEYS
+                            ),
+                        ));
+                    }
+                    index.set(m, value)?;
+                }
+                // Error
+                nested => {
+                    return Err(ParseError::new_spanned(
+                        nested,
+                        "expected `name`, `with`, `ty` or `index`",
+                    ));
+                }
+            }
+        }
+        Ok(Self {
+            name: name.get(),
+            with: with.get(),
+            ty: ty.get(),
+            index: index.get(),
+        })
+    }
+}
+
+/// Parses the contents of a string literal, e.g. a path or type.
+fn parse_lit_str<T: syn::parse::Parse>(
+    path: Path,
+    lit: &Lit,
+) -> ParseResult<T> {
+    match lit {
+        Lit::Str(lit) => lit.parse(),
+        _ => Err(ParseError::new_spanned(path, "expected a string literal")),
     }
 }
 
@@ -55,6 +145,8 @@ impl Parse for DeriveTable {
         let mut table_name: Attr<LitStr> = Attr::none(TABLE_NAME);
         let mut singleton: BoolAttr = BoolAttr::none(SINGLETON);
         let mut crate_path: Attr<Path> = Attr::none(CRATE_PATH);
+        let mut secondary_keys: Vec<SecondaryKey> = Vec::new();
+        let mut scope: Attr<Type> = Attr::none(SCOPE);
 
         // Get container attributes
         for meta_item in
@@ -79,6 +171,35 @@ impl Parse for DeriveTable {
                 NestedMeta::Meta(Meta::Path(word)) if word == SINGLETON => {
                     singleton.set_true(word)?;
                 }
+                // Parse `#[eosio(scope = "AccountName")]`
+                NestedMeta::Meta(Meta::NameValue(m)) if m.path == SCOPE => {
+                    scope.set(&m, parse_lit_str(m.path.clone(), &m.lit)?)?;
+                }
+                // Parse `#[eosio(secondary_key(name = "...", with = "...",
+                // ty = "..."))]`
+                NestedMeta::Meta(Meta::List(list))
+                    if list.path == SECONDARY_KEY =>
+                {
+                    let args = SecondaryKeyArgs::parse_list(&list)?;
+                    let (name, with, ty) = match (args.name, args.with, args.ty)
+                    {
+                        (Some(name), Some(with), Some(ty)) => (name, with, ty),
+                        _ => {
+                            return Err(ParseError::new_spanned(
+                                list,
+                                "computed secondary keys must set `name`, \
+                                 `with` and `ty`",
+                            ))
+                        }
+                    };
+                    secondary_keys.push(SecondaryKey {
+                        name,
+                        key: quote!(#with(row)),
+                        ty,
+                        index: args.index,
+                        tokens: list.into_token_stream(),
+                    });
+                }
                 // Parse `#[eosio(crate_path = "crate")]`
                 NestedMeta::Meta(Meta::NameValue(m))
                     if m.path == CRATE_PATH =>
@@ -142,14 +263,16 @@ impl Parse for DeriveTable {
 
         // Field attributes
         let mut primary_key: Attr<PrimaryKey> = Attr::none(PRIMARY_KEY);
-        let mut secondary_keys: Vec<SecondaryKey> = Vec::new();
         match input.data {
             Data::Struct(data) => match data.fields {
                 // Structs with named fields
                 Fields::Named(fields) => {
                     for field in fields.named.into_iter() {
                         let mut is_primary = false;
-                        let mut is_secondary = false;
+                        let mut secondary: Option<(
+                            TokenStream,
+                            SecondaryKeyArgs,
+                        )> = None;
                         for field_attr in field
                             .attrs
                             .iter()
@@ -167,7 +290,28 @@ impl Parse for DeriveTable {
                                 NestedMeta::Meta(Meta::Path(word))
                                     if word == SECONDARY_KEY =>
                                 {
-                                    is_secondary = true;
+                                    secondary = Some((
+                                        word.into_token_stream(),
+                                        SecondaryKeyArgs::default(),
+                                    ));
+                                }
+                                // Parse `#[eosio(secondary_key(name = "...",
+                                // index = 0))]`
+                                NestedMeta::Meta(Meta::List(list))
+                                    if list.path == SECONDARY_KEY =>
+                                {
+                                    let args =
+                                        SecondaryKeyArgs::parse_list(&list)?;
+                                    if args.with.is_some() || args.ty.is_some()
+                                    {
+                                        return Err(ParseError::new_spanned(
+                                            list,
+                                            "`with` and `ty` can only be used \
+                                             on the struct, for computed keys",
+                                        ));
+                                    }
+                                    secondary =
+                                        Some((list.into_token_stream(), args));
                                 }
                                 // Error
                                 NestedMeta::Meta(meta_item) => {
@@ -196,17 +340,21 @@ impl Parse for DeriveTable {
                             }
                         }
                         let ident = field.ident.clone().unwrap();
-                        match (is_primary, is_secondary) {
-                            (true, false) => {
+                        match (is_primary, secondary) {
+                            (true, None) => {
                                 primary_key.set(field, PrimaryKey { ident })?
                             }
-                            (false, true) => {
-                                secondary_keys.push(SecondaryKey {
-                                    ident,
+                            (false, Some((tokens, args))) => secondary_keys
+                                .push(SecondaryKey {
+                                    name: args
+                                        .name
+                                        .unwrap_or_else(|| ident.clone()),
+                                    key: quote!(row.#ident),
                                     ty: field.ty,
-                                })
-                            }
-                            (true, true) => {
+                                    index: args.index,
+                                    tokens,
+                                }),
+                            (true, Some(_)) => {
                                 return Err(ParseError::new_spanned(
                                     field,
                                     "cannot use both `#[eosio(primary_key)]` \
@@ -214,7 +362,7 @@ impl Parse for DeriveTable {
                                      same field",
                                 ));
                             }
-                            (false, false) => (),
+                            (false, None) => (),
                         }
                     }
                 }
@@ -267,10 +415,13 @@ impl Parse for DeriveTable {
                 ident: input.ident,
                 generics: input.generics,
                 name: table_name,
+                scope: scope.get(),
                 crate_path: None,
             }));
         }
 
+        check_secondary_indexes(&mut secondary_keys)?;
+
         let primary_key = match primary_key {
             Some((_, pk)) => pk,
             None => {
@@ -288,11 +439,90 @@ impl Parse for DeriveTable {
             generics: input.generics,
             primary_key,
             secondary_keys,
+            scope: scope.get(),
             crate_path: None,
         }))
     }
 }
 
+/// Sets the index of each secondary key, making sure that the indexes of
+/// tables with several secondary keys are explicit so that they don't change
+/// when fields are reordered.
+fn check_secondary_indexes(keys: &mut [SecondaryKey]) -> ParseResult<()> {
+    if keys.len() > MAX_SECONDARY_KEYS {
+        return Err(ParseError::new_spanned(
+            &keys[MAX_SECONDARY_KEYS].tokens,
+            format!(
+                "tables can't have more than {} secondary keys",
+                MAX_SECONDARY_KEYS
+            ),
+        ));
+    }
+    if let [key] = keys {
+        key.index.get_or_insert(0);
+    }
+    for (i, key) in keys.iter().enumerate() {
+        let index = match key.index {
+            Some(index) => index,
+            None => {
+                return Err(ParseError::new_spanned(
+                    &key.tokens,
+                    "tables with several secondary keys must set \
+                     `#[eosio(secondary_key(index = N))]` on each, so \
+                     reordering them doesn't change the on-chain indexes",
+                ))
+            }
+        };
+        if keys[..i].iter().any(|other| other.index == Some(index)) {
+            return Err(ParseError::new_spanned(
+                &key.tokens,
+                format!("secondary key index {} is already used", index),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The parts of the generated constructors that depend on the scope type.
+struct ScopeTokens {
+    /// Generic parameters of the constructors
+    params: TokenStream,
+    /// Type of the `scope` argument
+    ty: TokenStream,
+    /// Bounds on the generic parameters
+    bounds: TokenStream,
+    /// Value of `Table::SCOPE_TYPE`, if the scope type is set
+    scope_type: TokenStream,
+    /// Documentation of the scope type
+    doc: String,
+}
+
+impl ScopeTokens {
+    fn new(scope: &Option<Type>, eosio: &Path) -> Self {
+        match scope {
+            Some(ty) => {
+                let name = quote!(#ty).to_string().replace(' ', "");
+                Self {
+                    params: quote!(<C>),
+                    ty: quote!(#ty),
+                    bounds: quote!(),
+                    scope_type: quote! {
+                        const SCOPE_TYPE: Option<&'static str> = Some(#name);
+                    },
+                    doc: format!(", scoped by `{}`", name),
+                }
+            }
+            None => Self {
+                params: quote!(<C, S>),
+                ty: quote!(S),
+                bounds: quote!(S: Into<#eosio::ScopeName>,),
+                scope_type: quote!(),
+                doc: String::new(),
+            },
+        }
+    }
+}
+
 impl ToTokens for Table {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let (impl_generics, ty_generics, where_clause) =
@@ -304,25 +534,41 @@ impl ToTokens for Table {
             .parse_with(Path::parse_mod_style)
             .unwrap();
         let eosio = &self.crate_path.as_ref().unwrap_or(&default_path);
+        let ScopeTokens {
+            params,
+            ty: scope_ty,
+            bounds,
+            scope_type,
+            doc: scope_doc,
+        } = ScopeTokens::new(&self.scope, eosio);
+        let table_name = &self.name;
 
-        for i in 0..16 {
-            match self.secondary_keys.get(i) {
+        for i in 0..MAX_SECONDARY_KEYS {
+            let sk = self.secondary_keys.iter().find(|sk| sk.index == Some(i));
+            match sk {
                 Some(sk) => {
-                    let ident = &sk.ident;
+                    let key = &sk.key;
                     secondary_keys_expanded = quote! {
                         #secondary_keys_expanded
-                        Some(#eosio::SecondaryKey::from(row.#ident)),
+                        Some(#eosio::SecondaryKey::from(#key)),
                     };
                     let ty = &sk.ty;
                     let by_ident = sk.by_ident();
+                    let doc = format!(
+                        "Opens the `{}` index of the `{}` table{}.",
+                        sk.name,
+                        table_name.value(),
+                        scope_doc
+                    );
                     secondary_keys_constructors = quote! {
                         #secondary_keys_constructors
 
+                        #[doc = #doc]
                         #[inline]
-                        pub fn #by_ident<C, S>(code: C, scope: S) -> #eosio::SecondaryTableIndex<#ty, Self>
+                        pub fn #by_ident #params(code: C, scope: #scope_ty) -> #eosio::SecondaryTableIndex<#ty, Self>
                         where
                             C: Into<#eosio::AccountName>,
-                            S: Into<#eosio::ScopeName>,
+                            #bounds
                         {
                             #eosio::SecondaryTableIndex::new(code, scope, Self::NAME, #i)
                         }
@@ -337,14 +583,34 @@ impl ToTokens for Table {
             };
         }
 
-        let table_name = &self.name;
         let name = &self.ident;
         let primary_key = &self.primary_key.ident;
+        // typed scopes shadow `Table::table`
+        let table_constructor = if self.scope.is_some() {
+            let doc = format!(
+                "Opens the `{}` table{}.",
+                table_name.value(),
+                scope_doc
+            );
+            quote! {
+                #[doc = #doc]
+                #[inline]
+                pub fn table<C>(code: C, scope: #scope_ty) -> #eosio::PrimaryTableIndex<Self>
+                where
+                    C: Into<#eosio::AccountName>,
+                {
+                    #eosio::PrimaryTableIndex::new(code, scope)
+                }
+            }
+        } else {
+            quote!()
+        };
 
         let expanded = quote! {
             #[automatically_derived]
             impl #impl_generics #eosio::Table for #name #ty_generics #where_clause {
                 const NAME: #eosio::TableName = #eosio::TableName::new(#eosio::n!(#table_name));
+                #scope_type
 
                 type Row = Self;
 
@@ -355,7 +621,7 @@ impl ToTokens for Table {
 
                 #[inline]
                 fn secondary_keys(row: &Self::Row) -> #eosio::SecondaryKeys {
-                    SecondaryKeys::from([
+                    #eosio::SecondaryKeys::from([
                         #secondary_keys_expanded
                     ])
                 }
@@ -363,6 +629,7 @@ impl ToTokens for Table {
 
             #[automatically_derived]
             impl #impl_generics #name #ty_generics #where_clause {
+                #table_constructor
                 #secondary_keys_constructors
             }
         };
@@ -382,10 +649,23 @@ impl ToTokens for Singleton {
         let eosio = &self.crate_path.as_ref().unwrap_or(&default_path);
         let (impl_generics, ty_generics, where_clause) =
             &self.generics.split_for_impl();
+        let ScopeTokens {
+            params,
+            ty: scope_ty,
+            bounds,
+            scope_type,
+            doc: scope_doc,
+        } = ScopeTokens::new(&self.scope, eosio);
+        let doc = format!(
+            "Opens the `{}` singleton{}.",
+            table_name.value(),
+            scope_doc
+        );
         let expanded = quote! {
             #[automatically_derived]
             impl #impl_generics #eosio::Table for #name #ty_generics #where_clause {
                 const NAME: #eosio::TableName = #eosio::TableName::new(#eosio::n!(#table_name));
+                #scope_type
 
                 type Row = Self;
 
@@ -397,11 +677,12 @@ impl ToTokens for Singleton {
 
             #[automatically_derived]
             impl #impl_generics #name #ty_generics #where_clause {
+                #[doc = #doc]
                 #[inline]
-                pub fn singleton<C, S>(code: C, scope: S) -> ::eosio_cdt::SingletonIndex<Self>
+                pub fn singleton #params(code: C, scope: #scope_ty) -> ::eosio_cdt::SingletonIndex<Self>
                 where
                     C: Into<#eosio::AccountName>,
-                    S: Into<#eosio::ScopeName>,
+                    #bounds
                 {
                     ::eosio_cdt::SingletonIndex::new(code, scope)
                 }
@@ -1,11 +1,12 @@
+use heck::SnakeCase;
 use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
 use std::fmt::{self, Display};
 use syn::{
     parse::{Error as ParseError, Result as ParseResult},
-    Attribute, Ident, Lit, LitStr, Meta,
+    Attribute, GenericArgument, Ident, Lit, LitStr, Meta,
     Meta::List,
-    NestedMeta, Path,
+    NestedMeta, Path, PathArguments, Type,
 };
 
 #[derive(Copy, Clone)]
@@ -16,8 +17,14 @@ pub const TABLE_NAME: Symbol = Symbol("table_name");
 pub const SINGLETON: Symbol = Symbol("singleton");
 pub const PRIMARY_KEY: Symbol = Symbol("primary_key");
 pub const SECONDARY_KEY: Symbol = Symbol("secondary_key");
-// pub const INDEX: Symbol = Symbol("index");
+pub const INDEX: Symbol = Symbol("index");
+pub const NAME: Symbol = Symbol("name");
+pub const WITH: Symbol = Symbol("with");
+pub const TY: Symbol = Symbol("ty");
 pub const CRATE_PATH: Symbol = Symbol("crate_path");
+pub const SCOPE: Symbol = Symbol("scope");
+pub const CODE: Symbol = Symbol("code");
+pub const MESSAGE: Symbol = Symbol("message");
 
 impl PartialEq<Symbol> for Ident {
     fn eq(&self, word: &Symbol) -> bool {
@@ -150,3 +157,66 @@ impl BoolAttr {
         self.0.value.is_some()
     }
 }
+
+/// Returns the ABI name of a type. Built-in types of the `eosio` crate map to
+/// their ABI names, `Vec<T>` and `Option<T>` map to `T[]` and `T?`, `()` is
+/// empty, and other types are named after their identifier in snake case.
+pub fn abi_type_name(ty: &Type) -> String {
+    let segment = match ty {
+        Type::Path(ty) => match ty.path.segments.last() {
+            Some(segment) => segment,
+            None => return String::new(),
+        },
+        Type::Reference(ty) => return abi_type_name(&ty.elem),
+        Type::Paren(ty) => return abi_type_name(&ty.elem),
+        Type::Group(ty) => return abi_type_name(&ty.elem),
+        Type::Tuple(ty) if ty.elems.is_empty() => return String::new(),
+        _ => return ty.to_token_stream().to_string().replace(' ', ""),
+    };
+    let ident = segment.ident.to_string();
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        if let Some(GenericArgument::Type(arg)) = args.args.first() {
+            let arg = abi_type_name(arg);
+            match ident.as_str() {
+                "Vec" if arg == "uint8" => return "bytes".into(),
+                "Vec" => return format!("{}[]", arg),
+                "Option" => return format!("{}?", arg),
+                "BinaryExtension" => return format!("{}$", arg),
+                _ => (),
+            }
+        }
+    }
+    let name = match ident.as_str() {
+        "bool" => "bool",
+        "i8" => "int8",
+        "u8" => "uint8",
+        "i16" => "int16",
+        "u16" => "uint16",
+        "i32" => "int32",
+        "u32" => "uint32",
+        "i64" => "int64",
+        "u64" => "uint64",
+        "i128" => "int128",
+        "u128" => "uint128",
+        "UnsignedInt" => "varuint32",
+        "f32" => "float32",
+        "f64" => "float64",
+        "String" => "string",
+        "Name" | "AccountName" | "ActionName" | "PermissionName"
+        | "ScopeName" | "TableName" => "name",
+        "TimePoint" => "time_point",
+        "TimePointSec" => "time_point_sec",
+        "BlockTimestamp" => "block_timestamp_type",
+        "Checksum160" => "checksum160",
+        "Checksum256" => "checksum256",
+        "Checksum512" => "checksum512",
+        "PublicKey" => "public_key",
+        "Signature" => "signature",
+        "Symbol" => "symbol",
+        "SymbolCode" => "symbol_code",
+        "Asset" => "asset",
+        "ExtendedAsset" => "extended_asset",
+        _ => return ident.to_snake_case(),
+    };
+    name.into()
+}
@@ -16,19 +16,25 @@ extern crate proc_macro;
 
 mod abi;
 mod action;
+mod asset;
+mod contract;
+mod derive_contract_error;
+mod derive_kv_table;
 mod derive_num_bytes;
 mod derive_read;
 mod derive_table;
 mod derive_write;
 mod internal;
 mod n;
+mod on_notify;
+mod ricardian;
 mod s;
 mod table;
 
 use crate::proc_macro::TokenStream;
 use proc_macro_hack::proc_macro_hack;
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput, ItemFn};
+use syn::{parse_macro_input, DeriveInput, ItemFn, ItemImpl};
 
 #[proc_macro]
 pub fn abi(input: TokenStream) -> TokenStream {
@@ -49,13 +55,57 @@ pub fn s(input: TokenStream) -> TokenStream {
     quote!(#item).into()
 }
 
+#[proc_macro_hack]
+pub fn asset(input: TokenStream) -> TokenStream {
+    use crate::asset::EosioAsset;
+    let item = parse_macro_input!(input as EosioAsset);
+    quote!(#item).into()
+}
+
+#[proc_macro_hack]
+pub fn ext_asset(input: TokenStream) -> TokenStream {
+    use crate::asset::EosioExtendedAsset;
+    let item = parse_macro_input!(input as EosioExtendedAsset);
+    quote!(#item).into()
+}
+
 #[proc_macro_attribute]
 pub fn action(args: TokenStream, input: TokenStream) -> TokenStream {
     use crate::action::{ActionArgs, ActionFn};
     let args = parse_macro_input!(args as ActionArgs);
     let item = parse_macro_input!(input as ItemFn);
-    let action = ActionFn::new(args, item);
-    quote!(#action).into()
+    match ActionFn::new(args, item) {
+        Ok(action) => quote!(#action).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_attribute]
+pub fn contract(_args: TokenStream, input: TokenStream) -> TokenStream {
+    use crate::contract::Contract;
+    let item = parse_macro_input!(input as ItemImpl);
+    match Contract::new(item) {
+        Ok(contract) => quote!(#contract).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+pub fn ricardian_clauses(input: TokenStream) -> TokenStream {
+    use crate::ricardian::RicardianClauses;
+    let clauses = parse_macro_input!(input as RicardianClauses);
+    quote!(#clauses).into()
+}
+
+#[proc_macro_attribute]
+pub fn on_notify(args: TokenStream, input: TokenStream) -> TokenStream {
+    use crate::on_notify::{OnNotifyArgs, OnNotifyFn};
+    let args = parse_macro_input!(args as OnNotifyArgs);
+    let item = parse_macro_input!(input as ItemFn);
+    match OnNotifyFn::new(args, item) {
+        Ok(handler) => quote!(#handler).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
 }
 
 #[proc_macro_attribute]
@@ -102,3 +152,21 @@ pub fn derive_table(input: TokenStream) -> TokenStream {
     let item = parse_macro_input!(input as DeriveTable);
     quote!(#item).into()
 }
+
+/// Derive the `KvTable` trait
+#[inline]
+#[proc_macro_derive(KvTable, attributes(eosio))]
+pub fn derive_kv_table(input: TokenStream) -> TokenStream {
+    use crate::derive_kv_table::DeriveKvTable;
+    let item = parse_macro_input!(input as DeriveKvTable);
+    quote!(#item).into()
+}
+
+/// Derive the `ContractError` trait
+#[inline]
+#[proc_macro_derive(ContractError, attributes(eosio))]
+pub fn derive_contract_error(input: TokenStream) -> TokenStream {
+    use crate::derive_contract_error::DeriveContractError;
+    let item = parse_macro_input!(input as DeriveContractError);
+    quote!(#item).into()
+}
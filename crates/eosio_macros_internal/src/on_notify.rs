@@ -0,0 +1,138 @@
+use crate::action::{arg_checks, data_fields};
+use eosio_numstr::name_from_bytes;
+use heck::CamelCase;
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream, Result as ParseResult},
+    Block, Error as ParseError, FnArg, Ident, ItemFn, LitStr, PatType,
+    ReturnType, Signature,
+};
+
+pub struct OnNotifyArgs {
+    /// `None` for `*`, which matches any contract
+    code: Option<u64>,
+    action: u64,
+}
+
+impl Parse for OnNotifyArgs {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let lit = input.parse::<LitStr>()?;
+        let value = lit.value();
+        let mut parts = value.splitn(2, "::");
+        let (code, action) = match (parts.next(), parts.next()) {
+            (Some(code), Some(action))
+                if !code.is_empty() && !action.is_empty() =>
+            {
+                (code, action)
+            }
+            _ => {
+                return Err(ParseError::new_spanned(
+                    &lit,
+                    "expected \"contract::action\" or \"*::action\"",
+                ))
+            }
+        };
+        let name = |s: &str| {
+            name_from_bytes(s.bytes())
+                .map_err(|e| ParseError::new_spanned(&lit, e))
+        };
+        let code = if code == "*" { None } else { Some(name(code)?) };
+        Ok(Self {
+            code,
+            action: name(action)?,
+        })
+    }
+}
+
+impl OnNotifyArgs {
+    /// Returns the values of `NotifyFn::CODE` and `NotifyFn::NAME`.
+    pub fn consts(&self) -> (TokenStream2, TokenStream2) {
+        let code = match self.code {
+            Some(code) => {
+                let code = Literal::u64_suffixed(code);
+                quote!(Some(eosio::AccountName::new(#code)))
+            }
+            None => quote!(None),
+        };
+        let action = Literal::u64_suffixed(self.action);
+        (code, quote!(eosio::ActionName::new(#action)))
+    }
+}
+
+pub struct OnNotifyFn {
+    sig: Signature,
+    block: Box<Block>,
+    args: OnNotifyArgs,
+    first_receiver: PatType,
+    checks: TokenStream2,
+}
+
+impl OnNotifyFn {
+    pub fn new(args: OnNotifyArgs, mut item: ItemFn) -> ParseResult<Self> {
+        let first_receiver =
+            match item.sig.inputs.first() {
+                Some(FnArg::Typed(arg)) => arg.clone(),
+                _ => return Err(ParseError::new_spanned(
+                    &item.sig,
+                    "notification handlers must take the first receiver as \
+                     their first argument",
+                )),
+            };
+        if let ReturnType::Type(_, ty) = &item.sig.output {
+            return Err(ParseError::new_spanned(
+                ty,
+                "notification handlers can't return values",
+            ));
+        }
+        let checks = arg_checks(item.sig.inputs.iter_mut().skip(1), &[])?;
+        Ok(Self {
+            sig: item.sig,
+            block: item.block,
+            args,
+            first_receiver,
+            checks,
+        })
+    }
+
+    pub fn struct_ident(&self) -> Ident {
+        let name = self.sig.ident.to_string().as_str().to_camel_case();
+        Ident::new(&name, self.sig.ident.span())
+    }
+}
+
+impl ToTokens for OnNotifyFn {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let (struct_fields, assign_args) =
+            data_fields(self.sig.inputs.iter().skip(1));
+        let block = &self.block;
+        let struct_ident = self.struct_ident();
+        let type_ident = &self.sig.ident;
+        let (code, action) = self.args.consts();
+        let first_receiver = &self.first_receiver;
+        let checks = &self.checks;
+
+        let expanded = quote! {
+            #[derive(Clone, eosio::Read, eosio::Write, eosio::NumBytes)]
+            #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+            pub struct #struct_ident {
+                #struct_fields
+            }
+
+            #[allow(non_camel_case_types)]
+            pub type #type_ident = #struct_ident;
+
+            #[automatically_derived]
+            impl eosio::NotifyFn for #struct_ident {
+                const CODE: Option<eosio::AccountName> = #code;
+                const NAME: eosio::ActionName = #action;
+                fn call(self, #first_receiver) {
+                    #assign_args
+                    #checks
+                    #block
+                }
+            }
+        };
+        expanded.to_tokens(tokens);
+    }
+}
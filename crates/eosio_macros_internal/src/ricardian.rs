@@ -0,0 +1,189 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use std::{env, fs, path::Path};
+use syn::{
+    parse::{Parse, ParseStream, Result as ParseResult},
+    punctuated::Punctuated,
+    Error as ParseError, LitStr, Token,
+};
+
+/// Reads a markdown file relative to the root of the crate, returning its
+/// absolute path and contents.
+fn read(lit: &LitStr) -> ParseResult<(String, String)> {
+    let dir = env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+        ParseError::new_spanned(lit, "CARGO_MANIFEST_DIR is not set")
+    })?;
+    let path = Path::new(&dir).join(lit.value());
+    let contents = fs::read_to_string(&path).map_err(|e| {
+        ParseError::new_spanned(
+            lit,
+            format!("couldn't read {}: {}", path.display(), e),
+        )
+    })?;
+    Ok((path.display().to_string(), contents))
+}
+
+/// Returns the `key: value` pairs of the front matter, which is between two
+/// `---` lines after the optional `<h1>` title.
+fn front_matter(contents: &str) -> Option<Vec<(&str, &str)>> {
+    let mut lines = contents
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty() || line.starts_with("<h1"));
+    if lines.next()? != "---" {
+        return None;
+    }
+    let mut fields = Vec::new();
+    for line in lines {
+        if line == "---" {
+            return Some(fields);
+        }
+        if let Some(index) = line.find(':') {
+            fields.push((line[..index].trim(), line[index + 1..].trim()));
+        }
+    }
+    None
+}
+
+/// Returns the name in `<h1 class="contract">name</h1>`, if there is one.
+fn title(contents: &str) -> Option<&str> {
+    const OPEN: &str = "<h1 class=\"contract\">";
+    let start = contents.find(OPEN)? + OPEN.len();
+    let end = contents[start..].find("</h1>")?;
+    Some(contents[start..start + end].trim())
+}
+
+/// Returns the variables used by `{{...}}` expressions. Helpers, literals
+/// and builtins such as `$action` are skipped, and only the first segment of
+/// paths like `quantity.amount` is returned.
+fn variables(contents: &str) -> Vec<&str> {
+    let mut vars = Vec::new();
+    let mut rest = contents;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let end = match rest.find("}}") {
+            Some(end) => end,
+            None => break,
+        };
+        let expr = rest[..end].trim_matches(|c| c == '{' || c == '~').trim();
+        rest = &rest[end + 2..];
+        if expr.starts_with(['/', '!', '>']) {
+            continue;
+        }
+        let is_block = expr.starts_with('#');
+        let tokens: Vec<&str> = expr.split_whitespace().collect();
+        let skip = if is_block || tokens.len() > 1 { 1 } else { 0 };
+        for token in tokens.into_iter().skip(skip) {
+            let builtin = token == "else"
+                || token == "this"
+                || token.contains('=')
+                || token.starts_with(|c: char| {
+                    c == '$'
+                        || c == '@'
+                        || c == '"'
+                        || c == '\''
+                        || c.is_ascii_digit()
+                });
+            if !builtin {
+                vars.extend(token.split('.').next());
+            }
+        }
+    }
+    vars
+}
+
+/// Checks the Ricardian contract of an action and returns an expression that
+/// includes it.
+pub fn ricardian_contract(
+    lit: &LitStr,
+    action: &str,
+    args: &[String],
+) -> ParseResult<TokenStream2> {
+    let (path, contents) = read(lit)?;
+    let fields = front_matter(&contents).ok_or_else(|| {
+        ParseError::new_spanned(
+            lit,
+            "Ricardian contracts must start with front matter between `---` \
+             lines",
+        )
+    })?;
+    for &key in &["spec_version", "title", "summary"] {
+        let value = fields.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+        if value.is_none_or(str::is_empty) {
+            return Err(ParseError::new_spanned(
+                lit,
+                format!("missing `{}` in the front matter", key),
+            ));
+        }
+    }
+    if let Some(title) = title(&contents) {
+        if title != action {
+            return Err(ParseError::new_spanned(
+                lit,
+                format!(
+                    "Ricardian contract is for `{}`, not `{}`",
+                    title, action
+                ),
+            ));
+        }
+    }
+    for var in variables(&contents) {
+        if !args.iter().any(|arg| arg == var) {
+            return Err(ParseError::new_spanned(
+                lit,
+                format!(
+                    "unknown variable `{}`, expected one of: {}",
+                    var,
+                    args.join(", ")
+                ),
+            ));
+        }
+    }
+    Ok(quote!(include_str!(#path)))
+}
+
+/// A clause declared with `"id" => "path/to/clause.md"`.
+struct RicardianClause {
+    id: LitStr,
+    path: String,
+}
+
+impl Parse for RicardianClause {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let id = input.parse::<LitStr>()?;
+        input.parse::<Token![=>]>()?;
+        let lit = input.parse::<LitStr>()?;
+        let (path, _) = read(&lit)?;
+        Ok(Self { id, path })
+    }
+}
+
+pub struct RicardianClauses(Vec<RicardianClause>);
+
+impl Parse for RicardianClauses {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        let clauses =
+            Punctuated::<RicardianClause, Token![,]>::parse_terminated(input)?;
+        Ok(Self(clauses.into_iter().collect()))
+    }
+}
+
+impl ToTokens for RicardianClauses {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let clauses = self.0.iter().map(|clause| {
+            let id = &clause.id;
+            let path = &clause.path;
+            quote! {
+                eosio::RicardianClause {
+                    id: #id,
+                    body: include_str!(#path),
+                }
+            }
+        });
+        let expanded = quote! {
+            /// The Ricardian clauses of this contract.
+            pub const RICARDIAN_CLAUSES: &[eosio::RicardianClause] = &[#(#clauses),*];
+        };
+        expanded.to_tokens(tokens);
+    }
+}
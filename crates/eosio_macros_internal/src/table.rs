@@ -3,7 +3,7 @@ use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, ParseStream, Result as ParseResult},
     token::Comma,
-    DeriveInput, Error, Ident, LitStr,
+    DeriveInput, Error, Ident, LitStr, Token, Type,
 };
 
 pub struct Table {
@@ -20,28 +20,35 @@ impl Table {
 pub struct TableArgs {
     name: LitStr,
     is_singleton: bool,
+    /// The type of the scopes, from `scope = Type`
+    scope: Option<Type>,
 }
 
 impl Parse for TableArgs {
     fn parse(input: ParseStream) -> ParseResult<Self> {
         let name = input.parse::<LitStr>()?;
+        let mut is_singleton = false;
+        let mut scope = None;
 
-        if input.parse::<Comma>().is_ok() {
+        while input.parse::<Comma>().is_ok() {
             let ident = input.parse::<Ident>()?;
             if ident == "singleton" {
-                Ok(Self {
-                    name,
-                    is_singleton: true,
-                })
+                is_singleton = true;
+            } else if ident == "scope" {
+                input.parse::<Token![=]>()?;
+                scope = Some(input.parse::<Type>()?);
             } else {
-                Err(Error::new(ident.span(), "expected `singleton`"))
+                return Err(Error::new(
+                    ident.span(),
+                    "expected `singleton` or `scope = Type`",
+                ));
             }
-        } else {
-            Ok(Self {
-                name,
-                is_singleton: false,
-            })
         }
+        Ok(Self {
+            name,
+            is_singleton,
+            scope,
+        })
     }
 }
 
@@ -72,6 +79,19 @@ impl ToTokens for Table {
                 #[eosio(table_name = #name)]
             }
         };
+        let expanded = match &self.args.scope {
+            Some(scope) => {
+                let scope = LitStr::new(
+                    &quote!(#scope).to_string(),
+                    self.args.name.span(),
+                );
+                quote! {
+                    #expanded
+                    #[eosio(scope = #scope)]
+                }
+            }
+            None => expanded,
+        };
         let expanded = quote! {
             #expanded
             #input
@@ -44,7 +44,7 @@
     allow(clippy::option_unwrap_used, clippy::result_unwrap_used)
 )]
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 #[macro_use]
 extern crate std;
 
@@ -29,6 +29,9 @@ impl fmt::Display for ParseNameError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ParseNameError {}
+
 /// Attempts to create an EOSIO name from an `Iterator`.
 ///
 /// # Errors
@@ -38,6 +38,17 @@ impl fmt::Display for ParseSymbolError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ParseSymbolError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Precision(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 impl From<ParseSymbolCodeError> for ParseSymbolError {
     fn from(err: ParseSymbolCodeError) -> Self {
         match err {
@@ -32,6 +32,9 @@ impl fmt::Display for ParseSymbolCodeError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for ParseSymbolCodeError {}
+
 /// Attempts to create an EOSIO symbol from an `Iterator`.
 ///
 /// # Errors
@@ -0,0 +1,365 @@
+use crate::{
+    host::{self, Interrupt},
+    Error,
+};
+use eosio::{
+    AccountName, Action, ActionName, PermissionLevel, Read, ReadError,
+    ScopeName, Table, TimePoint, Transaction, Write,
+};
+use eosio_cdt_sys::mock::{self, with_host};
+use std::{collections::BTreeMap, string::String, vec::Vec};
+use wasmi::{Engine, Linker, Module, Store};
+
+/// How deeply inline actions can be nested, the default in nodeos.
+const MAX_INLINE_ACTION_DEPTH: usize = 4;
+
+/// The trace of an action run by one receiver.
+#[derive(Debug, Clone)]
+pub struct ActionTrace {
+    /// The account whose code ran
+    pub receiver: AccountName,
+    /// The action
+    pub action: Action<Vec<u8>>,
+    /// Everything printed by the receiver
+    pub console: String,
+    /// The packed value returned by the receiver
+    pub return_value: Vec<u8>,
+}
+
+impl ActionTrace {
+    /// Returns true if the receiver was notified of an action sent to
+    /// another account.
+    #[inline]
+    #[must_use]
+    pub fn is_notification(&self) -> bool {
+        self.receiver != self.action.account
+    }
+
+    /// Deserializes the action data.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the data could not be deserialized as `T`.
+    #[inline]
+    pub fn data<T: Read>(&self) -> Result<T, ReadError> {
+        T::unpack(&self.action.data)
+    }
+
+    /// Deserializes the value returned by the receiver.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the return value could not be deserialized as
+    /// `T`.
+    #[inline]
+    pub fn return_value<T: Read>(&self) -> Result<T, ReadError> {
+        T::unpack(&self.return_value)
+    }
+}
+
+struct Contract {
+    module: Module,
+    linker: Linker<()>,
+}
+
+/// An in-memory chain that runs compiled contracts.
+///
+/// Chain state is kept in the [`eosio_cdt_sys::mock`] host of the current
+/// thread, so only one `Chain` should be used per thread at a time.
+///
+/// Authorizations given to [`Chain::push_transaction`] are trusted as if
+/// they were signed. Inline actions may use those authorizations, or the
+/// authority of the contract that sends them.
+pub struct Chain {
+    engine: Engine,
+    contracts: BTreeMap<AccountName, Contract>,
+}
+
+impl Default for Chain {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chain {
+    /// Creates a chain with only the `eosio` account.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        mock::reset();
+        let chain = Self {
+            engine: Engine::default(),
+            contracts: BTreeMap::new(),
+        };
+        chain.create_account(AccountName::new(eosio::n!("eosio")));
+        chain
+    }
+
+    /// Creates an account.
+    #[inline]
+    pub fn create_account<A: Into<AccountName>>(&self, account: A) {
+        let account = account.into().as_u64();
+        with_host(|host| host.accounts.insert(account));
+    }
+
+    /// Returns true if the account exists.
+    #[inline]
+    pub fn is_account<A: Into<AccountName>>(&self, account: A) -> bool {
+        let account = account.into().as_u64();
+        with_host(|host| host.accounts.contains(&account))
+    }
+
+    /// Sets whether an account is privileged.
+    #[inline]
+    pub fn set_privileged<A: Into<AccountName>>(
+        &self,
+        account: A,
+        is_priv: bool,
+    ) {
+        let account = account.into().as_u64();
+        with_host(|host| {
+            if is_priv {
+                host.privileged.insert(account);
+            } else {
+                host.privileged.remove(&account);
+            }
+        });
+    }
+
+    /// Sets the current time.
+    #[inline]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn set_current_time(&self, time: TimePoint) {
+        let micros = time.as_micros() as u64;
+        with_host(|host| host.current_time = micros);
+    }
+
+    /// Deploys a compiled contract to an account.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the account does not exist or the contract is
+    /// not a valid WebAssembly module.
+    #[inline]
+    pub fn set_code<A: Into<AccountName>>(
+        &mut self,
+        account: A,
+        wasm: &[u8],
+    ) -> Result<(), Error> {
+        let account = account.into();
+        if !self.is_account(account) {
+            return Err(Error::AccountNotFound(account));
+        }
+        let module = Module::new(&self.engine, wasm)?;
+        let linker = host::linker(&self.engine, &module)?;
+        self.contracts.insert(account, Contract { module, linker });
+        Ok(())
+    }
+
+    /// Runs a single action in its own transaction.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the data could not be serialized, or for the
+    /// same reasons as [`Chain::push_transaction`].
+    #[inline]
+    pub fn push_action<A, N, T>(
+        &mut self,
+        account: A,
+        name: N,
+        authorization: &[PermissionLevel],
+        data: &T,
+    ) -> Result<Vec<ActionTrace>, Error>
+    where
+        A: Into<AccountName>,
+        N: Into<ActionName>,
+        T: Write,
+    {
+        let action = Action {
+            account: account.into(),
+            name: name.into(),
+            authorization: authorization.to_vec(),
+            data: data.pack()?,
+        };
+        self.push_transaction(&[action])
+    }
+
+    /// Runs actions atomically, returning the traces of every action,
+    /// notification and inline action in execution order.
+    ///
+    /// If any action fails, all changes made by the transaction are reverted.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an account does not exist, a contract aborts or
+    /// traps, or an inline action is not authorized.
+    #[inline]
+    pub fn push_transaction(
+        &mut self,
+        actions: &[Action<Vec<u8>>],
+    ) -> Result<Vec<ActionTrace>, Error> {
+        let trx = Transaction {
+            actions: actions.to_vec(),
+            ..Transaction::default()
+        };
+        let packed = trx.pack()?;
+        let packed_actions = actions
+            .iter()
+            .map(Write::pack)
+            .collect::<Result<Vec<_>, _>>()?;
+        let snapshot = with_host(|host| host.clone());
+        with_host(|host| {
+            host.transaction = packed;
+            host.transaction_actions = packed_actions;
+            host.context_free_actions.clear();
+            host.context_free_data.clear();
+        });
+        let mut traces = Vec::new();
+        let result = actions.iter().try_for_each(|action| {
+            self.execute(action, 0, &action.authorization, 0, &mut traces)
+        });
+        match result {
+            Ok(()) => Ok(traces),
+            Err(err) => {
+                with_host(|host| *host = snapshot);
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns the rows of a table, ordered by primary key.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if a row could not be deserialized.
+    #[inline]
+    pub fn rows<T, C, S>(
+        &self,
+        code: C,
+        scope: S,
+    ) -> Result<Vec<T::Row>, ReadError>
+    where
+        T: Table,
+        C: Into<AccountName>,
+        S: Into<ScopeName>,
+    {
+        let table = (
+            code.into().as_u64(),
+            scope.into().as_u64(),
+            T::NAME.as_u64(),
+        );
+        with_host(|host| {
+            host.db
+                .rows(table)
+                .iter()
+                .map(|(_, row)| T::Row::unpack(&row.data))
+                .collect()
+        })
+    }
+
+    /// Runs an action, its notifications, and the inline actions they send.
+    fn execute(
+        &self,
+        action: &Action<Vec<u8>>,
+        sender: u64,
+        trusted: &[PermissionLevel],
+        depth: usize,
+        traces: &mut Vec<ActionTrace>,
+    ) -> Result<(), Error> {
+        if depth > MAX_INLINE_ACTION_DEPTH {
+            return Err(Error::InlineDepthExceeded);
+        }
+        if !self.is_account(action.account) {
+            return Err(Error::AccountNotFound(action.account));
+        }
+        for level in &action.authorization {
+            if !self.is_account(level.actor) {
+                return Err(Error::AccountNotFound(level.actor));
+            }
+        }
+
+        let mut receivers = vec![action.account.as_u64()];
+        let mut inline_actions = Vec::new();
+        let mut i = 0;
+        while let Some(receiver) = receivers.get(i).copied() {
+            i += 1;
+            let (trace, recipients, sent) =
+                self.apply(receiver, action, sender)?;
+            traces.push(trace);
+            for recipient in recipients {
+                if !receivers.contains(&recipient) {
+                    receivers.push(recipient);
+                }
+            }
+            for bytes in sent {
+                inline_actions.push((receiver, Action::unpack(&bytes)?));
+            }
+        }
+
+        for (sender, inline) in inline_actions {
+            for level in &inline.authorization {
+                if level.actor.as_u64() != sender && !trusted.contains(level) {
+                    return Err(Error::MissingAuthority(*level));
+                }
+            }
+            self.execute(&inline, sender, trusted, depth + 1, traces)?;
+        }
+        Ok(())
+    }
+
+    /// Runs the contract of one receiver, returning its trace, the accounts
+    /// it notified and the inline actions it sent.
+    fn apply(
+        &self,
+        receiver: u64,
+        action: &Action<Vec<u8>>,
+        sender: u64,
+    ) -> Result<(ActionTrace, Vec<u64>, Vec<Vec<u8>>), Error> {
+        with_host(|host| {
+            host.receiver = receiver;
+            host.sender = sender;
+            host.action_data = action.data.clone();
+            host.action_return_value.clear();
+            host.authorizations = action
+                .authorization
+                .iter()
+                .map(|p| (p.actor.as_u64(), p.permission.as_u64()))
+                .collect();
+        });
+        if let Some(contract) = self.contracts.get(&AccountName::new(receiver))
+        {
+            let mut store = Store::new(&self.engine, ());
+            let instance = contract
+                .linker
+                .instantiate(&mut store, &contract.module)?
+                .start(&mut store)?;
+            let apply = instance
+                .get_typed_func::<(u64, u64, u64), ()>(&store, "apply")?;
+            let args =
+                (receiver, action.account.as_u64(), action.name.as_u64());
+            if let Err(err) = apply.call(&mut store, args) {
+                match err.downcast_ref::<Interrupt>() {
+                    Some(Interrupt::Exit) => (),
+                    Some(Interrupt::Abort(msg)) => {
+                        return Err(Error::Abort(msg.clone()))
+                    }
+                    None => return Err(err.into()),
+                }
+            }
+        }
+        Ok(with_host(|host| {
+            let trace = ActionTrace {
+                receiver: AccountName::new(receiver),
+                action: action.clone(),
+                console: core::mem::take(&mut host.output),
+                return_value: core::mem::take(&mut host.action_return_value),
+            };
+            (
+                trace,
+                core::mem::take(&mut host.recipients),
+                core::mem::take(&mut host.inline_actions),
+            )
+        }))
+    }
+}
@@ -0,0 +1,79 @@
+use core::fmt;
+use eosio::{AccountName, PermissionLevel, ReadError, WriteError};
+use std::string::String;
+
+/// Errors that can occur while running actions.
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// The account does not exist
+    AccountNotFound(AccountName),
+    /// An inline action was sent with an authorization that was not
+    /// granted to the contract sending it
+    MissingAuthority(PermissionLevel),
+    /// Inline actions were nested too deeply
+    InlineDepthExceeded,
+    /// The action failed with a message, e.g. from `eosio_assert`
+    Abort(String),
+    /// The contract could not be loaded, or trapped
+    Wasm(String),
+    /// An action sent by a contract could not be deserialized
+    Read(ReadError),
+    /// Action data could not be serialized
+    Write(WriteError),
+}
+
+impl fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AccountNotFound(account) => {
+                f.write_str("account does not exist: ")?;
+                fmt::Display::fmt(&**account, f)
+            }
+            Self::MissingAuthority(level) => {
+                f.write_str("missing authority of ")?;
+                fmt::Display::fmt(&*level.actor, f)?;
+                f.write_str("@")?;
+                fmt::Display::fmt(&*level.permission, f)
+            }
+            Self::InlineDepthExceeded => {
+                f.write_str("max inline action depth exceeded")
+            }
+            Self::Abort(msg) | Self::Wasm(msg) => f.write_str(msg),
+            Self::Read(err) => fmt::Display::fmt(err, f),
+            Self::Write(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Read(err) => Some(err),
+            Self::Write(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ReadError> for Error {
+    #[inline]
+    fn from(err: ReadError) -> Self {
+        Self::Read(err)
+    }
+}
+
+impl From<WriteError> for Error {
+    #[inline]
+    fn from(err: WriteError) -> Self {
+        Self::Write(err)
+    }
+}
+
+impl From<wasmi::Error> for Error {
+    #[inline]
+    fn from(err: wasmi::Error) -> Self {
+        Self::Wasm(err.to_string())
+    }
+}
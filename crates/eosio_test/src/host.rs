@@ -0,0 +1,1612 @@
+//! Host functions imported by contracts from the `env` module.
+//!
+//! Each host function copies its arguments out of the contract's linear
+//! memory, calls the matching intrinsic of the in-memory host in
+//! [`eosio_cdt_sys::mock`], and copies the results back.
+use core::fmt;
+use eosio_cdt_sys as sys;
+use eosio_cdt_sys::mock::Exit;
+use std::{
+    convert::TryFrom,
+    panic::{self, AssertUnwindSafe},
+    string::String,
+    vec::Vec,
+};
+use wasmi::{
+    core::HostError, Caller, Engine, Error as WasmError, Extern, Linker,
+    Memory, Module,
+};
+
+type Ctx<'a> = Caller<'a, ()>;
+
+/// The reasons a host function can stop a contract early.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Interrupt {
+    /// The action failed with a message.
+    Abort(String),
+    /// The contract called `eosio_exit`.
+    Exit,
+}
+
+impl fmt::Display for Interrupt {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Abort(msg) => f.write_str(msg),
+            Self::Exit => f.write_str("eosio_exit called"),
+        }
+    }
+}
+
+impl HostError for Interrupt {}
+
+/// Runs an intrinsic of the mock host, turning aborts into traps.
+fn guard<R, F: FnOnce() -> R>(f: F) -> Result<R, WasmError> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        if payload.is::<Exit>() {
+            return WasmError::host(Interrupt::Exit);
+        }
+        let msg = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| (*s).into()))
+            .unwrap_or_default();
+        WasmError::host(Interrupt::Abort(msg))
+    })
+}
+
+fn abort(msg: &str) -> WasmError {
+    WasmError::host(Interrupt::Abort(msg.into()))
+}
+
+fn memory(caller: &Ctx<'_>) -> Result<Memory, WasmError> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| abort("contract does not export memory"))
+}
+
+fn offset(ptr: u32) -> usize {
+    usize::try_from(ptr).unwrap_or(usize::MAX)
+}
+
+fn read(caller: &Ctx<'_>, ptr: u32, len: u32) -> Result<Vec<u8>, WasmError> {
+    let mut buf = vec![0_u8; offset(len)];
+    memory(caller)?
+        .read(caller, offset(ptr), &mut buf)
+        .map_err(|_| abort("access violation"))?;
+    Ok(buf)
+}
+
+fn read_array<const N: usize>(
+    caller: &Ctx<'_>,
+    ptr: u32,
+) -> Result<[u8; N], WasmError> {
+    let mut buf = [0_u8; N];
+    memory(caller)?
+        .read(caller, offset(ptr), &mut buf)
+        .map_err(|_| abort("access violation"))?;
+    Ok(buf)
+}
+
+/// Reads a NUL-terminated string.
+fn read_c_str(caller: &Ctx<'_>, ptr: u32) -> Result<Vec<u8>, WasmError> {
+    let data = memory(caller)?.data(caller);
+    let tail = data
+        .get(offset(ptr)..)
+        .ok_or_else(|| abort("access violation"))?;
+    let len = tail
+        .iter()
+        .position(|b| *b == 0)
+        .ok_or_else(|| abort("access violation"))?;
+    Ok(tail.get(..len).unwrap_or_default().to_vec())
+}
+
+fn write(
+    caller: &mut Ctx<'_>,
+    ptr: u32,
+    bytes: &[u8],
+) -> Result<(), WasmError> {
+    memory(caller)?
+        .write(caller, offset(ptr), bytes)
+        .map_err(|_| abort("access violation"))
+}
+
+/// Writes the first `len` bytes of `buf` returned by a host function.
+fn write_prefix(
+    caller: &mut Ctx<'_>,
+    ptr: u32,
+    buf: &[u8],
+    len: u32,
+) -> Result<(), WasmError> {
+    let len = buf.len().min(offset(len));
+    write(caller, ptr, buf.get(..len).unwrap_or_default())
+}
+
+fn read_u64(caller: &Ctx<'_>, ptr: u32) -> Result<u64, WasmError> {
+    read_array(caller, ptr).map(u64::from_le_bytes)
+}
+
+fn read_u128(caller: &Ctx<'_>, ptr: u32) -> Result<u128, WasmError> {
+    read_array(caller, ptr).map(u128::from_le_bytes)
+}
+
+fn read_words(
+    caller: &Ctx<'_>,
+    ptr: u32,
+    len: u32,
+) -> Result<Vec<u128>, WasmError> {
+    Ok(read(caller, ptr, len.saturating_mul(16))?
+        .chunks(16)
+        .map(|chunk| {
+            let mut word = [0_u8; 16];
+            word.copy_from_slice(chunk);
+            u128::from_le_bytes(word)
+        })
+        .collect())
+}
+
+fn write_words(
+    caller: &mut Ctx<'_>,
+    ptr: u32,
+    words: &[u128],
+) -> Result<(), WasmError> {
+    let bytes: Vec<u8> = words
+        .iter()
+        .flat_map(|w| w.to_le_bytes().to_vec())
+        .collect();
+    write(caller, ptr, &bytes)
+}
+
+fn checksum256(
+    caller: &Ctx<'_>,
+    ptr: u32,
+) -> Result<sys::capi_checksum256, WasmError> {
+    Ok(sys::capi_checksum256 {
+        hash: read_array(caller, ptr)?,
+    })
+}
+
+macro_rules! wrap_values {
+    ($linker:ident, $($name:ident($($arg:ident: $t:ty),*) $(-> $ret:ty)?;)*) => ($(
+        $linker.func_wrap(
+            "env",
+            stringify!($name),
+            |$($arg: $t),*| -> Result<wrap_values!(@ret $($ret)?), WasmError> {
+                guard(|| unsafe { sys::$name($($arg),*) })
+            },
+        )?;
+    )*);
+    (@ret) => (());
+    (@ret $ret:ty) => ($ret);
+}
+
+macro_rules! wrap_bools {
+    ($linker:ident, $($name:ident($($arg:ident: $t:ty),*);)*) => ($(
+        $linker.func_wrap(
+            "env",
+            stringify!($name),
+            |$($arg: $t),*| -> Result<i32, WasmError> {
+                guard(|| unsafe { sys::$name($($arg),*) }).map(i32::from)
+            },
+        )?;
+    )*)
+}
+
+/// Defines a hash function and its `assert_*` counterpart.
+macro_rules! hashes {
+    ($linker:ident, $($hash:ident, $assert:ident: $t:ident;)*) => ($(
+        $linker.func_wrap(
+            "env",
+            stringify!($hash),
+            |mut caller: Ctx<'_>,
+             data_ptr: u32,
+             data_len: u32,
+             hash_ptr: u32|
+             -> Result<(), WasmError> {
+                let data = read(&caller, data_ptr, data_len)?;
+                let mut hash = sys::$t::default();
+                guard(|| unsafe {
+                    sys::$hash(data.as_ptr().cast(), data_len, &mut hash)
+                })?;
+                write(&mut caller, hash_ptr, &hash.hash)
+            },
+        )?;
+        $linker.func_wrap(
+            "env",
+            stringify!($assert),
+            |caller: Ctx<'_>,
+             data_ptr: u32,
+             data_len: u32,
+             hash_ptr: u32|
+             -> Result<(), WasmError> {
+                let data = read(&caller, data_ptr, data_len)?;
+                let mut hash = sys::$t::default();
+                hash.hash = read_array(&caller, hash_ptr)?;
+                guard(|| unsafe {
+                    sys::$assert(data.as_ptr().cast(), data_len, &hash)
+                })
+            },
+        )?;
+    )*)
+}
+
+macro_rules! secondary_index {
+    (
+        $linker:ident, $t:ty,
+        $store:ident, $update:ident, $remove:ident, $next:ident,
+        $previous:ident, $find_primary:ident, $find_secondary:ident,
+        $lowerbound:ident, $upperbound:ident, $end:ident
+    ) => {
+        $linker.func_wrap(
+            "env",
+            stringify!($store),
+            |caller: Ctx<'_>,
+             scope: u64,
+             table: u64,
+             payer: u64,
+             id: u64,
+             ptr: u32|
+             -> Result<i32, WasmError> {
+                let key = <$t>::from_le_bytes(read_array(&caller, ptr)?);
+                guard(|| unsafe { sys::$store(scope, table, payer, id, &key) })
+            },
+        )?;
+        $linker.func_wrap(
+            "env",
+            stringify!($update),
+            |caller: Ctx<'_>,
+             iterator: i32,
+             payer: u64,
+             ptr: u32|
+             -> Result<(), WasmError> {
+                let key = <$t>::from_le_bytes(read_array(&caller, ptr)?);
+                guard(|| unsafe { sys::$update(iterator, payer, &key) })
+            },
+        )?;
+        wrap_values! { $linker, $remove(iterator: i32); }
+        wrap_values! { $linker, $end(code: u64, scope: u64, table: u64) -> i32; }
+        $linker.func_wrap(
+            "env",
+            stringify!($next),
+            |mut caller: Ctx<'_>,
+             iterator: i32,
+             ptr: u32|
+             -> Result<i32, WasmError> {
+                let mut primary = read_u64(&caller, ptr)?;
+                let itr = guard(|| unsafe { sys::$next(iterator, &mut primary) })?;
+                write(&mut caller, ptr, &primary.to_le_bytes())?;
+                Ok(itr)
+            },
+        )?;
+        $linker.func_wrap(
+            "env",
+            stringify!($previous),
+            |mut caller: Ctx<'_>,
+             iterator: i32,
+             ptr: u32|
+             -> Result<i32, WasmError> {
+                let mut primary = read_u64(&caller, ptr)?;
+                let itr =
+                    guard(|| unsafe { sys::$previous(iterator, &mut primary) })?;
+                write(&mut caller, ptr, &primary.to_le_bytes())?;
+                Ok(itr)
+            },
+        )?;
+        $linker.func_wrap(
+            "env",
+            stringify!($find_primary),
+            |mut caller: Ctx<'_>,
+             code: u64,
+             scope: u64,
+             table: u64,
+             ptr: u32,
+             primary: u64|
+             -> Result<i32, WasmError> {
+                let mut key = <$t>::from_le_bytes(read_array(&caller, ptr)?);
+                let itr = guard(|| unsafe {
+                    sys::$find_primary(code, scope, table, &mut key, primary)
+                })?;
+                write(&mut caller, ptr, &key.to_le_bytes())?;
+                Ok(itr)
+            },
+        )?;
+        $linker.func_wrap(
+            "env",
+            stringify!($find_secondary),
+            |mut caller: Ctx<'_>,
+             code: u64,
+             scope: u64,
+             table: u64,
+             ptr: u32,
+             primary_ptr: u32|
+             -> Result<i32, WasmError> {
+                let key = <$t>::from_le_bytes(read_array(&caller, ptr)?);
+                let mut primary = read_u64(&caller, primary_ptr)?;
+                let itr = guard(|| unsafe {
+                    sys::$find_secondary(code, scope, table, &key, &mut primary)
+                })?;
+                write(&mut caller, primary_ptr, &primary.to_le_bytes())?;
+                Ok(itr)
+            },
+        )?;
+        secondary_index!(@bound $linker, $t, $lowerbound);
+        secondary_index!(@bound $linker, $t, $upperbound);
+    };
+    (@bound $linker:ident, $t:ty, $bound:ident) => {
+        $linker.func_wrap(
+            "env",
+            stringify!($bound),
+            |mut caller: Ctx<'_>,
+             code: u64,
+             scope: u64,
+             table: u64,
+             ptr: u32,
+             primary_ptr: u32|
+             -> Result<i32, WasmError> {
+                let mut key = <$t>::from_le_bytes(read_array(&caller, ptr)?);
+                let mut primary = read_u64(&caller, primary_ptr)?;
+                let itr = guard(|| unsafe {
+                    sys::$bound(code, scope, table, &mut key, &mut primary)
+                })?;
+                write(&mut caller, ptr, &key.to_le_bytes())?;
+                write(&mut caller, primary_ptr, &primary.to_le_bytes())?;
+                Ok(itr)
+            },
+        )?;
+    };
+}
+
+macro_rules! idx256_bound {
+    ($linker:ident, $($bound:ident)*) => ($(
+        $linker.func_wrap(
+            "env",
+            stringify!($bound),
+            |mut caller: Ctx<'_>,
+             code: u64,
+             scope: u64,
+             table: u64,
+             ptr: u32,
+             len: u32,
+             primary_ptr: u32|
+             -> Result<i32, WasmError> {
+                let mut key = read_words(&caller, ptr, len)?;
+                let mut primary = read_u64(&caller, primary_ptr)?;
+                let itr = guard(|| unsafe {
+                    sys::$bound(
+                        code,
+                        scope,
+                        table,
+                        key.as_mut_ptr(),
+                        len,
+                        &mut primary,
+                    )
+                })?;
+                write_words(&mut caller, ptr, &key)?;
+                write(&mut caller, primary_ptr, &primary.to_le_bytes())?;
+                Ok(itr)
+            },
+        )?;
+    )*)
+}
+
+/// Creates a linker that provides the host functions imported by `module`.
+///
+/// Imports that aren't supported trap when they are called.
+pub(crate) fn linker(
+    engine: &Engine,
+    module: &Module,
+) -> Result<Linker<()>, WasmError> {
+    let mut linker = Linker::new(engine);
+    define_action(&mut linker)?;
+    define_transaction(&mut linker)?;
+    define_db(&mut linker)?;
+    define_kv(&mut linker)?;
+    define_print(&mut linker)?;
+    define_system(&mut linker)?;
+    define_crypto(&mut linker)?;
+    define_memory(&mut linker)?;
+    for import in module.imports() {
+        if let Some(ty) = import.ty().func() {
+            let name = String::from(import.name());
+            // fails if the import has already been defined
+            let _ = linker.func_new(
+                import.module(),
+                import.name(),
+                ty.clone(),
+                move |_, _, _| {
+                    let mut msg = String::from("unsupported host function: ");
+                    msg.push_str(&name);
+                    Err(abort(&msg))
+                },
+            );
+        }
+    }
+    Ok(linker)
+}
+
+fn define_action(linker: &mut Linker<()>) -> Result<(), WasmError> {
+    wrap_values! {
+        linker,
+        action_data_size() -> u32;
+        require_recipient(name: u64);
+        require_auth(name: u64);
+        require_auth2(name: u64, permission: u64);
+        publication_time() -> u64;
+        current_receiver() -> u64;
+        current_time() -> u64;
+        get_sender() -> u64;
+    }
+    wrap_bools! {
+        linker,
+        has_auth(name: u64);
+        is_account(name: u64);
+    }
+    linker.func_wrap(
+        "env",
+        "read_action_data",
+        |mut caller: Ctx<'_>, ptr: u32, len: u32| -> Result<u32, WasmError> {
+            let mut buf = vec![0_u8; offset(len)];
+            let size = guard(|| unsafe {
+                sys::read_action_data(buf.as_mut_ptr().cast(), len)
+            })?;
+            if len > 0 {
+                write_prefix(&mut caller, ptr, &buf, size)?;
+            }
+            Ok(size)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "send_inline",
+        |caller: Ctx<'_>, ptr: u32, len: u32| -> Result<(), WasmError> {
+            let mut data = read(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::send_inline(data.as_mut_ptr().cast(), data.len())
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "set_action_return_value",
+        |caller: Ctx<'_>, ptr: u32, len: u32| -> Result<(), WasmError> {
+            let mut data = read(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::set_action_return_value(
+                    data.as_mut_ptr().cast(),
+                    data.len(),
+                )
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "send_context_free_inline",
+        |caller: Ctx<'_>, ptr: u32, len: u32| -> Result<(), WasmError> {
+            let mut data = read(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::send_context_free_inline(
+                    data.as_mut_ptr().cast(),
+                    data.len(),
+                )
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "send_deferred",
+        |caller: Ctx<'_>,
+         id_ptr: u32,
+         payer: u64,
+         ptr: u32,
+         len: u32,
+         replace_existing: u32|
+         -> Result<(), WasmError> {
+            let sender_id = read_u128(&caller, id_ptr)?;
+            let data = read(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::send_deferred(
+                    &sender_id,
+                    payer,
+                    data.as_ptr().cast(),
+                    data.len(),
+                    replace_existing,
+                )
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "cancel_deferred",
+        |caller: Ctx<'_>, id_ptr: u32| -> Result<i32, WasmError> {
+            let sender_id = read_u128(&caller, id_ptr)?;
+            guard(|| unsafe { sys::cancel_deferred(&sender_id) })
+        },
+    )?;
+    Ok(())
+}
+
+fn define_transaction(linker: &mut Linker<()>) -> Result<(), WasmError> {
+    wrap_values! {
+        linker,
+        tapos_block_num() -> i32;
+        tapos_block_prefix() -> i32;
+        expiration() -> u32;
+    }
+    linker.func_wrap(
+        "env",
+        "transaction_size",
+        || -> Result<u32, WasmError> {
+            guard(|| unsafe { sys::transaction_size() })
+                .map(|size| u32::try_from(size).unwrap_or(u32::MAX))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "read_transaction",
+        |mut caller: Ctx<'_>, ptr: u32, len: u32| -> Result<u32, WasmError> {
+            let mut buf = vec![0_u8; offset(len)];
+            let size = guard(|| unsafe {
+                sys::read_transaction(buf.as_mut_ptr().cast(), buf.len())
+            })?;
+            let size = u32::try_from(size).unwrap_or(u32::MAX);
+            if len > 0 {
+                write_prefix(&mut caller, ptr, &buf, size)?;
+            }
+            Ok(size)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "check_transaction_authorization",
+        |caller: Ctx<'_>,
+         trx_ptr: u32,
+         trx_len: u32,
+         keys_ptr: u32,
+         keys_len: u32,
+         perms_ptr: u32,
+         perms_len: u32|
+         -> Result<i32, WasmError> {
+            let trx = read(&caller, trx_ptr, trx_len)?;
+            let keys = read(&caller, keys_ptr, keys_len)?;
+            let perms = read(&caller, perms_ptr, perms_len)?;
+            guard(|| unsafe {
+                sys::check_transaction_authorization(
+                    trx.as_ptr().cast(),
+                    trx_len,
+                    keys.as_ptr().cast(),
+                    keys_len,
+                    perms.as_ptr().cast(),
+                    perms_len,
+                )
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "get_action",
+        |mut caller: Ctx<'_>,
+         kind: u32,
+         index: u32,
+         ptr: u32,
+         len: u32|
+         -> Result<i32, WasmError> {
+            let mut buf = vec![0_u8; offset(len)];
+            let size = guard(|| unsafe {
+                sys::get_action(kind, index, buf.as_mut_ptr().cast(), buf.len())
+            })?;
+            if let Ok(size) = u32::try_from(size) {
+                if size <= len {
+                    write_prefix(&mut caller, ptr, &buf, size)?;
+                }
+            }
+            Ok(size)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "get_context_free_data",
+        |mut caller: Ctx<'_>,
+         index: u32,
+         ptr: u32,
+         len: u32|
+         -> Result<i32, WasmError> {
+            let mut buf = vec![0_u8; offset(len)];
+            let size = guard(|| unsafe {
+                sys::get_context_free_data(
+                    index,
+                    buf.as_mut_ptr().cast(),
+                    buf.len(),
+                )
+            })?;
+            if let Ok(size) = u32::try_from(size) {
+                if len > 0 {
+                    write_prefix(&mut caller, ptr, &buf, size)?;
+                }
+            }
+            Ok(size)
+        },
+    )?;
+    Ok(())
+}
+
+fn define_db(linker: &mut Linker<()>) -> Result<(), WasmError> {
+    wrap_values! {
+        linker,
+        db_remove_i64(iterator: i32);
+        db_find_i64(code: u64, scope: u64, table: u64, id: u64) -> i32;
+        db_lowerbound_i64(code: u64, scope: u64, table: u64, id: u64) -> i32;
+        db_upperbound_i64(code: u64, scope: u64, table: u64, id: u64) -> i32;
+        db_end_i64(code: u64, scope: u64, table: u64) -> i32;
+    }
+    linker.func_wrap(
+        "env",
+        "db_store_i64",
+        |caller: Ctx<'_>,
+         scope: u64,
+         table: u64,
+         payer: u64,
+         id: u64,
+         ptr: u32,
+         len: u32|
+         -> Result<i32, WasmError> {
+            let data = read(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::db_store_i64(
+                    scope,
+                    table,
+                    payer,
+                    id,
+                    data.as_ptr().cast(),
+                    len,
+                )
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "db_update_i64",
+        |caller: Ctx<'_>,
+         iterator: i32,
+         payer: u64,
+         ptr: u32,
+         len: u32|
+         -> Result<(), WasmError> {
+            let data = read(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::db_update_i64(iterator, payer, data.as_ptr().cast(), len)
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "db_get_i64",
+        |mut caller: Ctx<'_>,
+         iterator: i32,
+         ptr: u32,
+         len: u32|
+         -> Result<i32, WasmError> {
+            let mut buf = vec![0_u8; offset(len)];
+            let size = guard(|| unsafe {
+                sys::db_get_i64(iterator, buf.as_mut_ptr().cast(), len)
+            })?;
+            if len > 0 {
+                let size = u32::try_from(size).unwrap_or_default();
+                write_prefix(&mut caller, ptr, &buf, size)?;
+            }
+            Ok(size)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "db_next_i64",
+        |mut caller: Ctx<'_>,
+         iterator: i32,
+         ptr: u32|
+         -> Result<i32, WasmError> {
+            let mut primary = read_u64(&caller, ptr)?;
+            let itr =
+                guard(|| unsafe { sys::db_next_i64(iterator, &mut primary) })?;
+            write(&mut caller, ptr, &primary.to_le_bytes())?;
+            Ok(itr)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "db_previous_i64",
+        |mut caller: Ctx<'_>,
+         iterator: i32,
+         ptr: u32|
+         -> Result<i32, WasmError> {
+            let mut primary = read_u64(&caller, ptr)?;
+            let itr = guard(|| unsafe {
+                sys::db_previous_i64(iterator, &mut primary)
+            })?;
+            write(&mut caller, ptr, &primary.to_le_bytes())?;
+            Ok(itr)
+        },
+    )?;
+    secondary_index!(
+        linker,
+        u64,
+        db_idx64_store,
+        db_idx64_update,
+        db_idx64_remove,
+        db_idx64_next,
+        db_idx64_previous,
+        db_idx64_find_primary,
+        db_idx64_find_secondary,
+        db_idx64_lowerbound,
+        db_idx64_upperbound,
+        db_idx64_end
+    );
+    secondary_index!(
+        linker,
+        u128,
+        db_idx128_store,
+        db_idx128_update,
+        db_idx128_remove,
+        db_idx128_next,
+        db_idx128_previous,
+        db_idx128_find_primary,
+        db_idx128_find_secondary,
+        db_idx128_lowerbound,
+        db_idx128_upperbound,
+        db_idx128_end
+    );
+    secondary_index!(
+        linker,
+        f64,
+        db_idx_double_store,
+        db_idx_double_update,
+        db_idx_double_remove,
+        db_idx_double_next,
+        db_idx_double_previous,
+        db_idx_double_find_primary,
+        db_idx_double_find_secondary,
+        db_idx_double_lowerbound,
+        db_idx_double_upperbound,
+        db_idx_double_end
+    );
+    secondary_index!(
+        linker,
+        u128,
+        db_idx_long_double_store,
+        db_idx_long_double_update,
+        db_idx_long_double_remove,
+        db_idx_long_double_next,
+        db_idx_long_double_previous,
+        db_idx_long_double_find_primary,
+        db_idx_long_double_find_secondary,
+        db_idx_long_double_lowerbound,
+        db_idx_long_double_upperbound,
+        db_idx_long_double_end
+    );
+    define_idx256(linker)
+}
+
+fn define_idx256(linker: &mut Linker<()>) -> Result<(), WasmError> {
+    wrap_values! {
+        linker,
+        db_idx256_remove(iterator: i32);
+        db_idx256_end(code: u64, scope: u64, table: u64) -> i32;
+    }
+    linker.func_wrap(
+        "env",
+        "db_idx256_store",
+        |caller: Ctx<'_>,
+         scope: u64,
+         table: u64,
+         payer: u64,
+         id: u64,
+         ptr: u32,
+         len: u32|
+         -> Result<i32, WasmError> {
+            let key = read_words(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::db_idx256_store(scope, table, payer, id, key.as_ptr(), len)
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "db_idx256_update",
+        |caller: Ctx<'_>,
+         iterator: i32,
+         payer: u64,
+         ptr: u32,
+         len: u32|
+         -> Result<(), WasmError> {
+            let key = read_words(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::db_idx256_update(iterator, payer, key.as_ptr(), len)
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "db_idx256_next",
+        |mut caller: Ctx<'_>,
+         iterator: i32,
+         ptr: u32|
+         -> Result<i32, WasmError> {
+            let mut primary = read_u64(&caller, ptr)?;
+            let itr = guard(|| unsafe {
+                sys::db_idx256_next(iterator, &mut primary)
+            })?;
+            write(&mut caller, ptr, &primary.to_le_bytes())?;
+            Ok(itr)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "db_idx256_previous",
+        |mut caller: Ctx<'_>,
+         iterator: i32,
+         ptr: u32|
+         -> Result<i32, WasmError> {
+            let mut primary = read_u64(&caller, ptr)?;
+            let itr = guard(|| unsafe {
+                sys::db_idx256_previous(iterator, &mut primary)
+            })?;
+            write(&mut caller, ptr, &primary.to_le_bytes())?;
+            Ok(itr)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "db_idx256_find_primary",
+        |mut caller: Ctx<'_>,
+         code: u64,
+         scope: u64,
+         table: u64,
+         ptr: u32,
+         len: u32,
+         primary: u64|
+         -> Result<i32, WasmError> {
+            let mut key = read_words(&caller, ptr, len)?;
+            let itr = guard(|| unsafe {
+                sys::db_idx256_find_primary(
+                    code,
+                    scope,
+                    table,
+                    key.as_mut_ptr(),
+                    len,
+                    primary,
+                )
+            })?;
+            write_words(&mut caller, ptr, &key)?;
+            Ok(itr)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "db_idx256_find_secondary",
+        |mut caller: Ctx<'_>,
+         code: u64,
+         scope: u64,
+         table: u64,
+         ptr: u32,
+         len: u32,
+         primary_ptr: u32|
+         -> Result<i32, WasmError> {
+            let key = read_words(&caller, ptr, len)?;
+            let mut primary = read_u64(&caller, primary_ptr)?;
+            let itr = guard(|| unsafe {
+                sys::db_idx256_find_secondary(
+                    code,
+                    scope,
+                    table,
+                    key.as_ptr(),
+                    len,
+                    &mut primary,
+                )
+            })?;
+            write(&mut caller, primary_ptr, &primary.to_le_bytes())?;
+            Ok(itr)
+        },
+    )?;
+    idx256_bound! { linker, db_idx256_lowerbound db_idx256_upperbound }
+    Ok(())
+}
+
+fn define_kv(linker: &mut Linker<()>) -> Result<(), WasmError> {
+    wrap_values! {
+        linker,
+        kv_it_destroy(itr: u32);
+        kv_it_status(itr: u32) -> i32;
+        kv_it_compare(itr_a: u32, itr_b: u32) -> i32;
+        kv_it_move_to_end(itr: u32) -> i32;
+    }
+    linker.func_wrap(
+        "env",
+        "kv_erase",
+        |caller: Ctx<'_>,
+         contract: u64,
+         key: u32,
+         key_size: u32|
+         -> Result<i64, WasmError> {
+            let key = read(&caller, key, key_size)?;
+            guard(|| unsafe { sys::kv_erase(contract, key.as_ptr(), key_size) })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "kv_set",
+        |caller: Ctx<'_>,
+         contract: u64,
+         key: u32,
+         key_size: u32,
+         value: u32,
+         value_size: u32,
+         payer: u64|
+         -> Result<i64, WasmError> {
+            let key = read(&caller, key, key_size)?;
+            let value = read(&caller, value, value_size)?;
+            guard(|| unsafe {
+                sys::kv_set(
+                    contract,
+                    key.as_ptr(),
+                    key_size,
+                    value.as_ptr(),
+                    value_size,
+                    payer,
+                )
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "kv_get",
+        |mut caller: Ctx<'_>,
+         contract: u64,
+         key: u32,
+         key_size: u32,
+         size_ptr: u32|
+         -> Result<i32, WasmError> {
+            let key = read(&caller, key, key_size)?;
+            let mut size = 0;
+            let found = guard(|| unsafe {
+                sys::kv_get(contract, key.as_ptr(), key_size, &mut size)
+            })?;
+            write(&mut caller, size_ptr, &size.to_le_bytes())?;
+            Ok(i32::from(found))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "kv_get_data",
+        |mut caller: Ctx<'_>,
+         offset: u32,
+         ptr: u32,
+         len: u32|
+         -> Result<u32, WasmError> {
+            let mut buf = vec![0_u8; self::offset(len)];
+            let size = guard(|| unsafe {
+                sys::kv_get_data(offset, buf.as_mut_ptr(), len)
+            })?;
+            write_prefix(&mut caller, ptr, &buf, size.saturating_sub(offset))?;
+            Ok(size)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "kv_it_create",
+        |caller: Ctx<'_>,
+         contract: u64,
+         prefix: u32,
+         size: u32|
+         -> Result<u32, WasmError> {
+            let prefix = read(&caller, prefix, size)?;
+            guard(|| unsafe {
+                sys::kv_it_create(contract, prefix.as_ptr(), size)
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "kv_it_key_compare",
+        |caller: Ctx<'_>,
+         itr: u32,
+         key: u32,
+         size: u32|
+         -> Result<i32, WasmError> {
+            let key = read(&caller, key, size)?;
+            guard(|| unsafe { sys::kv_it_key_compare(itr, key.as_ptr(), size) })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "kv_it_next",
+        |mut caller: Ctx<'_>,
+         itr: u32,
+         key_size: u32,
+         value_size: u32|
+         -> Result<i32, WasmError> {
+            let found = guard(|| {
+                kv_found(|k, v| unsafe { sys::kv_it_next(itr, k, v) })
+            })?;
+            write_found(&mut caller, found, key_size, value_size)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "kv_it_prev",
+        |mut caller: Ctx<'_>,
+         itr: u32,
+         key_size: u32,
+         value_size: u32|
+         -> Result<i32, WasmError> {
+            let found = guard(|| {
+                kv_found(|k, v| unsafe { sys::kv_it_prev(itr, k, v) })
+            })?;
+            write_found(&mut caller, found, key_size, value_size)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "kv_it_lower_bound",
+        |mut caller: Ctx<'_>,
+         itr: u32,
+         key: u32,
+         size: u32,
+         key_size: u32,
+         value_size: u32|
+         -> Result<i32, WasmError> {
+            let key = read(&caller, key, size)?;
+            let found = guard(|| {
+                kv_found(|k, v| unsafe {
+                    sys::kv_it_lower_bound(itr, key.as_ptr(), size, k, v)
+                })
+            })?;
+            write_found(&mut caller, found, key_size, value_size)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "kv_it_key",
+        |mut caller: Ctx<'_>,
+         itr: u32,
+         offset: u32,
+         dest: u32,
+         size: u32,
+         actual_size: u32|
+         -> Result<i32, WasmError> {
+            kv_copy(
+                &mut caller,
+                offset,
+                dest,
+                size,
+                actual_size,
+                |d, a| unsafe { sys::kv_it_key(itr, offset, d, size, a) },
+            )
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "kv_it_value",
+        |mut caller: Ctx<'_>,
+         itr: u32,
+         offset: u32,
+         dest: u32,
+         size: u32,
+         actual_size: u32|
+         -> Result<i32, WasmError> {
+            kv_copy(
+                &mut caller,
+                offset,
+                dest,
+                size,
+                actual_size,
+                |d, a| unsafe { sys::kv_it_value(itr, offset, d, size, a) },
+            )
+        },
+    )?;
+    Ok(())
+}
+
+/// Calls a `kv_it_*` intrinsic that moves an iterator, returning its status
+/// and the sizes of the new key and value.
+fn kv_found<F: FnOnce(*mut u32, *mut u32) -> i32>(f: F) -> (i32, u32, u32) {
+    let (mut key_size, mut value_size) = (0, 0);
+    let status = f(&mut key_size, &mut value_size);
+    (status, key_size, value_size)
+}
+
+fn write_found(
+    caller: &mut Ctx<'_>,
+    (status, key_size, value_size): (i32, u32, u32),
+    key_ptr: u32,
+    value_ptr: u32,
+) -> Result<i32, WasmError> {
+    write(caller, key_ptr, &key_size.to_le_bytes())?;
+    write(caller, value_ptr, &value_size.to_le_bytes())?;
+    Ok(status)
+}
+
+/// Calls `kv_it_key` or `kv_it_value` and copies the result into memory.
+fn kv_copy<F: FnOnce(*mut u8, *mut u32) -> i32>(
+    caller: &mut Ctx<'_>,
+    offset: u32,
+    dest: u32,
+    size: u32,
+    actual_ptr: u32,
+    f: F,
+) -> Result<i32, WasmError> {
+    let mut buf = vec![0_u8; self::offset(size)];
+    let mut actual = 0;
+    let status = guard(|| f(buf.as_mut_ptr(), &mut actual))?;
+    write_prefix(caller, dest, &buf, actual.saturating_sub(offset))?;
+    write(caller, actual_ptr, &actual.to_le_bytes())?;
+    Ok(status)
+}
+
+fn define_print(linker: &mut Linker<()>) -> Result<(), WasmError> {
+    wrap_values! {
+        linker,
+        printi(value: i64);
+        printui(value: u64);
+        printsf(value: f32);
+        printdf(value: f64);
+        printn(name: u64);
+    }
+    linker.func_wrap(
+        "env",
+        "prints",
+        |caller: Ctx<'_>, ptr: u32| -> Result<(), WasmError> {
+            let s = read_c_str(&caller, ptr)?;
+            let len = u32::try_from(s.len()).unwrap_or_default();
+            guard(|| unsafe { sys::prints_l(s.as_ptr().cast(), len) })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "prints_l",
+        |caller: Ctx<'_>, ptr: u32, len: u32| -> Result<(), WasmError> {
+            let s = read(&caller, ptr, len)?;
+            guard(|| unsafe { sys::prints_l(s.as_ptr().cast(), len) })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "printhex",
+        |caller: Ctx<'_>, ptr: u32, len: u32| -> Result<(), WasmError> {
+            let data = read(&caller, ptr, len)?;
+            guard(|| unsafe { sys::printhex(data.as_ptr().cast(), len) })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "printi128",
+        |caller: Ctx<'_>, ptr: u32| -> Result<(), WasmError> {
+            let value = i128::from_le_bytes(read_array(&caller, ptr)?);
+            guard(|| unsafe { sys::printi128(&value) })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "printui128",
+        |caller: Ctx<'_>, ptr: u32| -> Result<(), WasmError> {
+            let value = read_u128(&caller, ptr)?;
+            guard(|| unsafe { sys::printui128(&value) })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "printqf",
+        |caller: Ctx<'_>, ptr: u32| -> Result<(), WasmError> {
+            let value = read_u128(&caller, ptr)?;
+            guard(|| unsafe { sys::printqf(&value) })
+        },
+    )?;
+    Ok(())
+}
+
+fn define_system(linker: &mut Linker<()>) -> Result<(), WasmError> {
+    wrap_values! {
+        linker,
+        eosio_assert_code(test: u32, code: u64);
+        eosio_exit(code: i32);
+        set_resource_limits(account: u64, ram: i64, net: i64, cpu: i64);
+    }
+    wrap_bools! {
+        linker,
+        is_privileged(account: u64);
+    }
+    linker.func_wrap("env", "abort", || -> Result<(), WasmError> {
+        Err(abort("abort() called"))
+    })?;
+    linker.func_wrap(
+        "env",
+        "eosio_assert",
+        |caller: Ctx<'_>, test: u32, ptr: u32| -> Result<(), WasmError> {
+            if test != 0 {
+                return Ok(());
+            }
+            let msg = read_c_str(&caller, ptr)?;
+            let len = u32::try_from(msg.len()).unwrap_or_default();
+            guard(|| unsafe {
+                sys::eosio_assert_message(0, msg.as_ptr().cast(), len)
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "eosio_assert_message",
+        |caller: Ctx<'_>,
+         test: u32,
+         ptr: u32,
+         len: u32|
+         -> Result<(), WasmError> {
+            if test != 0 {
+                return Ok(());
+            }
+            let msg = read(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::eosio_assert_message(0, msg.as_ptr().cast(), len)
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "set_privileged",
+        |account: u64, is_priv: i32| -> Result<(), WasmError> {
+            guard(|| unsafe { sys::set_privileged(account, is_priv != 0) })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "get_resource_limits",
+        |mut caller: Ctx<'_>,
+         account: u64,
+         ram_ptr: u32,
+         net_ptr: u32,
+         cpu_ptr: u32|
+         -> Result<(), WasmError> {
+            let (mut ram, mut net, mut cpu) = (0, 0, 0);
+            guard(|| unsafe {
+                sys::get_resource_limits(account, &mut ram, &mut net, &mut cpu)
+            })?;
+            write(&mut caller, ram_ptr, &i64::to_le_bytes(ram))?;
+            write(&mut caller, net_ptr, &i64::to_le_bytes(net))?;
+            write(&mut caller, cpu_ptr, &i64::to_le_bytes(cpu))
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "get_active_producers",
+        |mut caller: Ctx<'_>, ptr: u32, len: u32| -> Result<u32, WasmError> {
+            let mut buf = vec![0_u8; offset(len)];
+            let size = guard(|| unsafe {
+                sys::get_active_producers(buf.as_mut_ptr().cast(), len)
+            })?;
+            if len > 0 {
+                write_prefix(&mut caller, ptr, &buf, size)?;
+            }
+            Ok(size)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "set_proposed_producers",
+        |caller: Ctx<'_>, ptr: u32, len: u32| -> Result<i64, WasmError> {
+            let mut data = read(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::set_proposed_producers(data.as_mut_ptr().cast(), len)
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "set_blockchain_parameters_packed",
+        |caller: Ctx<'_>, ptr: u32, len: u32| -> Result<(), WasmError> {
+            let mut data = read(&caller, ptr, len)?;
+            guard(|| unsafe {
+                sys::set_blockchain_parameters_packed(
+                    data.as_mut_ptr().cast(),
+                    len,
+                )
+            })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "get_blockchain_parameters_packed",
+        |mut caller: Ctx<'_>, ptr: u32, len: u32| -> Result<u32, WasmError> {
+            let mut buf = vec![0_u8; offset(len)];
+            let size = guard(|| unsafe {
+                sys::get_blockchain_parameters_packed(
+                    buf.as_mut_ptr().cast(),
+                    len,
+                )
+            })?;
+            if len > 0 {
+                write_prefix(&mut caller, ptr, &buf, size)?;
+            }
+            Ok(size)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "preactivate_feature",
+        |caller: Ctx<'_>, ptr: u32| -> Result<(), WasmError> {
+            let digest = checksum256(&caller, ptr)?;
+            guard(|| unsafe { sys::preactivate_feature(&digest) })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "is_feature_activated",
+        |caller: Ctx<'_>, ptr: u32| -> Result<i32, WasmError> {
+            let digest = checksum256(&caller, ptr)?;
+            guard(|| unsafe { sys::is_feature_activated(&digest) })
+                .map(i32::from)
+        },
+    )?;
+    Ok(())
+}
+
+fn define_crypto(linker: &mut Linker<()>) -> Result<(), WasmError> {
+    hashes! {
+        linker,
+        sha256, assert_sha256: capi_checksum256;
+        sha1, assert_sha1: capi_checksum160;
+        sha512, assert_sha512: capi_checksum512;
+        ripemd160, assert_ripemd160: capi_checksum160;
+    }
+    linker.func_wrap(
+        "env",
+        "sha3",
+        |mut caller: Ctx<'_>,
+         data_ptr: u32,
+         data_len: u32,
+         hash_ptr: u32,
+         hash_len: u32,
+         keccak: i32|
+         -> Result<(), WasmError> {
+            let data = read(&caller, data_ptr, data_len)?;
+            let mut hash = vec![0_u8; offset(hash_len)];
+            guard(|| unsafe {
+                sys::sha3(
+                    data.as_ptr().cast(),
+                    data_len,
+                    hash.as_mut_ptr().cast(),
+                    hash_len,
+                    keccak,
+                )
+            })?;
+            write(&mut caller, hash_ptr, &hash)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "k1_recover",
+        |mut caller: Ctx<'_>,
+         sig_ptr: u32,
+         sig_len: u32,
+         dig_ptr: u32,
+         dig_len: u32,
+         pub_ptr: u32,
+         pub_len: u32|
+         -> Result<i32, WasmError> {
+            let sig = read(&caller, sig_ptr, sig_len)?;
+            let dig = read(&caller, dig_ptr, dig_len)?;
+            let mut key = vec![0_u8; offset(pub_len)];
+            let result = guard(|| unsafe {
+                sys::k1_recover(
+                    sig.as_ptr().cast(),
+                    sig_len,
+                    dig.as_ptr().cast(),
+                    dig_len,
+                    key.as_mut_ptr().cast(),
+                    pub_len,
+                )
+            })?;
+            write(&mut caller, pub_ptr, &key)?;
+            Ok(result)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "alt_bn128_add",
+        |mut caller: Ctx<'_>,
+         op1_ptr: u32,
+         op1_len: u32,
+         op2_ptr: u32,
+         op2_len: u32,
+         result_ptr: u32,
+         result_len: u32|
+         -> Result<i32, WasmError> {
+            let op1 = read(&caller, op1_ptr, op1_len)?;
+            let op2 = read(&caller, op2_ptr, op2_len)?;
+            let mut buf = vec![0_u8; offset(result_len)];
+            let result = guard(|| unsafe {
+                sys::alt_bn128_add(
+                    op1.as_ptr().cast(),
+                    op1_len,
+                    op2.as_ptr().cast(),
+                    op2_len,
+                    buf.as_mut_ptr().cast(),
+                    result_len,
+                )
+            })?;
+            write(&mut caller, result_ptr, &buf)?;
+            Ok(result)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "alt_bn128_mul",
+        |mut caller: Ctx<'_>,
+         g1_ptr: u32,
+         g1_len: u32,
+         scalar_ptr: u32,
+         scalar_len: u32,
+         result_ptr: u32,
+         result_len: u32|
+         -> Result<i32, WasmError> {
+            let g1 = read(&caller, g1_ptr, g1_len)?;
+            let scalar = read(&caller, scalar_ptr, scalar_len)?;
+            let mut buf = vec![0_u8; offset(result_len)];
+            let result = guard(|| unsafe {
+                sys::alt_bn128_mul(
+                    g1.as_ptr().cast(),
+                    g1_len,
+                    scalar.as_ptr().cast(),
+                    scalar_len,
+                    buf.as_mut_ptr().cast(),
+                    result_len,
+                )
+            })?;
+            write(&mut caller, result_ptr, &buf)?;
+            Ok(result)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "alt_bn128_pair",
+        |caller: Ctx<'_>, ptr: u32, len: u32| -> Result<i32, WasmError> {
+            let pairs = read(&caller, ptr, len)?;
+            guard(|| unsafe { sys::alt_bn128_pair(pairs.as_ptr().cast(), len) })
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "mod_exp",
+        |mut caller: Ctx<'_>,
+         base_ptr: u32,
+         base_len: u32,
+         exp_ptr: u32,
+         exp_len: u32,
+         mod_ptr: u32,
+         mod_len: u32,
+         result_ptr: u32,
+         result_len: u32|
+         -> Result<i32, WasmError> {
+            let base = read(&caller, base_ptr, base_len)?;
+            let exp = read(&caller, exp_ptr, exp_len)?;
+            let modulus = read(&caller, mod_ptr, mod_len)?;
+            let mut buf = vec![0_u8; offset(result_len)];
+            let result = guard(|| unsafe {
+                sys::mod_exp(
+                    base.as_ptr().cast(),
+                    base_len,
+                    exp.as_ptr().cast(),
+                    exp_len,
+                    modulus.as_ptr().cast(),
+                    mod_len,
+                    buf.as_mut_ptr().cast(),
+                    result_len,
+                )
+            })?;
+            write(&mut caller, result_ptr, &buf)?;
+            Ok(result)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "blake2_f",
+        |mut caller: Ctx<'_>,
+         rounds: u32,
+         state_ptr: u32,
+         state_len: u32,
+         msg_ptr: u32,
+         msg_len: u32,
+         t0_ptr: u32,
+         t0_len: u32,
+         t1_ptr: u32,
+         t1_len: u32,
+         final_: i32,
+         result_ptr: u32,
+         result_len: u32|
+         -> Result<i32, WasmError> {
+            let state = read(&caller, state_ptr, state_len)?;
+            let msg = read(&caller, msg_ptr, msg_len)?;
+            let t0 = read(&caller, t0_ptr, t0_len)?;
+            let t1 = read(&caller, t1_ptr, t1_len)?;
+            let mut buf = vec![0_u8; offset(result_len)];
+            let result = guard(|| unsafe {
+                sys::blake2_f(
+                    rounds,
+                    state.as_ptr().cast(),
+                    state_len,
+                    msg.as_ptr().cast(),
+                    msg_len,
+                    t0.as_ptr().cast(),
+                    t0_len,
+                    t1.as_ptr().cast(),
+                    t1_len,
+                    final_,
+                    buf.as_mut_ptr().cast(),
+                    result_len,
+                )
+            })?;
+            write(&mut caller, result_ptr, &buf)?;
+            Ok(result)
+        },
+    )?;
+    Ok(())
+}
+
+/// The `memcpy` family, which eosio.cdt contracts import from the host.
+fn define_memory(linker: &mut Linker<()>) -> Result<(), WasmError> {
+    fn copy(
+        caller: &mut Ctx<'_>,
+        dest: u32,
+        src: u32,
+        len: u32,
+    ) -> Result<u32, WasmError> {
+        let data = read(caller, src, len)?;
+        write(caller, dest, &data)?;
+        Ok(dest)
+    }
+
+    linker.func_wrap(
+        "env",
+        "memcpy",
+        |mut caller: Ctx<'_>, dest: u32, src: u32, len: u32| {
+            let overlaps = dest.max(src) - dest.min(src) < len;
+            if overlaps {
+                return Err(abort(
+                    "memcpy can only accept non-aliasing pointers",
+                ));
+            }
+            copy(&mut caller, dest, src, len)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "memmove",
+        |mut caller: Ctx<'_>, dest: u32, src: u32, len: u32| {
+            copy(&mut caller, dest, src, len)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "memset",
+        |mut caller: Ctx<'_>,
+         dest: u32,
+         value: i32,
+         len: u32|
+         -> Result<u32, WasmError> {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let data = vec![value as u8; offset(len)];
+            write(&mut caller, dest, &data)?;
+            Ok(dest)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "memcmp",
+        |caller: Ctx<'_>, a: u32, b: u32, len: u32| -> Result<i32, WasmError> {
+            let a = read(&caller, a, len)?;
+            let b = read(&caller, b, len)?;
+            Ok(match a.cmp(&b) {
+                core::cmp::Ordering::Less => -1,
+                core::cmp::Ordering::Equal => 0,
+                core::cmp::Ordering::Greater => 1,
+            })
+        },
+    )?;
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Runs compiled EOSIO smart contracts in-process, so that they can be
+//! tested end-to-end with `cargo test`.
+//!
+//! Contracts are executed by the [`wasmi`] WebAssembly interpreter. Host
+//! functions are backed by the in-memory host in [`eosio_cdt_sys::mock`],
+//! which keeps accounts, authorizations and tables.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use eosio::{n, AccountName, PermissionLevel, PermissionName};
+//! use eosio_test::Chain;
+//!
+//! let alice = AccountName::new(n!("alice"));
+//! let mut chain = Chain::new();
+//! chain.create_account(n!("hello"));
+//! chain.create_account(alice);
+//! let wasm = std::fs::read("target/wasm32-unknown-unknown/release/hello.wasm")
+//!     .unwrap();
+//! chain.set_code(n!("hello"), &wasm).unwrap();
+//!
+//! let auth = PermissionLevel {
+//!     actor: alice,
+//!     permission: PermissionName::new(n!("active")),
+//! };
+//! let traces = chain.push_action(n!("hello"), n!("hi"), &[auth], &alice).unwrap();
+//! assert_eq!(traces[0].console, "Hello, alice");
+//! ```
+#![deny(
+    clippy::correctness,
+    clippy::indexing_slicing,
+    clippy::unimplemented,
+    clippy::wrong_self_convention
+)]
+#![warn(
+    clippy::complexity,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::style,
+    clippy::perf,
+    clippy::dbg_macro,
+    clippy::else_if_without_else,
+    clippy::float_cmp_const,
+    clippy::mem_forget,
+    clippy::use_debug
+)]
+#![allow(clippy::module_name_repetitions)]
+
+mod chain;
+mod error;
+mod host;
+
+pub use self::{
+    chain::{ActionTrace, Chain},
+    error::Error,
+};
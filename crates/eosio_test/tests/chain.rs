@@ -0,0 +1,418 @@
+use eosio::{
+    n, AccountName, Action, ActionName, NumBytes, PermissionLevel, Read, Table,
+    TableName, Write,
+};
+use eosio_test::{Chain, Error};
+
+#[derive(Read, Write, NumBytes, Debug, PartialEq)]
+struct Count {
+    account: AccountName,
+    count: u64,
+}
+
+impl Table for Count {
+    type Row = Self;
+
+    const NAME: TableName = TableName::new(n!("counter"));
+
+    fn primary_key(row: &Self::Row) -> u64 {
+        row.account.as_u64()
+    }
+}
+
+/// A contract with these actions:
+///
+/// - `inc(account)` requires the authority of `account`, increments its
+///   count, prints it and notifies it.
+/// - `fail()` fails an assertion.
+/// - `forward(action)` sends its data as an inline action.
+/// - `exit()` exits before failing an assertion.
+/// - `hash()` calls an intrinsic the harness does not support.
+fn counter_wasm() -> Vec<u8> {
+    let wat = format!(
+        r#"
+(module
+  (import "env" "read_action_data" (func $read_action_data (param i32 i32) (result i32)))
+  (import "env" "action_data_size" (func $action_data_size (result i32)))
+  (import "env" "require_auth" (func $require_auth (param i64)))
+  (import "env" "require_recipient" (func $require_recipient (param i64)))
+  (import "env" "send_inline" (func $send_inline (param i32 i32)))
+  (import "env" "eosio_assert" (func $eosio_assert (param i32 i32)))
+  (import "env" "eosio_exit" (func $eosio_exit (param i32)))
+  (import "env" "prints" (func $prints (param i32)))
+  (import "env" "printn" (func $printn (param i64)))
+  (import "env" "db_find_i64" (func $db_find_i64 (param i64 i64 i64 i64) (result i32)))
+  (import "env" "db_store_i64" (func $db_store_i64 (param i64 i64 i64 i64 i32 i32) (result i32)))
+  (import "env" "db_get_i64" (func $db_get_i64 (param i32 i32 i32) (result i32)))
+  (import "env" "db_update_i64" (func $db_update_i64 (param i32 i64 i32 i32)))
+  (import "env" "get_permission_last_used" (func $get_permission_last_used (param i64 i64) (result i64)))
+  (memory (export "memory") 1)
+  (data (i32.const 16) "boom\00")
+  (data (i32.const 32) "counted \00")
+  (func (export "apply") (param $receiver i64) (param $code i64) (param $action i64)
+    (local $account i64)
+    (local $itr i32)
+    (drop (call $read_action_data (i32.const 1024) (call $action_data_size)))
+    (if (i64.eq (local.get $action) (i64.const {inc})) (then
+      (local.set $account (i64.load (i32.const 1024)))
+      (call $require_auth (local.get $account))
+      (local.set $itr (call $db_find_i64 (local.get $receiver) (local.get $receiver) (i64.const {table}) (local.get $account)))
+      (if (i32.lt_s (local.get $itr) (i32.const 0))
+        (then
+          (i64.store (i32.const 64) (local.get $account))
+          (i64.store (i32.const 72) (i64.const 1))
+          (drop (call $db_store_i64 (local.get $receiver) (i64.const {table}) (local.get $account) (local.get $account) (i32.const 64) (i32.const 16))))
+        (else
+          (drop (call $db_get_i64 (local.get $itr) (i32.const 64) (i32.const 16)))
+          (i64.store (i32.const 72) (i64.add (i64.load (i32.const 72)) (i64.const 1)))
+          (call $db_update_i64 (local.get $itr) (i64.const 0) (i32.const 64) (i32.const 16))))
+      (call $prints (i32.const 32))
+      (call $printn (local.get $account))
+      (call $require_recipient (local.get $account))))
+    (if (i64.eq (local.get $action) (i64.const {fail})) (then
+      (call $eosio_assert (i32.const 0) (i32.const 16))))
+    (if (i64.eq (local.get $action) (i64.const {forward})) (then
+      (call $send_inline (i32.const 1024) (call $action_data_size))))
+    (if (i64.eq (local.get $action) (i64.const {exit})) (then
+      (call $eosio_exit (i32.const 0))
+      (call $eosio_assert (i32.const 0) (i32.const 16))))
+    (if (i64.eq (local.get $action) (i64.const {lastused})) (then
+      (drop (call $get_permission_last_used (local.get $receiver) (i64.const 0)))))))
+"#,
+        inc = n!("inc") as i64,
+        table = n!("counter") as i64,
+        fail = n!("fail") as i64,
+        forward = n!("forward") as i64,
+        exit = n!("exit") as i64,
+        lastused = n!("lastused") as i64,
+    );
+    wat::parse_str(wat).unwrap()
+}
+
+/// A contract that stores its action data, a `(key, value)` pair of 4-byte
+/// strings, in the key-value database. It then prints the stored value and
+/// the first key.
+fn kv_wasm() -> Vec<u8> {
+    wat::parse_str(
+        r#"
+(module
+  (import "env" "read_action_data" (func $read_action_data (param i32 i32) (result i32)))
+  (import "env" "kv_set" (func $kv_set (param i64 i32 i32 i32 i32 i64) (result i64)))
+  (import "env" "kv_get" (func $kv_get (param i64 i32 i32 i32) (result i32)))
+  (import "env" "kv_get_data" (func $kv_get_data (param i32 i32 i32) (result i32)))
+  (import "env" "kv_it_create" (func $kv_it_create (param i64 i32 i32) (result i32)))
+  (import "env" "kv_it_next" (func $kv_it_next (param i32 i32 i32) (result i32)))
+  (import "env" "kv_it_key" (func $kv_it_key (param i32 i32 i32 i32 i32) (result i32)))
+  (import "env" "prints_l" (func $prints_l (param i32 i32)))
+  (memory (export "memory") 1)
+  (func (export "apply") (param $receiver i64) (param $code i64) (param $action i64)
+    (local $itr i32)
+    (drop (call $read_action_data (i32.const 1024) (i32.const 8)))
+    (drop (call $kv_set (local.get $receiver) (i32.const 1024) (i32.const 4) (i32.const 1028) (i32.const 4) (local.get $receiver)))
+    (drop (call $kv_get (local.get $receiver) (i32.const 1024) (i32.const 4) (i32.const 2000)))
+    (drop (call $kv_get_data (i32.const 0) (i32.const 2048) (i32.const 4)))
+    (call $prints_l (i32.const 2048) (i32.load (i32.const 2000)))
+    (local.set $itr (call $kv_it_create (local.get $receiver) (i32.const 0) (i32.const 0)))
+    (drop (call $kv_it_next (local.get $itr) (i32.const 2000) (i32.const 2004)))
+    (drop (call $kv_it_key (local.get $itr) (i32.const 0) (i32.const 2056) (i32.const 4) (i32.const 2008)))
+    (call $prints_l (i32.const 2056) (i32.load (i32.const 2008)))))
+"#,
+    )
+    .unwrap()
+}
+
+fn echo_wasm() -> Vec<u8> {
+    wat::parse_str(
+        r#"
+(module
+  (import "env" "read_action_data" (func $read_action_data (param i32 i32) (result i32)))
+  (import "env" "set_action_return_value" (func $set_action_return_value (param i32 i32)))
+  (memory (export "memory") 1)
+  (func (export "apply") (param $receiver i64) (param $code i64) (param $action i64)
+    (call $set_action_return_value
+      (i32.const 1024)
+      (call $read_action_data (i32.const 1024) (i32.const 300)))))
+"#,
+    )
+    .unwrap()
+}
+
+/// Returns the second action of the transaction.
+fn second_action_wasm() -> Vec<u8> {
+    wat::parse_str(
+        r#"
+(module
+  (import "env" "get_action" (func $get_action (param i32 i32 i32 i32) (result i32)))
+  (import "env" "set_action_return_value" (func $set_action_return_value (param i32 i32)))
+  (memory (export "memory") 1)
+  (func (export "apply") (param $receiver i64) (param $code i64) (param $action i64)
+    (local $size i32)
+    (local.set $size
+      (call $get_action (i32.const 1) (i32.const 1) (i32.const 0) (i32.const 0)))
+    (drop
+      (call $get_action (i32.const 1) (i32.const 1) (i32.const 1024) (local.get $size)))
+    (call $set_action_return_value (i32.const 1024) (local.get $size))))
+"#,
+    )
+    .unwrap()
+}
+
+fn account(name: u64) -> AccountName {
+    AccountName::new(name)
+}
+
+fn active(actor: u64) -> PermissionLevel {
+    PermissionLevel {
+        actor: account(actor),
+        permission: n!("active").into(),
+    }
+}
+
+fn setup() -> Chain {
+    let mut chain = Chain::new();
+    for name in &[n!("counter"), n!("alice"), n!("bob")] {
+        chain.create_account(account(*name));
+    }
+    chain
+        .set_code(account(n!("counter")), &counter_wasm())
+        .unwrap();
+    chain
+}
+
+#[test]
+fn runs_actions_and_stores_rows() {
+    let mut chain = setup();
+    let alice = account(n!("alice"));
+    for _ in 0..2 {
+        let traces = chain
+            .push_action(
+                account(n!("counter")),
+                ActionName::new(n!("inc")),
+                &[active(n!("alice"))],
+                &alice,
+            )
+            .unwrap();
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0].console, "counted alice");
+        assert!(!traces[0].is_notification());
+        assert_eq!(traces[1].receiver, alice);
+        assert!(traces[1].is_notification());
+        assert_eq!(traces[1].data::<AccountName>().unwrap(), alice);
+    }
+    let rows = chain
+        .rows::<Count, _, _>(account(n!("counter")), n!("counter"))
+        .unwrap();
+    assert_eq!(
+        rows,
+        vec![Count {
+            account: alice,
+            count: 2
+        }]
+    );
+}
+
+#[test]
+fn notifications_run_recipient_code() {
+    let mut chain = setup();
+    let bob = account(n!("bob"));
+    chain.set_code(bob, &counter_wasm()).unwrap();
+    let traces = chain
+        .push_action(
+            account(n!("counter")),
+            ActionName::new(n!("inc")),
+            &[active(n!("bob"))],
+            &bob,
+        )
+        .unwrap();
+    assert_eq!(traces.len(), 2);
+    assert_eq!(traces[1].receiver, bob);
+    assert_eq!(traces[1].console, "counted bob");
+    assert_eq!(chain.rows::<Count, _, _>(bob, n!("bob")).unwrap().len(), 1);
+}
+
+#[test]
+fn failed_transactions_are_reverted() {
+    let mut chain = setup();
+    let alice = account(n!("alice"));
+    let inc = Action {
+        account: account(n!("counter")),
+        name: ActionName::new(n!("inc")),
+        authorization: vec![active(n!("alice"))],
+        data: alice.pack().unwrap(),
+    };
+    let fail = Action {
+        name: ActionName::new(n!("fail")),
+        data: Vec::new(),
+        ..inc.clone()
+    };
+    let err = chain.push_transaction(&[inc, fail]).unwrap_err();
+    match err {
+        Error::Abort(msg) => {
+            assert_eq!(msg, "assertion failure with message: boom")
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+    assert!(chain
+        .rows::<Count, _, _>(account(n!("counter")), n!("counter"))
+        .unwrap()
+        .is_empty());
+
+    let err = chain
+        .push_action(
+            account(n!("counter")),
+            ActionName::new(n!("inc")),
+            &[active(n!("bob"))],
+            &alice,
+        )
+        .unwrap_err();
+    match err {
+        Error::Abort(msg) => assert_eq!(msg, "missing authority of alice"),
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn inline_actions_need_authority() {
+    let mut chain = setup();
+    let alice = account(n!("alice"));
+    let inline = Action {
+        account: account(n!("counter")),
+        name: ActionName::new(n!("inc")),
+        authorization: vec![active(n!("alice"))],
+        data: alice.pack().unwrap(),
+    };
+
+    let traces = chain
+        .push_action(
+            account(n!("counter")),
+            ActionName::new(n!("forward")),
+            &[active(n!("alice"))],
+            &inline,
+        )
+        .unwrap();
+    let names: Vec<_> = traces.iter().map(|t| t.action.name).collect();
+    assert_eq!(
+        names,
+        vec![
+            ActionName::new(n!("forward")),
+            ActionName::new(n!("inc")),
+            ActionName::new(n!("inc")),
+        ]
+    );
+
+    let err = chain
+        .push_action(
+            account(n!("counter")),
+            ActionName::new(n!("forward")),
+            &[active(n!("bob"))],
+            &inline,
+        )
+        .unwrap_err();
+    match err {
+        Error::MissingAuthority(level) => {
+            assert_eq!(level, active(n!("alice")))
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+    let rows = chain
+        .rows::<Count, _, _>(account(n!("counter")), n!("counter"))
+        .unwrap();
+    assert_eq!(rows[0].count, 1);
+}
+
+#[test]
+fn exit_and_unsupported_intrinsics() {
+    let mut chain = setup();
+    chain
+        .push_action(
+            account(n!("counter")),
+            ActionName::new(n!("exit")),
+            &[],
+            &Vec::<u8>::new(),
+        )
+        .unwrap();
+
+    let err = chain
+        .push_action(
+            account(n!("counter")),
+            ActionName::new(n!("lastused")),
+            &[],
+            &Vec::<u8>::new(),
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("get_permission_last_used"),
+        "{}",
+        err
+    );
+
+    let err = chain
+        .push_action(
+            account(n!("nobody")),
+            ActionName::new(n!("inc")),
+            &[],
+            &Vec::<u8>::new(),
+        )
+        .unwrap_err();
+    match err {
+        Error::AccountNotFound(name) => assert_eq!(name, account(n!("nobody"))),
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn kv_intrinsics() {
+    let mut chain = setup();
+    let kv = account(n!("kv"));
+    chain.create_account(kv);
+    chain.set_code(kv, &kv_wasm()).unwrap();
+    let mut console = Vec::new();
+    for (key, value) in &[(b"key2", b"okay"), (b"key1", b"fine")] {
+        let data = (u32::from_le_bytes(**key), u32::from_le_bytes(**value));
+        let traces = chain
+            .push_action(kv, ActionName::new(n!("set")), &[], &data)
+            .unwrap();
+        console.push(traces[0].console.clone());
+    }
+    assert_eq!(console, vec!["okaykey2", "finekey1"]);
+}
+
+#[test]
+fn action_return_values() {
+    let mut chain = setup();
+    let echo = account(n!("echo"));
+    chain.create_account(echo);
+    chain.set_code(echo, &echo_wasm()).unwrap();
+    let traces = chain
+        .push_action(echo, ActionName::new(n!("echo")), &[], &42_u64)
+        .unwrap();
+    assert_eq!(traces[0].return_value::<u64>(), Ok(42));
+    let err = chain
+        .push_action(echo, ActionName::new(n!("echo")), &[], &vec![0_u8; 256])
+        .unwrap_err();
+    assert!(err.to_string().contains("action return value size"));
+}
+
+#[test]
+fn contracts_read_their_transaction() {
+    let mut chain = setup();
+    let intro = account(n!("intro"));
+    chain.create_account(intro);
+    chain.set_code(intro, &second_action_wasm()).unwrap();
+    let actions: Vec<_> = [n!("first"), n!("second")]
+        .iter()
+        .map(|name| Action {
+            account: intro,
+            name: ActionName::new(*name),
+            authorization: vec![active(n!("alice"))],
+            data: vec![1, 2, 3],
+        })
+        .collect();
+    let traces = chain.push_transaction(&actions).unwrap();
+    assert_eq!(traces.len(), 2);
+    for trace in traces {
+        let action = trace.return_value::<Action<Vec<u8>>>().unwrap();
+        assert_eq!(action.name, ActionName::new(n!("second")));
+        assert_eq!(action.data, vec![1, 2, 3]);
+    }
+}
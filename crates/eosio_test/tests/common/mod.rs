@@ -0,0 +1,45 @@
+//! Builds the contracts that the integration tests run.
+use std::{env, fs, path::Path, process::Command};
+
+const TARGET: &str = "wasm32-unknown-unknown";
+
+/// Builds the contract in the `package` workspace member for
+/// `wasm32-unknown-unknown` and returns its code, or `None` if the
+/// workspace or the target are not available, in which case the test
+/// should be skipped.
+pub fn contract_wasm(package: &str) -> Option<Vec<u8>> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    if !root.join("contracts").is_dir() {
+        eprintln!("skipping: the contracts are only in the workspace");
+        return None;
+    }
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let libdir = Command::new(rustc)
+        .args(["--print", "target-libdir", "--target", TARGET])
+        .output()
+        .expect("failed to run rustc");
+    let libdir = String::from_utf8_lossy(&libdir.stdout);
+    if !Path::new(libdir.trim()).is_dir() {
+        eprintln!("skipping: the {} target is not installed", TARGET);
+        return None;
+    }
+
+    // a separate target directory, since `cargo test` holds the lock on the
+    // workspace one
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("contracts");
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = Command::new(cargo)
+        .current_dir(&root)
+        .args(["build", "--release", "--target", TARGET, "-p", package])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success(), "failed to build `{}`", package);
+
+    let wasm = target_dir
+        .join(TARGET)
+        .join("release")
+        .join(format!("{}.wasm", package));
+    Some(fs::read(wasm).unwrap())
+}
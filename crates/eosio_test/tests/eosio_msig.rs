@@ -0,0 +1,158 @@
+//! Runs the `eosio_msig` contract end to end.
+mod common;
+
+use eosio::{
+    n, AccountName, Action, ActionName, Checksum256, Name, NumBytes,
+    PermissionLevel, Read, Table, TableName, TimePoint, TimePointSec,
+    Transaction, TransactionHeader, Write,
+};
+use eosio_cdt_sys::mock::with_host;
+use eosio_test::{Chain, Error};
+
+#[derive(Read, Write, NumBytes, Debug)]
+struct Proposal {
+    proposal_name: Name,
+    packed_transaction: Vec<u8>,
+}
+
+impl Table for Proposal {
+    type Row = Self;
+
+    const NAME: TableName = TableName::new(n!("proposal"));
+
+    fn primary_key(row: &Self::Row) -> u64 {
+        row.proposal_name.as_u64()
+    }
+}
+
+fn active(actor: AccountName) -> PermissionLevel {
+    PermissionLevel {
+        actor,
+        permission: n!("active").into(),
+    }
+}
+
+fn abort_message(err: Error) -> String {
+    match err {
+        Error::Abort(msg) => msg,
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn propose_approve_exec() {
+    let wasm = match common::contract_wasm("eosio_msig") {
+        Some(wasm) => wasm,
+        None => return,
+    };
+
+    let msig = AccountName::new(n!("eosio.msig"));
+    let alice = AccountName::new(n!("alice"));
+    let bob = AccountName::new(n!("bob"));
+    let mut chain = Chain::new();
+    for account in &[msig, alice, bob] {
+        chain.create_account(*account);
+    }
+    chain.set_code(msig, &wasm).unwrap();
+    chain.set_current_time(TimePoint::from_micros(1_000_000));
+
+    let proposal_name = Name::new(n!("payday"));
+    let trx = Transaction {
+        header: TransactionHeader {
+            expiration: TimePointSec::from_secs(60),
+            ..TransactionHeader::default()
+        },
+        actions: vec![Action {
+            account: AccountName::new(n!("eosio.token")),
+            name: ActionName::new(n!("transfer")),
+            authorization: vec![active(bob)],
+            data: Vec::<u8>::new(),
+        }],
+        ..Transaction::default()
+    };
+    let packed_transaction = trx.pack().unwrap();
+
+    let err = chain
+        .push_action(
+            msig,
+            ActionName::new(n!("propose")),
+            &[active(alice)],
+            &(alice, proposal_name, vec![active(alice)], trx.clone()),
+        )
+        .unwrap_err();
+    assert_eq!(
+        abort_message(err),
+        "assertion failure with message: transaction authorization failed"
+    );
+
+    chain
+        .push_action(
+            msig,
+            ActionName::new(n!("propose")),
+            &[active(alice)],
+            &(alice, proposal_name, vec![active(bob)], trx),
+        )
+        .unwrap();
+    let proposals = chain.rows::<Proposal, _, _>(msig, alice).unwrap();
+    assert_eq!(proposals[0].packed_transaction, packed_transaction);
+
+    let err = chain
+        .push_action(
+            msig,
+            ActionName::new(n!("exec")),
+            &[active(alice)],
+            &(alice, proposal_name, alice),
+        )
+        .unwrap_err();
+    assert_eq!(
+        abort_message(err),
+        "assertion failure with message: transaction authorization failed"
+    );
+
+    let err = chain
+        .push_action(
+            msig,
+            ActionName::new(n!("approve")),
+            &[active(bob)],
+            &(
+                alice,
+                proposal_name,
+                active(bob),
+                Checksum256::hash("wrong"),
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(abort_message(err), "hash mismatch");
+
+    chain
+        .push_action(
+            msig,
+            ActionName::new(n!("approve")),
+            &[active(bob)],
+            &(
+                alice,
+                proposal_name,
+                active(bob),
+                Checksum256::hash(&packed_transaction),
+            ),
+        )
+        .unwrap();
+    chain
+        .push_action(
+            msig,
+            ActionName::new(n!("exec")),
+            &[active(alice)],
+            &(alice, proposal_name, alice),
+        )
+        .unwrap();
+    assert!(chain
+        .rows::<Proposal, _, _>(msig, alice)
+        .unwrap()
+        .is_empty());
+
+    let sender_id =
+        (u128::from(alice.as_u64()) << 64) | u128::from(proposal_name.as_u64());
+    let deferred =
+        with_host(|host| host.deferred_transactions.get(&sender_id).cloned());
+    assert_eq!(deferred, Some((alice.as_u64(), packed_transaction)));
+}
@@ -0,0 +1,118 @@
+//! Runs the `eosio_token` contract end to end.
+mod common;
+
+use eosio::{
+    n, AccountName, ActionName, Asset, NumBytes, PermissionLevel, Read, Table,
+    TableName, Write,
+};
+use eosio_test::{Chain, Error};
+
+#[derive(Read, Write, NumBytes, Debug)]
+struct Account {
+    balance: Asset,
+}
+
+impl Table for Account {
+    type Row = Self;
+
+    const NAME: TableName = TableName::new(n!("accounts"));
+
+    fn primary_key(row: &Self::Row) -> u64 {
+        row.balance.symbol.code().as_u64()
+    }
+}
+
+fn active(actor: AccountName) -> PermissionLevel {
+    PermissionLevel {
+        actor,
+        permission: n!("active").into(),
+    }
+}
+
+fn balance(chain: &Chain, owner: AccountName) -> Asset {
+    chain
+        .rows::<Account, _, _>(AccountName::new(n!("eosio.token")), owner)
+        .unwrap()[0]
+        .balance
+}
+
+#[test]
+fn create_issue_transfer() {
+    let wasm = match common::contract_wasm("eosio_token") {
+        Some(wasm) => wasm,
+        None => return,
+    };
+
+    let token = AccountName::new(n!("eosio.token"));
+    let alice = AccountName::new(n!("alice"));
+    let bob = AccountName::new(n!("bob"));
+    let mut chain = Chain::new();
+    for account in &[token, alice, bob] {
+        chain.create_account(*account);
+    }
+    chain.set_code(token, &wasm).unwrap();
+
+    let max_supply: Asset = "1000.0000 TKN".parse().unwrap();
+    chain
+        .push_action(
+            token,
+            ActionName::new(n!("create")),
+            &[active(token)],
+            &(alice, max_supply),
+        )
+        .unwrap();
+
+    let quantity: Asset = "100.0000 TKN".parse().unwrap();
+    chain
+        .push_action(
+            token,
+            ActionName::new(n!("issue")),
+            &[active(alice)],
+            &(alice, quantity, String::from("issue")),
+        )
+        .unwrap();
+    assert_eq!(balance(&chain, alice), quantity);
+
+    let quantity: Asset = "25.0000 TKN".parse().unwrap();
+    let traces = chain
+        .push_action(
+            token,
+            ActionName::new(n!("transfer")),
+            &[active(alice)],
+            &(alice, bob, quantity, String::from("hi")),
+        )
+        .unwrap();
+    let receivers: Vec<_> = traces.iter().map(|t| t.receiver).collect();
+    assert_eq!(receivers, vec![token, alice, bob]);
+    assert_eq!(balance(&chain, bob), quantity);
+
+    let err = chain
+        .push_action(
+            token,
+            ActionName::new(n!("transfer")),
+            &[active(bob)],
+            &(alice, bob, quantity, String::from("stolen")),
+        )
+        .unwrap_err();
+    match err {
+        Error::Abort(msg) => assert_eq!(msg, "missing authority of alice"),
+        err => panic!("unexpected error: {}", err),
+    }
+
+    let zero: Asset = "0.0000 TKN".parse().unwrap();
+    let err = chain
+        .push_action(
+            token,
+            ActionName::new(n!("transfer")),
+            &[active(alice)],
+            &(alice, bob, zero, String::from("nothing")),
+        )
+        .unwrap_err();
+    match err {
+        Error::Abort(msg) => assert_eq!(
+            msg,
+            "assertion failure with message: must transfer positive quantity"
+        ),
+        err => panic!("unexpected error: {}", err),
+    }
+}
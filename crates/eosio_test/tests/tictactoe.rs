@@ -0,0 +1,124 @@
+//! Runs the `tictactoe` example contract end to end.
+mod common;
+
+use eosio::{
+    n, AccountName, ActionName, NumBytes, PermissionLevel, Read, Table,
+    TableName, Write,
+};
+use eosio_test::{Chain, Error};
+
+const HOST: u8 = 1;
+const CHALLENGER: u8 = 2;
+
+#[derive(Read, Write, NumBytes, Debug)]
+struct Game {
+    host: AccountName,
+    challenger: AccountName,
+    turn: u8,
+    winner: u8,
+    board: [u8; 9],
+}
+
+impl Table for Game {
+    type Row = Self;
+
+    const NAME: TableName = TableName::new(n!("game"));
+
+    fn primary_key(row: &Self::Row) -> u64 {
+        row.challenger.as_u64()
+    }
+}
+
+fn active(actor: AccountName) -> PermissionLevel {
+    PermissionLevel {
+        actor,
+        permission: n!("active").into(),
+    }
+}
+
+fn game(chain: &Chain, host: AccountName) -> Game {
+    chain
+        .rows::<Game, _, _>(AccountName::new(n!("tictactoe")), host)
+        .unwrap()
+        .remove(0)
+}
+
+#[test]
+fn play_until_host_wins() {
+    let wasm = match common::contract_wasm("tictactoe") {
+        Some(wasm) => wasm,
+        None => return,
+    };
+
+    let contract = AccountName::new(n!("tictactoe"));
+    let alice = AccountName::new(n!("alice"));
+    let bob = AccountName::new(n!("bob"));
+    let mut chain = Chain::new();
+    for account in &[contract, alice, bob] {
+        chain.create_account(*account);
+    }
+    chain.set_code(contract, &wasm).unwrap();
+
+    chain
+        .push_action(
+            contract,
+            ActionName::new(n!("create")),
+            &[active(alice)],
+            &(alice, bob),
+        )
+        .unwrap();
+    assert_eq!(game(&chain, alice).turn, HOST);
+
+    let moves = [
+        (HOST, 0_u16, 0_u16),
+        (CHALLENGER, 1, 0),
+        (HOST, 0, 1),
+        (CHALLENGER, 1, 1),
+        (HOST, 0, 2),
+    ];
+    for (by, row, col) in &moves {
+        let player = if *by == HOST { alice } else { bob };
+        chain
+            .push_action(
+                contract,
+                ActionName::new(n!("makemove")),
+                &[active(player)],
+                &(alice, bob, *by, *row, *col),
+            )
+            .unwrap();
+    }
+    let state = game(&chain, alice);
+    assert_eq!(state.winner, HOST);
+    assert_eq!(state.board, [1, 1, 1, 2, 2, 0, 0, 0, 0]);
+
+    let err = chain
+        .push_action(
+            contract,
+            ActionName::new(n!("makemove")),
+            &[active(bob)],
+            &(alice, bob, CHALLENGER, 2_u16, 2_u16),
+        )
+        .unwrap_err();
+    match err {
+        Error::Abort(msg) => {
+            assert_eq!(
+                msg,
+                "assertion failure with message: the game has ended!"
+            )
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+
+    chain
+        .push_action(
+            contract,
+            ActionName::new(n!("close")),
+            &[active(alice)],
+            &(alice, bob),
+        )
+        .unwrap();
+    assert!(chain
+        .rows::<Game, _, _>(contract, alice)
+        .unwrap()
+        .is_empty());
+}
@@ -3,7 +3,7 @@ use eosio_cdt::*;
 
 eosio::abi!(add, update, erase, like, likezip);
 
-#[eosio::table("address")]
+#[eosio::table("address", scope = AccountName)]
 struct Address {
     #[eosio(primary_key)]
     account: AccountName,
@@ -107,7 +107,7 @@ fn like(account: AccountName) {
 fn likezip(zip: u32) {
     let code = current_receiver();
     let table = Address::by_zip(code, code);
-    for cursor in table.lower_bound(zip).into_iter() {
+    for cursor in table.lower_bound(zip).into_iter().flatten() {
         let mut addr = cursor.get().expect("read");
         if addr.zip != zip {
             break;
@@ -116,3 +116,102 @@ fn likezip(zip: u32) {
         cursor.modify(Payer::Same, addr).expect("write");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eosio_cdt::mock::MockHost;
+
+    const CODE: u64 = n!("addressbook");
+
+    fn auth(actor: u64) -> Vec<PermissionLevel> {
+        vec![PermissionLevel {
+            actor: AccountName::new(actor),
+            permission: PermissionName::new(n!("active")),
+        }]
+    }
+
+    fn add(host: &MockHost, account: u64, zip: u32) -> Result<(), String> {
+        let action = Add {
+            account: AccountName::new(account),
+            first_name: "First".into(),
+            last_name: "Last".into(),
+            street: "1 Main St".into(),
+            city: "City".into(),
+            state: "State".into(),
+            zip,
+        };
+        host.push_action(CODE, &auth(account), action)
+    }
+
+    fn addresses(host: &MockHost) -> Vec<Address> {
+        host.rows::<Address, _, _>(CODE, CODE).unwrap()
+    }
+
+    #[test]
+    fn add_and_erase() {
+        let host = MockHost::new();
+        add(&host, n!("bob"), 10000).unwrap();
+        add(&host, n!("alice"), 20000).unwrap();
+        let rows = addresses(&host);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].account, AccountName::new(n!("alice")));
+        assert_eq!(rows[1].zip, 10000);
+
+        let err = add(&host, n!("alice"), 30000).unwrap_err();
+        assert_eq!(err, "Address for account already exists");
+
+        let erase = Erase {
+            account: AccountName::new(n!("alice")),
+        };
+        let err = host
+            .push_action(CODE, &auth(n!("bob")), erase.clone())
+            .unwrap_err();
+        assert_eq!(err, "missing authority of alice");
+        assert_eq!(addresses(&host).len(), 2);
+
+        host.push_action(CODE, &auth(n!("alice")), erase).unwrap();
+        let rows = addresses(&host);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].account, AccountName::new(n!("bob")));
+    }
+
+    #[test]
+    fn like_by_zip() {
+        let host = MockHost::new();
+        add(&host, n!("alice"), 10000).unwrap();
+        add(&host, n!("bob"), 20000).unwrap();
+        add(&host, n!("carol"), 10000).unwrap();
+        add(&host, n!("dave"), 30000).unwrap();
+
+        host.push_action(CODE, &[], Likezip { zip: 10000 }).unwrap();
+        let liked: Vec<_> = addresses(&host)
+            .into_iter()
+            .map(|row| (row.account.as_u64(), row.liked))
+            .collect();
+        assert_eq!(
+            liked,
+            vec![
+                (n!("alice"), 1),
+                (n!("bob"), 0),
+                (n!("carol"), 1),
+                (n!("dave"), 0)
+            ]
+        );
+
+        let update = Update {
+            account: AccountName::new(n!("carol")),
+            first_name: "First".into(),
+            last_name: "Last".into(),
+            street: "2 Main St".into(),
+            city: "City".into(),
+            state: "State".into(),
+            zip: 20000,
+        };
+        host.push_action(CODE, &auth(n!("carol")), update).unwrap();
+        host.push_action(CODE, &[], Likezip { zip: 20000 }).unwrap();
+        let liked: Vec<_> =
+            addresses(&host).into_iter().map(|row| row.liked).collect();
+        assert_eq!(liked, vec![1, 1, 2, 0]);
+    }
+}
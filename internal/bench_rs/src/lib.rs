@@ -1,7 +1,7 @@
 #![no_std]
-use core::marker::PhantomData;
 
-pub fn noop(_: PhantomData<()>) {}
+#[eosio::action]
+fn noop() {}
 
 eosio::abi! {
     noop